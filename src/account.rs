@@ -1,5 +1,12 @@
 use std::collections::HashMap;
 use std::collections::hash_map::Entry;
+use std::fmt;
+use serde::{Deserialize, Serialize, Serializer};
+
+use crate::futures::FuturesAccount;
+use crate::margin::{MarginAccount, SHORT_SELL};
+use crate::protect::Protections;
+use crate::risk::{RiskDecision, RiskManager};
 
 
 
@@ -25,26 +32,129 @@ pub struct Account {
 
     /// 交割单
     pub transactions: Vec<Transaction>,
-    /// 订单记录
-    pub orders: Vec<Order>,
-    
-    // 佣金费率 
-    // commission_ratio:f64,
-    // 税率
-    // tax_ratio:f64,
+    /// 订单记录，包括未成交的委托
+    pub orders: Vec<OrderRecord>,
+
+    /// 手续费设置
+    pub fee: FeeModel,
+
+    /// 风控，None 表示不检查
+    pub risk: Option<RiskManager>,
+    /// 持仓上的止损止盈单
+    pub protect: Protections,
+    /// 信用账户，None 表示普通账户
+    pub margin: Option<MarginAccount>,
+    /// 期货持仓，None 表示不交易期货
+    pub futures: Option<FuturesAccount>,
+}
+
+/// 手续费模型，默认不收费
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FeeModel {
+    /// 佣金费率，买卖双向收取
+    pub commission_ratio: f64,
+    /// 单笔最低佣金
+    pub min_commission: f64,
+    /// 印花税率，仅卖出收取
+    pub tax_ratio: f64,
+}
+
+impl FeeModel {
+    /// 计算一笔成交的费用
+    pub fn calc(&self, turnover: f64, order_type: char) -> f64 {
+        let mut commission = turnover * self.commission_ratio;
+        if commission > 0.0 || self.min_commission > 0.0 {
+            commission = commission.max(self.min_commission);
+        }
+        let tax = if order_type == 'S' { turnover * self.tax_ratio } else { 0.0 };
+        commission + tax
+    }
 }
 
 
 impl Account {
-    /// 买入操作
+    /// 买入操作，委托记入订单记录；设置了风控时先经风控检查，可能被拒绝或缩量
     pub fn buy(&mut self, order: &Order) -> bool {
+        let decision = match self.risk.take() {
+            Some(mut risk) => {
+                let decision = risk.check_buy(order, self);
+                self.risk = Some(risk);
+                decision
+            }
+            None => RiskDecision::Accept,
+        };
+        let order = match decision {
+            RiskDecision::Accept => order.clone(),
+            RiskDecision::Resize(volume) => Order { volume, ..order.clone() },
+            RiskDecision::Reject => {
+                self.orders.push(OrderRecord { order: order.clone(), filled: false });
+                return false;
+            }
+        };
+        let held = self.hold.get(&order.code).map(|p| p.volume).unwrap_or(0);
+        let filled = match self.margin.take() {
+            Some(mut margin) => {
+                let filled = margin.buy(self, &order);
+                self.margin = Some(margin);
+                filled
+            }
+            None => self.fill_buy(&order),
+        };
+        if filled && let Some(risk) = &mut self.risk {
+            risk.on_buy_filled(&order.code, held);
+        }
+        self.orders.push(OrderRecord { order, filled });
+        filled
+    }
+
+    /// 卖出操作，委托记入订单记录；信用账户超过可卖数量的部分融券卖出，融券部分先经风控检查
+    pub fn sell(&mut self, order: &Order) -> bool {
+        let available = self.hold.get(&order.code).map(|p| p.available_vol).unwrap_or(0);
+        let short = if self.margin.is_some() { order.volume - available } else { 0 };
+        let decision = match self.risk.take() {
+            Some(mut risk) if short > 0 => {
+                let decision = risk.check_short(&Order { volume: short, order_type: SHORT_SELL, ..order.clone() }, self);
+                self.risk = Some(risk);
+                decision
+            }
+            risk => {
+                self.risk = risk;
+                RiskDecision::Accept
+            }
+        };
+        // 融券被拒绝时只卖出持仓
+        let order = match decision {
+            RiskDecision::Accept => order.clone(),
+            RiskDecision::Resize(volume) => Order { volume: order.volume - short + volume, ..order.clone() },
+            RiskDecision::Reject if order.volume > short => Order { volume: order.volume - short, ..order.clone() },
+            RiskDecision::Reject => {
+                self.orders.push(OrderRecord { order: order.clone(), filled: false });
+                return false;
+            }
+        };
+        let filled = match self.margin.take() {
+            Some(mut margin) => {
+                let filled = margin.sell(self, &order);
+                self.margin = Some(margin);
+                filled
+            }
+            None => self.fill_sell(&order),
+        };
+        self.orders.push(OrderRecord { order, filled });
+        filled
+    }
+
+    /// 买入成交
+    pub(crate) fn fill_buy(&mut self, order: &Order) -> bool {
         let turnover = order.price * order.volume as f64;
+        let fee = self.fee.calc(turnover, 'B');
         // 资金检查
-        if self.available_balance < turnover {
+        if self.available_balance < turnover + fee {
             return false;
         }
         // 更新资产
-        self.available_balance = self.available_balance - turnover;
+        self.available_balance = self.available_balance - turnover - fee;
         
         // let position:&mut Position = self.get_position(&order.code);
         // 计算新成本价（考虑浮点精度）
@@ -58,8 +168,8 @@ impl Account {
         // 先处理position，提取需要的数据
         let (total_volume, cost_price) = {
             let position = self.get_position(order.code.clone());
-            // 买入成交后，花费总资金
-            let total_cost = position.volume as f64 * position.cost_price + turnover;
+            // 买入成交后，花费总资金 (含手续费)
+            let total_cost = position.volume as f64 * position.cost_price + turnover + fee;
             // 更新持仓，买入成交后，持仓数量
             position.volume = position.volume + order.volume;                        
             // 计算新成本价（考虑浮点精度）
@@ -69,19 +179,21 @@ impl Account {
 
         // 记录交易
         self.transactions.push(Transaction {
+            code: order.code.clone(),
             time: order.time,
             price: order.price,
             volume: order.volume,
             order_type: order.order_type.clone(),
             remain_vol: total_volume,
             remain_cost: cost_price,
+            fee,
         });
 
         true
     }
 
-    /// 卖出操作
-    pub fn sell(&mut self, order: &Order) -> bool {
+    /// 卖出成交
+    pub(crate) fn fill_sell(&mut self, order: &Order) -> bool {
         let position = match self.hold.get_mut(&order.code) {
             Some(p) => p,
             None => return false, // 无持仓
@@ -92,16 +204,18 @@ impl Account {
             return false;
         }
 
-        // 计算成交金额
+        // 计算成交金额，扣除手续费后入账
         let turnover = order.price * order.volume as f64;
+        let fee = self.fee.calc(turnover, 'S');
+        let proceeds = turnover - fee;
 
         // 更新资产
-        self.available_balance = self.available_balance + turnover;
+        self.available_balance = self.available_balance + proceeds;
 
         // 计算新成本价（当完全卖出时重置为0）
         let total_volume = position.volume - order.volume;
         position.cost_price = if total_volume != 0 {
-            (position.volume as f64 * position.cost_price - turnover) / total_volume as f64
+            (position.volume as f64 * position.cost_price - proceeds) / total_volume as f64
         } else {
             0.0
         };
@@ -112,12 +226,14 @@ impl Account {
 
         // 记录交易
         self.transactions.push(Transaction {
+            code: order.code.clone(),
             time: order.time,
             price: order.price,
             volume: -order.volume, // 用负数表示卖出
             order_type: order.order_type.clone(),
             remain_vol: total_volume,
             remain_cost: position.cost_price,
+            fee,
         });
 
         true
@@ -125,15 +241,73 @@ impl Account {
 
     /// 行情变化时，更新持仓市值 todo 
     pub fn on_price_change(&mut self, code: &str, price: f64) {
-        if let Some(position) = self.hold.get_mut(&StockCode::from(code)) {
+        let code = StockCode::from(code);
+        let mut changed = false;
+        if let Some(position) = self.hold.get_mut(&code) {
             let after_value = price * position.volume  as f64;
             self.portfolio_value = self.portfolio_value + after_value - position.market_value;
             position.current_price = price;
             position.market_value = after_value;
-            self.balance = self.available_balance + self.portfolio_value + self.freeze_balance;
+            changed = true;
+        }
+        if let Some(short) = self.margin.as_mut().and_then(|m| m.shorts.get_mut(&code)) {
+            short.price = price;
+            changed = true;
+        }
+        if let Some(futures) = &mut self.futures {
+            changed |= futures.on_price_change(code.as_str(), price);
+        }
+        if changed {
+            self.balance = self.net_assets();
         }
     }
+
+    /// 净资产 = 可用金额 + 总市值 + 冻结金额 + 信用净值 + 期货权益
+    pub fn net_assets(&self) -> f64 {
+        self.available_balance + self.portfolio_value + self.freeze_balance + self.credit_value() + self.futures_value()
+    }
+
+    /// 信用净值：融券卖出所得减去融资融券负债，普通账户为 0
+    pub fn credit_value(&self) -> f64 {
+        self.margin.as_ref().map(|m| m.net_value()).unwrap_or(0.0)
+    }
+
+    /// 期货权益：占用保证金加浮动盈亏，不交易期货时为 0
+    pub fn futures_value(&self) -> f64 {
+        self.futures.as_ref().map(|f| f.value()).unwrap_or(0.0)
+    }
+
+    /// 持仓市值：`quotes` 给出的股票按 (代码, 价格) 计，其余按 `Position::mark_price`
+    pub fn market_value_with(&self, quotes: &[(&str, f64)]) -> f64 {
+        self.hold
+            .values()
+            .map(|p| {
+                let price = quotes
+                    .iter()
+                    .find(|(code, _)| *code == p.code.as_str())
+                    .map(|&(_, price)| price)
+                    .unwrap_or_else(|| p.mark_price());
+                p.volume as f64 * price
+            })
+            .sum()
+    }
+
+    /// 按 `market_value_with` 估值的总资产，计入冻结资金、信用净值和期货权益
+    pub fn equity_with(&self, quotes: &[(&str, f64)]) -> f64 {
+        self.available_balance + self.freeze_balance + self.market_value_with(quotes) + self.credit_value() + self.futures_value()
+    }
     
+    /// 按持仓现价计算总资产，交给风控更新最高点和当日开盘资产
+    pub fn update_risk(&mut self, time: i64) {
+        if self.risk.is_none() {
+            return;
+        }
+        let equity = self.equity_with(&[]);
+        if let Some(risk) = &mut self.risk {
+            risk.observe(time, equity);
+        }
+    }
+
     /// 撤单操作（示例实现）
     pub fn cancel_order(&mut self) -> Option<Transaction> {
         // 实际实现需要订单ID管理和状态追踪
@@ -156,7 +330,7 @@ impl Account {
 
 
 /// 持仓信息
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct Position {
     /// 股票代码
     pub code: StockCode,
@@ -176,22 +350,33 @@ pub struct Position {
     pub market_value: f64,
 }
 
+impl Position {
+    /// 估值价格：当前价格，尚未收到行情时取成本价
+    pub fn mark_price(&self) -> f64 {
+        if self.current_price > 0.0 { self.current_price } else { self.cost_price }
+    }
+}
+
 
 /// 交割单
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Transaction {
+    /// 股票代码
+    pub code: StockCode,
     /// 成交时间
     pub time: i64,
     /// 成交价格
     pub price: f64,
     /// 成交数量
     pub volume: i32,
-    /// 交易类型（买入/卖出） B S
+    /// 交易类型（买入/卖出） B S，信用账户另有融券卖出 Q、买券还券 H
     pub order_type: char,
     /// 成交后数量
     pub remain_vol: i32,
     /// 成交后成本价
     pub remain_cost: f64,
+    /// 手续费
+    pub fee: f64,
 }
 
 /// 委托
@@ -211,9 +396,38 @@ pub struct Order {
     pub order_type: char,
 }
 
+/// 委托记录
+#[derive(Debug, Clone)]
+pub struct OrderRecord {
+    pub order: Order,
+    /// 是否成交
+    pub filled: bool,
+}
+
 #[derive(Debug,Default,Eq,PartialEq,Ord,PartialOrd,Hash,Clone)]
 pub struct StockCode([u8; 8]);
 
+impl StockCode {
+    /// 去掉末尾补齐的 0 后的代码
+    pub fn as_str(&self) -> &str {
+        let len = self.0.iter().position(|&b| b == 0).unwrap_or(self.0.len());
+        std::str::from_utf8(&self.0[..len]).unwrap_or("")
+    }
+}
+
+impl fmt::Display for StockCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// 序列化为字符串
+impl Serialize for StockCode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
 impl From<&str> for StockCode {
     fn from(value: &str) -> Self {
         let mut bytes = [0u8; 8];
@@ -0,0 +1,142 @@
+use std::path::Path;
+
+use crate::data::load_klines;
+use crate::engine::BacktestResult;
+use crate::metrics::{mean_std, TRADING_DAYS_PER_YEAR};
+use crate::model::KLine;
+
+/// 基准价格序列
+#[derive(Debug, Clone)]
+pub struct Benchmark {
+    pub name: String,
+    /// (时间戳, 收盘价)，按时间升序
+    pub prices: Vec<(i64, f64)>,
+}
+
+impl Benchmark {
+    /// 买入持有同一标的
+    pub fn buy_and_hold(name: &str, bars: &[KLine]) -> Self {
+        Self {
+            name: name.to_string(),
+            prices: bars.iter().map(|b| (b.time, b.close)).collect(),
+        }
+    }
+
+    /// 从 K 线格式的 CSV 读取指数，取收盘价
+    pub fn from_csv<P: AsRef<Path>>(name: &str, path: P) -> Result<Self, csv::Error> {
+        Ok(Self::buy_and_hold(name, &load_klines(path)?))
+    }
+
+    /// 对齐到给定时间点，缺失时取之前最近的价格；之前没有价格时为 None
+    pub fn align(&self, times: &[i64]) -> Vec<Option<f64>> {
+        let mut i = 0;
+        let mut last = None;
+        times
+            .iter()
+            .map(|&t| {
+                while i < self.prices.len() && self.prices[i].0 <= t {
+                    last = Some(self.prices[i].1);
+                    i += 1;
+                }
+                last
+            })
+            .collect()
+    }
+}
+
+/// 相对基准的指标，收益类指标均为年化
+#[derive(Debug, Clone, Default)]
+pub struct RelativeMetrics {
+    pub benchmark_name: String,
+    /// 基准总收益率
+    pub benchmark_return: f64,
+    /// 策略总收益率减基准总收益率
+    pub excess_return: f64,
+    pub alpha: f64,
+    pub beta: f64,
+    /// 跟踪误差
+    pub tracking_error: f64,
+    /// 信息比率
+    pub information_ratio: f64,
+    /// 在共同起点以策略当时的总资产买入基准的资金曲线
+    pub benchmark_equity: Vec<(i64, f64)>,
+    /// 累计超额收益曲线 (策略累计收益 - 基准累计收益)
+    pub excess_curve: Vec<(i64, f64)>,
+}
+
+/// 把回测结果与基准比较，只使用基准有价格的区间
+///
+/// 两条曲线都从共同的第一个时间点起算：基准覆盖回测开始时策略以初始资金为起点，
+/// 基准晚于回测开始时策略以该时间点的总资产为起点。
+pub fn compare(result: &BacktestResult, benchmark: &Benchmark) -> RelativeMetrics {
+    let times: Vec<i64> = result.equity.iter().map(|e| e.0).collect();
+    let aligned = benchmark.align(&times);
+    let points: Vec<(i64, f64, f64)> = result
+        .equity
+        .iter()
+        .zip(aligned)
+        .filter_map(|(&(t, eq), bm)| bm.map(|p| (t, eq, p)))
+        .collect();
+    let Some(&(start, start_eq, base_price)) = points.first() else {
+        return RelativeMetrics {
+            benchmark_name: benchmark.name.clone(),
+            ..Default::default()
+        };
+    };
+    let init = if times.first() == Some(&start) { result.init_cash } else { start_eq };
+
+    let benchmark_equity: Vec<(i64, f64)> = points.iter().map(|&(t, _, p)| (t, init * p / base_price)).collect();
+    let excess_curve: Vec<(i64, f64)> = points
+        .iter()
+        .map(|&(t, eq, p)| (t, (eq / init - 1.0) - (p / base_price - 1.0)))
+        .collect();
+
+    // 逐期收益
+    let mut strat_ret = Vec::with_capacity(points.len());
+    let mut bench_ret = Vec::with_capacity(points.len());
+    let mut prev = (init, base_price);
+    for &(_, eq, p) in &points {
+        strat_ret.push(if prev.0 != 0.0 { eq / prev.0 - 1.0 } else { 0.0 });
+        bench_ret.push(p / prev.1 - 1.0);
+        prev = (eq, p);
+    }
+
+    let (sm, _) = mean_std(&strat_ret);
+    let (bm, bs) = mean_std(&bench_ret);
+    let n = strat_ret.len() as f64;
+    let cov = if n > 1.0 {
+        strat_ret.iter().zip(&bench_ret).map(|(s, b)| (s - sm) * (b - bm)).sum::<f64>() / (n - 1.0)
+    } else {
+        0.0
+    };
+    let beta = if bs > 0.0 { cov / (bs * bs) } else { 0.0 };
+    let alpha = (sm - beta * bm) * TRADING_DAYS_PER_YEAR;
+
+    let active: Vec<f64> = strat_ret.iter().zip(&bench_ret).map(|(s, b)| s - b).collect();
+    let (am, asd) = mean_std(&active);
+    let tracking_error = asd * TRADING_DAYS_PER_YEAR.sqrt();
+    let information_ratio = if asd > 0.0 { am / asd * TRADING_DAYS_PER_YEAR.sqrt() } else { 0.0 };
+
+    let last = points[points.len() - 1];
+    let benchmark_return = last.2 / base_price - 1.0;
+    RelativeMetrics {
+        benchmark_name: benchmark.name.clone(),
+        benchmark_return,
+        excess_return: (last.1 / init - 1.0) - benchmark_return,
+        alpha,
+        beta,
+        tracking_error,
+        information_ratio,
+        benchmark_equity,
+        excess_curve,
+    }
+}
+
+impl RelativeMetrics {
+    pub fn print_report(&self) {
+        println!("\n基准：{}", self.benchmark_name);
+        println!("基准收益：{:.2}%  超额收益：{:.2}%", self.benchmark_return * 100.0, self.excess_return * 100.0);
+        println!("Alpha：{:.4}  Beta：{:.4}", self.alpha, self.beta);
+        println!("跟踪误差：{:.4}  信息比率：{:.4}", self.tracking_error, self.information_ratio);
+    }
+}
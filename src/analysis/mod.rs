@@ -0,0 +1,4 @@
+pub mod benchmark;
+pub mod monte_carlo;
+pub mod pairs;
+pub mod trades;
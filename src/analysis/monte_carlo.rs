@@ -0,0 +1,193 @@
+use crate::analysis::trades::RoundTrip;
+use crate::engine::BacktestResult;
+use crate::metrics::max_drawdown;
+use crate::model::KLine;
+use crate::optimize::search::Rng;
+
+/// 交易序列的重采样方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resample {
+    /// 打乱顺序，交易集合不变
+    Shuffle,
+    /// 有放回抽样
+    Bootstrap,
+}
+
+/// 一组模拟结果的分布
+#[derive(Debug, Clone, Default)]
+pub struct Distribution {
+    /// 升序排列的样本
+    pub values: Vec<f64>,
+}
+
+impl Distribution {
+    pub fn new(mut values: Vec<f64>) -> Self {
+        values.sort_by(|a, b| a.total_cmp(b));
+        Self { values }
+    }
+
+    /// 百分位 (0~100)，线性插值
+    pub fn percentile(&self, p: f64) -> f64 {
+        if self.values.is_empty() {
+            return f64::NAN;
+        }
+        let rank = (p / 100.0).clamp(0.0, 1.0) * (self.values.len() - 1) as f64;
+        let lo = rank.floor() as usize;
+        let hi = rank.ceil() as usize;
+        self.values[lo] + (self.values[hi] - self.values[lo]) * (rank - lo as f64)
+    }
+
+    pub fn mean(&self) -> f64 {
+        if self.values.is_empty() {
+            return f64::NAN;
+        }
+        self.values.iter().sum::<f64>() / self.values.len() as f64
+    }
+
+    /// 小于 threshold 的样本占比
+    pub fn prob_below(&self, threshold: f64) -> f64 {
+        if self.values.is_empty() {
+            return 0.0;
+        }
+        self.values.iter().filter(|&&v| v < threshold).count() as f64 / self.values.len() as f64
+    }
+}
+
+/// 蒙特卡洛模拟结果
+#[derive(Debug, Clone)]
+pub struct MonteCarloReport {
+    pub init_cash: f64,
+    /// 原始回测的期末资产
+    pub actual_final: f64,
+    /// 原始回测的最大回撤
+    pub actual_drawdown: f64,
+    /// 期末资产分布
+    pub final_equity: Distribution,
+    /// 最大回撤分布
+    pub max_drawdown: Distribution,
+}
+
+impl MonteCarloReport {
+    /// 期末资产不高于原始回测的模拟占比，越接近 1 说明原结果越依赖运气
+    pub fn actual_rank(&self) -> f64 {
+        let n = self.final_equity.values.len();
+        if n == 0 {
+            return f64::NAN;
+        }
+        self.final_equity.values.iter().filter(|&&v| v <= self.actual_final).count() as f64 / n as f64
+    }
+
+    pub fn print_report(&self) {
+        println!("\n蒙特卡洛模拟：{} 次", self.final_equity.values.len());
+        println!("原始结果：期末资产 {:.2}  最大回撤 {:.2}%", self.actual_final, self.actual_drawdown * 100.0);
+        println!("{:>6} {:>16} {:>10}", "分位", "期末资产", "最大回撤");
+        for p in [5.0, 25.0, 50.0, 75.0, 95.0] {
+            println!(
+                "{:>5}% {:>16.2} {:>9.2}%",
+                p,
+                self.final_equity.percentile(p),
+                self.max_drawdown.percentile(p) * 100.0
+            );
+        }
+        println!("亏损概率：{:.2}%", self.final_equity.prob_below(self.init_cash) * 100.0);
+        println!("原始结果所处分位：{:.2}%", self.actual_rank() * 100.0);
+    }
+}
+
+/// 对买卖回合盈亏重采样，按交易顺序累计得到资金路径
+pub fn resample_trades(
+    trips: &[RoundTrip],
+    init_cash: f64,
+    method: Resample,
+    simulations: usize,
+    seed: u64,
+) -> MonteCarloReport {
+    let pnls: Vec<f64> = trips.iter().map(|t| t.pnl).collect();
+    let path = |pnls: &[f64]| {
+        let mut balance = init_cash;
+        let mut curve = Vec::with_capacity(pnls.len() + 1);
+        curve.push(balance);
+        for p in pnls {
+            balance += p;
+            curve.push(balance);
+        }
+        (balance, max_drawdown(&curve))
+    };
+    let (actual_final, actual_drawdown) = path(&pnls);
+
+    let mut rng = Rng::new(seed);
+    let mut finals = Vec::with_capacity(simulations);
+    let mut drawdowns = Vec::with_capacity(simulations);
+    let mut sample = pnls.clone();
+    for _ in 0..simulations {
+        match method {
+            Resample::Shuffle => {
+                // Fisher-Yates
+                for i in (1..sample.len()).rev() {
+                    sample.swap(i, rng.below(i + 1));
+                }
+            }
+            Resample::Bootstrap => {
+                for s in sample.iter_mut() {
+                    *s = pnls[rng.below(pnls.len())];
+                }
+            }
+        }
+        let (f, dd) = path(&sample);
+        finals.push(f);
+        drawdowns.push(dd);
+    }
+
+    MonteCarloReport {
+        init_cash,
+        actual_final,
+        actual_drawdown,
+        final_equity: Distribution::new(finals),
+        max_drawdown: Distribution::new(drawdowns),
+    }
+}
+
+/// 给每根 K 线的价格乘上 (1 + N(0, sigma)) 的扰动，保持高低价包住开收盘
+pub fn perturb_bars(bars: &[KLine], sigma: f64, rng: &mut Rng) -> Vec<KLine> {
+    bars.iter()
+        .map(|bar| {
+            let mut noisy = |p: f64| (p * (1.0 + sigma * rng.next_normal())).max(0.01);
+            let open = noisy(bar.open);
+            let close = noisy(bar.close);
+            let high = noisy(bar.high).max(open).max(close);
+            let low = noisy(bar.low).min(open).min(close);
+            KLine { open, high, low, close, ..bar.clone() }
+        })
+        .collect()
+}
+
+/// 对 K 线价格加扰动后重复回测，`run` 用给定 K 线执行一次回测
+pub fn perturb_prices<F>(bars: &[KLine], sigma: f64, simulations: usize, seed: u64, run: F) -> MonteCarloReport
+where
+    F: Fn(&[KLine]) -> BacktestResult,
+{
+    let summary = |r: &BacktestResult| {
+        let balances: Vec<f64> = r.equity.iter().map(|e| e.1).collect();
+        (r.final_balance(), max_drawdown(&balances))
+    };
+    let actual = run(bars);
+    let (actual_final, actual_drawdown) = summary(&actual);
+
+    let mut rng = Rng::new(seed);
+    let mut finals = Vec::with_capacity(simulations);
+    let mut drawdowns = Vec::with_capacity(simulations);
+    for _ in 0..simulations {
+        let noisy = perturb_bars(bars, sigma, &mut rng);
+        let (f, dd) = summary(&run(&noisy));
+        finals.push(f);
+        drawdowns.push(dd);
+    }
+
+    MonteCarloReport {
+        init_cash: actual.init_cash,
+        actual_final,
+        actual_drawdown,
+        final_equity: Distribution::new(finals),
+        max_drawdown: Distribution::new(drawdowns),
+    }
+}
@@ -0,0 +1,98 @@
+use crate::metrics::mean_std;
+
+/// Engle-Granger 两变量协整检验 5% 临界值（含常数项）
+pub const EG_CRITICAL_5PCT: f64 = -3.34;
+
+/// 最小二乘 y = alpha + beta * x，返回 (alpha, beta)
+pub fn ols(x: &[f64], y: &[f64]) -> (f64, f64) {
+    let n = x.len().min(y.len());
+    if n == 0 {
+        return (0.0, 0.0);
+    }
+    let (mx, my) = (x[..n].iter().sum::<f64>() / n as f64, y[..n].iter().sum::<f64>() / n as f64);
+    let (mut cov, mut var) = (0.0, 0.0);
+    for i in 0..n {
+        cov += (x[i] - mx) * (y[i] - my);
+        var += (x[i] - mx) * (x[i] - mx);
+    }
+    let beta = if var > 0.0 { cov / var } else { 0.0 };
+    (my - beta * mx, beta)
+}
+
+/// 价差 a - alpha - beta * b 最后一个值的 z 分数，标准差为 0 时返回 None
+pub fn zscore(spread: &[f64]) -> Option<f64> {
+    let (mean, std) = mean_std(spread);
+    let last = *spread.last()?;
+    (std > 0.0).then(|| (last - mean) / std)
+}
+
+/// 协整检验结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cointegration {
+    /// 对数价格回归 ln a = alpha + beta * ln b
+    pub alpha: f64,
+    pub beta: f64,
+    /// 残差 ADF 统计量（不含滞后项），越小越平稳
+    pub adf_stat: f64,
+    /// 残差均值回归的半衰期（根数），不回归时为无穷大
+    pub half_life: f64,
+}
+
+impl Cointegration {
+    /// 5% 显著性下是否协整
+    pub fn is_cointegrated(&self) -> bool {
+        self.adf_stat < EG_CRITICAL_5PCT
+    }
+
+    pub fn print_report(&self) {
+        println!("\n协整检验：");
+        println!("对冲比例 beta：{:.4}  alpha：{:.4}", self.beta, self.alpha);
+        println!("ADF 统计量：{:.3}（5% 临界值 {}）", self.adf_stat, EG_CRITICAL_5PCT);
+        println!("半衰期：{:.1} 根", self.half_life);
+        println!("结论：{}", if self.is_cointegrated() { "协整" } else { "不协整" });
+    }
+}
+
+/// Engle-Granger 检验：对数价格回归后，检验残差是否平稳。a、b 为对齐后的收盘价
+pub fn cointegration(a: &[f64], b: &[f64]) -> Option<Cointegration> {
+    let n = a.len().min(b.len());
+    if n < 10 || a[..n].iter().chain(&b[..n]).any(|&p| p <= 0.0) {
+        return None;
+    }
+    let la: Vec<f64> = a[..n].iter().map(|p| p.ln()).collect();
+    let lb: Vec<f64> = b[..n].iter().map(|p| p.ln()).collect();
+    let (alpha, beta) = ols(&lb, &la);
+    let resid: Vec<f64> = (0..n).map(|i| la[i] - alpha - beta * lb[i]).collect();
+
+    // Δe_t = gamma * e_{t-1} + u_t，不含常数项
+    let (mut sxy, mut sxx) = (0.0, 0.0);
+    for t in 1..n {
+        sxy += resid[t - 1] * (resid[t] - resid[t - 1]);
+        sxx += resid[t - 1] * resid[t - 1];
+    }
+    if sxx == 0.0 {
+        return None;
+    }
+    let gamma = sxy / sxx;
+    let sse: f64 = (1..n)
+        .map(|t| {
+            let u = resid[t] - resid[t - 1] - gamma * resid[t - 1];
+            u * u
+        })
+        .sum();
+    let se = (sse / (n - 2) as f64 / sxx).sqrt();
+    let adf_stat = if se > 0.0 { gamma / se } else { f64::NEG_INFINITY };
+    let half_life = if gamma < 0.0 && gamma > -1.0 {
+        -std::f64::consts::LN_2 / (1.0 + gamma).ln()
+    } else if gamma <= -1.0 {
+        0.0
+    } else {
+        f64::INFINITY
+    };
+    Some(Cointegration {
+        alpha,
+        beta,
+        adf_stat,
+        half_life,
+    })
+}
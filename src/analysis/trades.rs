@@ -0,0 +1,89 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::account::{StockCode, Transaction};
+use crate::margin::{BUY_TO_COVER, SHORT_SELL};
+
+/// 未平仓的批次 (时间, 价格, 剩余数量)
+type Lots = VecDeque<(i64, f64, i32)>;
+
+/// 一笔完整的买卖 (按先进先出配对)
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoundTrip {
+    pub code: StockCode,
+    /// 配对到的最早一笔开仓时间
+    pub entry_time: i64,
+    /// 平仓时间
+    pub exit_time: i64,
+    /// 数量，融券回合为负数
+    pub volume: i32,
+    /// 开仓均价：买入含手续费，融券卖出扣除手续费
+    pub entry_price: f64,
+    /// 平仓价
+    pub exit_price: f64,
+    /// 盈亏金额 (扣除买卖手续费)
+    pub pnl: f64,
+}
+
+impl RoundTrip {
+    /// 收益率，融券回合按价格下跌计算
+    pub fn return_pct(&self) -> f64 {
+        if self.entry_price <= 0.0 || self.exit_price <= 0.0 {
+            0.0
+        } else if self.volume < 0 {
+            self.entry_price / self.exit_price - 1.0
+        } else {
+            self.exit_price / self.entry_price - 1.0
+        }
+    }
+}
+
+/// 把交割单按股票代码分组、先进先出配对成买卖回合，每笔平仓生成一条记录，按平仓顺序排列
+///
+/// 买入与卖出配对成多头回合；融券卖出 (`SHORT_SELL`) 与买券还券 (`BUY_TO_COVER`) 单独配对成融券回合。
+pub fn round_trips(transactions: &[Transaction]) -> Vec<RoundTrip> {
+    // 每只股票的未平仓批次，多头和融券分开
+    let mut open: HashMap<(&StockCode, bool), Lots> = HashMap::new();
+    let mut trips = Vec::new();
+    for t in transactions {
+        let short = matches!(t.order_type, SHORT_SELL | BUY_TO_COVER);
+        let lots = open.entry((&t.code, short)).or_default();
+        let opening = if short { t.order_type == SHORT_SELL } else { t.volume > 0 };
+        if opening {
+            let volume = t.volume.abs();
+            let fee = if short { -t.fee } else { t.fee };
+            lots.push_back((t.time, t.price + fee / volume as f64, volume));
+            continue;
+        }
+        let mut remain = t.volume.abs();
+        let mut cost = 0.0;
+        let mut matched = 0;
+        let mut entry_time = t.time;
+        while remain > 0 {
+            let Some(lot) = lots.front_mut() else { break };
+            if matched == 0 {
+                entry_time = lot.0;
+            }
+            let vol = remain.min(lot.2);
+            cost += lot.1 * vol as f64;
+            matched += vol;
+            remain -= vol;
+            lot.2 -= vol;
+            if lot.2 == 0 {
+                lots.pop_front();
+            }
+        }
+        if matched > 0 {
+            let exit = t.price * matched as f64;
+            trips.push(RoundTrip {
+                code: t.code.clone(),
+                entry_time,
+                exit_time: t.time,
+                volume: if short { -matched } else { matched },
+                entry_price: cost / matched as f64,
+                exit_price: t.price,
+                pnl: if short { cost - exit - t.fee } else { exit - t.fee - cost },
+            });
+        }
+    }
+    trips
+}
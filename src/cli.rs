@@ -0,0 +1,410 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{Duration, TimeZone, Utc};
+use csv::Writer;
+
+use crate::analysis::benchmark::{compare, Benchmark, RelativeMetrics};
+use crate::analysis::monte_carlo::{resample_trades, Resample};
+use crate::analysis::trades::round_trips;
+use crate::config::{BacktestConfig, OUTPUT_FORMATS};
+use crate::data::load_klines;
+use crate::engine::{self, BacktestResult};
+use crate::export::{export_result, ExportFormat};
+use crate::margin::{MarginEventKind, SHORT_SELL};
+use crate::metrics::{Metric, Metrics};
+use crate::model::KLine;
+use crate::optimize::search::{genetic, random_search, GeneticConfig, RandomSearchConfig};
+use crate::optimize::walk_forward::{walk_forward, WalkForwardConfig};
+use crate::optimize::{grid_search, Evaluation, ParamRange, ParamSet, ParamSpace};
+use crate::report::write_html_report;
+use crate::strategy;
+
+const USAGE: &str = "用法：
+  以下子命令都可用 --config <toml|yaml> 读取配置，命令行参数覆盖配置中的同名项
+  backtest-cli run      --data <csv> --code <代码> [--strategy k] [--cash 1000000] [--param 名称=值]... [--out 目录] [--format csv,json,parquet,html]
+  backtest-cli optimize --data <csv> --code <代码> --range 名称=起:止:步长... [--metric total_return|annual_return|max_drawdown|sharpe]
+                        [--method grid|random|genetic] [--evals 200] [--seed 42] [--walk-forward 样本内:样本外[:anchored]] [--out 目录]
+  backtest-cli report   --data <csv> --code <代码> [--benchmark hold|<指数csv>] [--monte-carlo 1000] [--seed 42] [--out 目录]
+                        指定 --out 时生成 report.html";
+
+/// 解析后的命令行：子命令 + `--名称 值` 选项，选项可重复
+#[derive(Debug)]
+pub struct Args {
+    pub command: String,
+    pub options: Vec<(String, String)>,
+}
+
+impl Args {
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let mut iter = args.iter();
+        let command = iter.next().ok_or_else(|| USAGE.to_string())?.clone();
+        let mut options = Vec::new();
+        while let Some(arg) = iter.next() {
+            let Some(name) = arg.strip_prefix("--") else {
+                return Err(format!("无法识别的参数 {}\n{}", arg, USAGE));
+            };
+            let value = iter.next().ok_or_else(|| format!("--{} 缺少取值", name))?;
+            options.push((name.to_string(), value.clone()));
+        }
+        Ok(Self { command, options })
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.options.iter().rev().find(|o| o.0 == name).map(|o| o.1.as_str())
+    }
+
+    pub fn all(&self, name: &str) -> Vec<&str> {
+        self.options.iter().filter(|o| o.0 == name).map(|o| o.1.as_str()).collect()
+    }
+
+    pub fn number<T: std::str::FromStr>(&self, name: &str, default: T) -> Result<T, String> {
+        match self.get(name) {
+            Some(v) => v.parse().map_err(|_| format!("--{} 不是有效数字：{}", name, v)),
+            None => Ok(default),
+        }
+    }
+}
+
+/// 一次回测需要的公共输入
+pub struct Job {
+    pub bars: Vec<KLine>,
+    pub code: String,
+    pub strategy: String,
+    pub cash: f64,
+    pub params: ParamSet,
+    pub out: Option<PathBuf>,
+    /// 输出格式，见 `OUTPUT_FORMATS`
+    pub formats: Vec<String>,
+    /// 配置文件，账户的手续费、风控、融资融券和期货设置取自其中
+    pub config: Option<BacktestConfig>,
+}
+
+impl Job {
+    pub fn from_args(args: &Args) -> Result<Self, String> {
+        let config = match args.get("config") {
+            Some(path) => Some(BacktestConfig::load(path).map_err(|e| format!("{}：{}", path, e))?),
+            None => None,
+        };
+        let bars = match (args.get("data"), &config) {
+            (Some(data), _) => load_klines(data).map_err(|e| format!("读取 {} 失败：{}", data, e))?,
+            (None, Some(config)) => config.load_bars().map_err(|e| e.to_string())?,
+            (None, None) => return Err(format!("缺少 --data\n{}", USAGE)),
+        };
+        let code = match (args.get("code"), &config) {
+            (Some(code), _) => code.to_string(),
+            (None, Some(config)) => config.data.code.clone(),
+            (None, None) => return Err(format!("缺少 --code\n{}", USAGE)),
+        };
+        let mut params = config.as_ref().map(|c| c.params()).unwrap_or_default();
+        for p in args.all("param") {
+            let (name, value) = p.split_once('=').ok_or_else(|| format!("--param 格式应为 名称=值：{}", p))?;
+            let value = value.parse().map_err(|_| format!("参数 {} 的取值不是数字：{}", name, value))?;
+            params.insert(name.to_string(), value);
+        }
+        let job = Self {
+            bars,
+            code,
+            strategy: args
+                .get("strategy")
+                .or(config.as_ref().map(|c| c.strategy.name.as_str()))
+                .unwrap_or("k")
+                .to_string(),
+            cash: args.number("cash", config.as_ref().map(|c| c.initial_cash).unwrap_or(1_000_000.0))?,
+            params,
+            out: args
+                .get("out")
+                .map(PathBuf::from)
+                .or(config.as_ref().and_then(|c| c.output.dir.clone())),
+            formats: match (args.get("format"), &config) {
+                (Some(f), _) => f.split(',').map(|s| s.trim().to_string()).collect(),
+                (None, Some(c)) if !c.output.formats.is_empty() => c.output.formats.clone(),
+                _ => vec!["csv".to_string()],
+            },
+            config,
+        };
+        if let Some(f) = job.formats.iter().find(|f| !OUTPUT_FORMATS.contains(&f.as_str())) {
+            return Err(format!("不支持的输出格式 {}，可选：{}", f, OUTPUT_FORMATS.join(", ")));
+        }
+        // 提前检查策略名和参数名
+        strategy::build(&job.strategy, &job.params)?;
+        if let Some(out) = &job.out {
+            fs::create_dir_all(out).map_err(|e| format!("创建目录 {} 失败：{}", out.display(), e))?;
+        }
+        Ok(job)
+    }
+
+    /// 用给定参数 (覆盖命令行 --param) 回测一段 K 线，参数组合无效时返回错误
+    pub fn run(&self, set: &ParamSet, bars: &[KLine], cash: f64) -> Result<BacktestResult, String> {
+        let mut params = self.params.clone();
+        params.extend(set.iter().map(|(k, v)| (k.clone(), *v)));
+        let mut strategy = strategy::build(&self.strategy, &params)?;
+        let account = match &self.config {
+            Some(config) => BacktestConfig { initial_cash: cash, ..config.clone() }.new_account(),
+            None => engine::new_account(cash),
+        };
+        Ok(engine::run_with(strategy.as_mut(), bars, &self.code, account))
+    }
+
+    fn out_file(&self, name: &str) -> Option<PathBuf> {
+        self.out.as_ref().map(|dir| dir.join(name))
+    }
+
+    fn wants(&self, format: &str) -> bool {
+        self.formats.iter().any(|f| f == format)
+    }
+
+    /// 按输出格式导出交割单、委托、持仓和资金曲线
+    fn export(&self, result: &BacktestResult) -> Result<(), String> {
+        let Some(dir) = &self.out else { return Ok(()) };
+        let formats: Vec<ExportFormat> = self.formats.iter().filter_map(|f| ExportFormat::from_key(f)).collect();
+        let files = export_result(result, dir, &formats).map_err(|e| format!("导出到 {} 失败：{}", dir.display(), e))?;
+        for file in files {
+            println!("已写入 {}", file.display());
+        }
+        Ok(())
+    }
+
+    fn write_html(&self, result: &BacktestResult, relative: Option<&RelativeMetrics>) -> Result<(), String> {
+        let Some(path) = self.out_file("report.html") else { return Ok(()) };
+        let title = format!("{} {} 回测报告", self.code, self.strategy);
+        write_html_report(&path, &title, &self.code, &self.bars, result, &self.params, relative)
+            .map_err(|e| format!("写入 {} 失败：{}", path.display(), e))?;
+        println!("已写入 {}", path.display());
+        Ok(())
+    }
+}
+
+/// 命令行入口，`args` 不含程序名
+pub fn run(args: &[String]) -> Result<(), String> {
+    let args = Args::parse(args)?;
+    match args.command.as_str() {
+        "run" => cmd_run(&args),
+        "optimize" => cmd_optimize(&args),
+        "report" => cmd_report(&args),
+        "help" | "--help" | "-h" => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        other => Err(format!("未知子命令 {}\n{}", other, USAGE)),
+    }
+}
+
+fn cmd_run(args: &Args) -> Result<(), String> {
+    let job = Job::from_args(args)?;
+    let result = job.run(&ParamSet::new(), &job.bars, job.cash)?;
+    print_transactions(&result);
+    print_risk_events(&result);
+    print_margin_events(&result);
+    print_summary(&Metrics::from_result(&result));
+    job.export(&result)?;
+    if job.wants("html") {
+        job.write_html(&result, None)?;
+    }
+    Ok(())
+}
+
+fn cmd_optimize(args: &Args) -> Result<(), String> {
+    let job = Job::from_args(args)?;
+    let mut ranges = Vec::new();
+    for r in args.all("range") {
+        ranges.push(parse_range(r)?);
+    }
+    if ranges.is_empty() {
+        return Err(format!("至少需要一个 --range\n{}", USAGE));
+    }
+    // 用各范围的起点检查参数名，不展开整个网格
+    let mut probe = job.params.clone();
+    probe.extend(ranges.iter().map(|r| (r.name.clone(), r.start)));
+    strategy::build(&job.strategy, &probe)?;
+    let space = ParamSpace::new(ranges);
+    let metric_key = args.get("metric").unwrap_or("total_return");
+    let metric = Metric::from_key(metric_key).ok_or_else(|| format!("未知指标 {}", metric_key))?;
+    let seed = args.number("seed", 42u64)?;
+
+    if let Some(wf) = args.get("walk-forward") {
+        let parts: Vec<&str> = wf.split(':').collect();
+        if parts.len() < 2 {
+            return Err(format!("--walk-forward 格式应为 样本内:样本外[:anchored]：{}", wf));
+        }
+        let config = WalkForwardConfig {
+            in_sample: parts[0].parse().map_err(|_| format!("样本内长度无效：{}", parts[0]))?,
+            out_of_sample: parts[1].parse().map_err(|_| format!("样本外长度无效：{}", parts[1]))?,
+            anchored: parts.get(2) == Some(&"anchored"),
+            metric,
+        };
+        let result = walk_forward(&job.bars, &space, &config, job.cash, |set, bars, cash| job.run(set, bars, cash).ok());
+        result.print_report();
+        if let Some(path) = job.out_file("walk_forward_equity.csv") {
+            write_equity(&path, &result.equity)?;
+        }
+        return Ok(());
+    }
+
+    // 参数组合无效 (如短均线不短于长均线) 时得分为 NaN，排在最后
+    let objective = |set: &ParamSet| match job.run(set, &job.bars, job.cash) {
+        Ok(result) => metric.score(&Metrics::from_result(&result)),
+        Err(_) => f64::NAN,
+    };
+    let evaluations: Vec<Evaluation> = match args.get("method").unwrap_or("grid") {
+        "grid" => grid_search(&space, objective),
+        "random" => {
+            let config = RandomSearchConfig {
+                max_evals: args.number("evals", 200)?,
+                patience: args.number("patience", 0)?,
+                seed,
+            };
+            random_search(&space, &config, objective).ranked()
+        }
+        "genetic" => {
+            let config = GeneticConfig {
+                population: args.number("population", 20)?,
+                generations: args.number("generations", 30)?,
+                patience: args.number("patience", 5)?,
+                seed,
+                ..Default::default()
+            };
+            genetic(&space, &config, objective).ranked()
+        }
+        other => return Err(format!("未知寻优方法 {}", other)),
+    };
+
+    println!("\n共评估 {} 组参数，按{}排序前 10：", evaluations.len(), metric.name());
+    for e in evaluations.iter().take(10) {
+        let params: Vec<String> = e.params.iter().map(|(k, v)| format!("{}={:.4}", k, v)).collect();
+        println!("{:>12.4}  {}", e.score, params.join(" "));
+    }
+    if let Some(path) = job.out_file("optimize.csv") {
+        write_evaluations(&path, &space, &evaluations)?;
+    }
+    Ok(())
+}
+
+fn cmd_report(args: &Args) -> Result<(), String> {
+    let job = Job::from_args(args)?;
+    let result = job.run(&ParamSet::new(), &job.bars, job.cash)?;
+    print_summary(&Metrics::from_result(&result));
+
+    let relative = match args.get("benchmark") {
+        Some(b) => {
+            let benchmark = if b == "hold" {
+                Benchmark::buy_and_hold(&job.code, &job.bars)
+            } else {
+                Benchmark::from_csv(b, b).map_err(|e| format!("读取基准 {} 失败：{}", b, e))?
+            };
+            let relative = compare(&result, &benchmark);
+            relative.print_report();
+            if let Some(path) = job.out_file("excess.csv") {
+                write_equity(&path, &relative.excess_curve)?;
+            }
+            Some(relative)
+        }
+        None => None,
+    };
+
+    let simulations: usize = args.number("monte-carlo", 0)?;
+    if simulations > 0 {
+        let seed = args.number("seed", 42u64)?;
+        let trips = round_trips(&result.account.transactions);
+        println!("\n买卖回合：{} 笔", trips.len());
+        for method in [Resample::Shuffle, Resample::Bootstrap] {
+            println!("\n重采样方式：{:?}", method);
+            resample_trades(&trips, job.cash, method, simulations, seed).print_report();
+        }
+    }
+    job.export(&result)?;
+    // report 子命令总是输出 HTML 报告
+    job.write_html(&result, relative.as_ref())?;
+    Ok(())
+}
+
+/// 解析 `名称=起:止:步长`
+pub fn parse_range(s: &str) -> Result<ParamRange, String> {
+    let err = || format!("--range 格式应为 名称=起:止:步长：{}", s);
+    let (name, spec) = s.split_once('=').ok_or_else(err)?;
+    let nums: Vec<f64> = spec.split(':').map(|v| v.parse()).collect::<Result<_, _>>().map_err(|_| err())?;
+    match nums[..] {
+        [start, end, step] => Ok(ParamRange::new(name, start, end, step)),
+        [value] => Ok(ParamRange::new(name, value, value, 0.0)),
+        _ => Err(err()),
+    }
+}
+
+/// 时间戳转成北京时间日期
+fn format_date(time: i64) -> String {
+    let utc_time = Utc.timestamp_opt(time, 0).single().unwrap_or_default();
+    (utc_time + Duration::hours(8)).format("%Y-%m-%d").to_string()
+}
+
+fn print_transactions(result: &BacktestResult) {
+    println!("\n交易记录：");
+    for t in &result.account.transactions {
+        println!(
+            "{} - {:4} {}股 @ {:.2}  成交后{}股 成交后成本{:.3}",
+            format_date(t.time), t.order_type, t.volume, t.price, t.remain_vol, t.remain_cost
+        );
+    }
+}
+
+fn print_risk_events(result: &BacktestResult) {
+    let Some(risk) = &result.account.risk else { return };
+    if risk.log.is_empty() {
+        return;
+    }
+    println!("\n风控记录：");
+    for e in &risk.log {
+        let action = if e.allowed > 0 { format!("缩量至{}股", e.allowed) } else { "拒绝".to_string() };
+        let side = if e.order_type == SHORT_SELL { "融券卖出" } else { "买入" };
+        println!("{} - {} {}{}股 {}：{}，{}", format_date(e.time), e.code, side, e.requested, action, e.rule, e.reason);
+    }
+}
+
+fn print_margin_events(result: &BacktestResult) {
+    let Some(margin) = &result.account.margin else { return };
+    println!("\n融资负债：{:.2}  融券市值：{:.2}  未付利息：{:.2}", margin.cash_debt, margin.short_value(), margin.interest);
+    for e in &margin.log {
+        let kind = match e.kind {
+            MarginEventKind::Call => "追加担保",
+            MarginEventKind::Liquidation => "强制平仓",
+            MarginEventKind::Restored => "担保恢复",
+        };
+        println!("{} - {} 维持担保比例 {:.3}", format_date(e.time), kind, e.ratio);
+    }
+}
+
+fn print_summary(m: &Metrics) {
+    println!("\n期末总资产：{:.2}", m.final_balance);
+    println!("总收益率：{:.2}%  年化收益率：{:.2}%", m.total_return * 100.0, m.annual_return * 100.0);
+    println!("最大回撤：{:.2}%  年化波动率：{:.2}%", m.max_drawdown * 100.0, m.volatility * 100.0);
+    println!("夏普比率：{:.3}  成交笔数：{}", m.sharpe, m.trade_count);
+}
+
+fn csv_err(path: &Path) -> impl Fn(csv::Error) -> String + '_ {
+    move |e| format!("写入 {} 失败：{}", path.display(), e)
+}
+
+fn write_equity(path: &Path, equity: &[(i64, f64)]) -> Result<(), String> {
+    let mut w = Writer::from_path(path).map_err(csv_err(path))?;
+    w.write_record(["time", "date", "value"]).map_err(csv_err(path))?;
+    for &(t, v) in equity {
+        w.write_record([t.to_string(), format_date(t), v.to_string()]).map_err(csv_err(path))?;
+    }
+    w.flush().map_err(|e| format!("写入 {} 失败：{}", path.display(), e))?;
+    println!("已写入 {}", path.display());
+    Ok(())
+}
+
+fn write_evaluations(path: &Path, space: &ParamSpace, evaluations: &[Evaluation]) -> Result<(), String> {
+    let mut w = Writer::from_path(path).map_err(csv_err(path))?;
+    let mut header: Vec<&str> = space.ranges.iter().map(|r| r.name.as_str()).collect();
+    header.push("score");
+    w.write_record(&header).map_err(csv_err(path))?;
+    for e in evaluations {
+        let mut row: Vec<String> = space.ranges.iter().map(|r| e.params[&r.name].to_string()).collect();
+        row.push(e.score.to_string());
+        w.write_record(&row).map_err(csv_err(path))?;
+    }
+    w.flush().map_err(|e| format!("写入 {} 失败：{}", path.display(), e))?;
+    println!("已写入 {}", path.display());
+    Ok(())
+}
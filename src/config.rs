@@ -0,0 +1,306 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::account::{Account, FeeModel};
+use crate::data::{filter_dates, find_symbol_file, load_klines};
+use crate::engine::new_account;
+use crate::model::KLine;
+use crate::futures::{FuturesAccount, FuturesContract};
+use crate::margin::{MarginAccount, MarginConfig};
+use crate::optimize::ParamSet;
+use crate::risk::{RiskLimits, RiskManager};
+use crate::strategy;
+
+/// 支持的输出格式
+pub const OUTPUT_FORMATS: [&str; 4] = ["csv", "json", "parquet", "html"];
+
+/// 回测配置文件，支持 TOML 和 YAML
+///
+/// ```toml
+/// initial_cash = 1000000
+///
+/// [data]
+/// dir = "A:/data/day"
+/// code = "601111"
+///
+/// [range]
+/// start = "2020-01-01"
+/// end = "2024-12-31"
+///
+/// [fees]
+/// commission_ratio = 0.00025
+/// min_commission = 5
+/// tax_ratio = 0.0005
+///
+/// [strategy]
+/// name = "k"
+/// params = { buy_price_low = 5.9, buy_price_high = 7.8, init_base_volume = 20000 }
+///
+/// [output]
+/// dir = "out"
+/// formats = ["csv"]
+///
+/// [risk]
+/// max_position_pct = 0.5
+/// max_adds = 3
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BacktestConfig {
+    pub data: DataConfig,
+    #[serde(default)]
+    pub range: DateRange,
+    #[serde(default = "default_cash")]
+    pub initial_cash: f64,
+    #[serde(default)]
+    pub fees: FeeModel,
+    pub strategy: StrategyConfig,
+    #[serde(default)]
+    pub output: OutputConfig,
+    /// 风控限制，见 `RiskLimits`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub risk: Option<RiskLimits>,
+    /// 设置后使用信用账户，见 `MarginConfig`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub margin: Option<MarginConfig>,
+    /// 可交易的期货合约，见 `FuturesContract`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub futures: Vec<FuturesContract>,
+}
+
+fn default_cash() -> f64 {
+    1_000_000.0
+}
+
+/// 数据来源：直接给出文件，或给出目录按代码查找
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DataConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dir: Option<PathBuf>,
+    /// 股票代码
+    pub code: String,
+}
+
+/// 回测日期区间，格式 YYYY-MM-DD，留空表示不限
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DateRange {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StrategyConfig {
+    pub name: String,
+    #[serde(default)]
+    pub params: BTreeMap<String, f64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OutputConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dir: Option<PathBuf>,
+    #[serde(default)]
+    pub formats: Vec<String>,
+}
+
+/// 配置错误，key 为出错的配置项路径，如 `strategy.params.foo`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigError {
+    pub key: String,
+    pub message: String,
+}
+
+impl ConfigError {
+    fn new(key: &str, message: impl Into<String>) -> Self {
+        Self {
+            key: key.to_string(),
+            message: message.into(),
+        }
+    }
+
+    /// 解析器报错，key 取出错位置的路径，如 `risk.max_adds`
+    fn from_parse<E: fmt::Display>(e: serde_path_to_error::Error<E>) -> Self {
+        let path = e.path().to_string();
+        let key = if path == "." { "" } else { &path };
+        Self::new(key, e.inner().to_string())
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.key.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}: {}", self.key, self.message)
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl BacktestConfig {
+    /// 按扩展名读取 .toml / .yaml / .yml 并校验
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)
+            .map_err(|e| ConfigError::new("", format!("读取 {} 失败：{}", path.display(), e)))?;
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("yaml") | Some("yml") => Self::from_yaml_str(&text),
+            _ => Self::from_toml_str(&text),
+        }
+    }
+
+    /// 按扩展名写出 .toml / .yaml / .yml
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), ConfigError> {
+        let path = path.as_ref();
+        let text = match path.extension().and_then(|e| e.to_str()) {
+            Some("yaml") | Some("yml") => self.to_yaml_string(),
+            _ => self.to_toml_string(),
+        };
+        fs::write(path, text).map_err(|e| ConfigError::new("", format!("写入 {} 失败：{}", path.display(), e)))
+    }
+
+    pub fn from_toml_str(text: &str) -> Result<Self, ConfigError> {
+        let config: Self = serde_path_to_error::deserialize(toml::Deserializer::new(text)).map_err(ConfigError::from_parse)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn from_yaml_str(text: &str) -> Result<Self, ConfigError> {
+        let config: Self = serde_path_to_error::deserialize(serde_yaml::Deserializer::from_str(text)).map_err(ConfigError::from_parse)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn to_toml_string(&self) -> String {
+        toml::to_string_pretty(self).expect("配置可序列化为 TOML")
+    }
+
+    pub fn to_yaml_string(&self) -> String {
+        serde_yaml::to_string(self).expect("配置可序列化为 YAML")
+    }
+
+    /// 检查取值是否合法，返回第一个出错的配置项
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        match (&self.data.path, &self.data.dir) {
+            (None, None) => return Err(ConfigError::new("data", "需要 path 或 dir")),
+            (Some(_), Some(_)) => return Err(ConfigError::new("data", "path 和 dir 只能设置一个")),
+            _ => {}
+        }
+        let code = &self.data.code;
+        if code.is_empty() || code.len() > 8 || !code.is_ascii() {
+            return Err(ConfigError::new("data.code", format!("无效的股票代码 {:?}", code)));
+        }
+
+        let start = parse_date("range.start", &self.range.start)?;
+        let end = parse_date("range.end", &self.range.end)?;
+        if let (Some(s), Some(e)) = (start, end)
+            && s > e
+        {
+            return Err(ConfigError::new("range", format!("开始日期 {} 晚于结束日期 {}", s, e)));
+        }
+
+        if self.initial_cash.is_nan() || self.initial_cash <= 0.0 {
+            return Err(ConfigError::new("initial_cash", "必须大于 0"));
+        }
+        for (key, value) in [
+            ("fees.commission_ratio", self.fees.commission_ratio),
+            ("fees.tax_ratio", self.fees.tax_ratio),
+        ] {
+            if !(0.0..0.1).contains(&value) {
+                return Err(ConfigError::new(key, format!("费率 {} 超出范围 [0, 0.1)", value)));
+            }
+        }
+        if self.fees.min_commission < 0.0 {
+            return Err(ConfigError::new("fees.min_commission", "不能为负数"));
+        }
+
+        let name = &self.strategy.name;
+        strategy::build(name, &ParamSet::new()).map_err(|e| ConfigError::new("strategy.name", e))?;
+        for (key, &value) in &self.strategy.params {
+            strategy::check_param(name, key, value)
+                .map_err(|e| ConfigError::new(&format!("strategy.params.{}", key), e))?;
+        }
+        // 参数之间的关系，如网格上下边界
+        strategy::build(name, &self.params()).map_err(|e| ConfigError::new("strategy.params", e))?;
+
+        if let Some(risk) = &self.risk {
+            risk.validate().map_err(|(key, e)| ConfigError::new(&format!("risk.{}", key), e))?;
+        }
+        if let Some(margin) = &self.margin {
+            margin.validate().map_err(|(key, e)| ConfigError::new(&format!("margin.{}", key), e))?;
+        }
+        for (i, contract) in self.futures.iter().enumerate() {
+            contract.validate().map_err(|(key, e)| ConfigError::new(&format!("futures[{}].{}", i, key), e))?;
+        }
+
+        for (i, format) in self.output.formats.iter().enumerate() {
+            if !OUTPUT_FORMATS.contains(&format.as_str()) {
+                return Err(ConfigError::new(
+                    &format!("output.formats[{}]", i),
+                    format!("不支持的格式 {}，可选：{}", format, OUTPUT_FORMATS.join(", ")),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// 数据文件路径
+    pub fn data_file(&self) -> Result<PathBuf, ConfigError> {
+        if let Some(path) = &self.data.path {
+            return Ok(path.clone());
+        }
+        let dir = self.data.dir.as_ref().ok_or_else(|| ConfigError::new("data", "需要 path 或 dir"))?;
+        find_symbol_file(dir, &self.data.code).ok_or_else(|| {
+            ConfigError::new("data.dir", format!("{} 中没有 {} 的数据文件", dir.display(), self.data.code))
+        })
+    }
+
+    /// 读取数据并按日期区间过滤
+    pub fn load_bars(&self) -> Result<Vec<KLine>, ConfigError> {
+        let path = self.data_file()?;
+        let bars = load_klines(&path)
+            .map_err(|e| ConfigError::new("data", format!("读取 {} 失败：{}", path.display(), e)))?;
+        let start = parse_date("range.start", &self.range.start)?;
+        let end = parse_date("range.end", &self.range.end)?;
+        Ok(filter_dates(bars, start, end))
+    }
+
+    /// 按配置的初始资金、手续费、风控、信用和期货设置创建账户
+    pub fn new_account(&self) -> Account {
+        Account {
+            fee: self.fees.clone(),
+            risk: self.risk.clone().map(RiskManager::new),
+            margin: self.margin.clone().map(MarginAccount::new),
+            futures: (!self.futures.is_empty()).then(|| FuturesAccount::new(self.futures.clone())),
+            ..new_account(self.initial_cash)
+        }
+    }
+
+    pub fn params(&self) -> ParamSet {
+        self.strategy.params.clone()
+    }
+}
+
+fn parse_date(key: &str, value: &Option<String>) -> Result<Option<NaiveDate>, ConfigError> {
+    value
+        .as_deref()
+        .map(|v| {
+            NaiveDate::parse_from_str(v, "%Y-%m-%d")
+                .map_err(|_| ConfigError::new(key, format!("日期格式应为 YYYY-MM-DD：{}", v)))
+        })
+        .transpose()
+}
@@ -0,0 +1,127 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{Duration, NaiveDate, TimeZone, Utc};
+use csv::Reader;
+
+use crate::model::{KLine, TickData};
+
+/// 读取 K 线 CSV，按时间升序返回
+pub fn load_klines<P: AsRef<Path>>(path: P) -> Result<Vec<KLine>, csv::Error> {
+    let mut reader = Reader::from_path(path)?;
+    let mut bars = Vec::new();
+    for row in reader.deserialize() {
+        let bar: KLine = row?;
+        bars.push(bar);
+    }
+    bars.sort_by_key(|b| b.time);
+    Ok(bars)
+}
+
+/// 读取 Tick CSV，按时间升序返回，列名与 `TickData` 字段一致
+pub fn load_ticks<P: AsRef<Path>>(path: P) -> Result<Vec<TickData>, csv::Error> {
+    let mut reader = Reader::from_path(path)?;
+    let mut ticks = Vec::new();
+    for row in reader.deserialize() {
+        let tick: TickData = row?;
+        ticks.push(tick);
+    }
+    ticks.sort_by_key(|t| t.time);
+    Ok(ticks)
+}
+
+/// 同一时间点各标的的 K 线，顺序与输入的数据源一致
+#[derive(Debug, Clone)]
+pub struct AlignedBars {
+    pub time: i64,
+    pub bars: Vec<KLine>,
+}
+
+/// 按时间戳对齐多个已排序的 K 线序列，只保留所有标的都有数据的时间点
+/// （停牌等缺失的时间点整体跳过，不用旧价格成交）
+pub fn align_feeds(feeds: &[Vec<KLine>]) -> Vec<AlignedBars> {
+    let mut cursors = vec![0usize; feeds.len()];
+    let mut aligned = Vec::new();
+    if feeds.is_empty() {
+        return aligned;
+    }
+    loop {
+        // 各序列当前位置的最大时间戳，落后的序列追上来
+        let mut time = i64::MIN;
+        for (feed, &i) in feeds.iter().zip(&cursors) {
+            match feed.get(i) {
+                Some(bar) => time = time.max(bar.time),
+                None => return aligned,
+            }
+        }
+        let mut all = true;
+        for (feed, i) in feeds.iter().zip(cursors.iter_mut()) {
+            while feed.get(*i).is_some_and(|b| b.time < time) {
+                *i += 1;
+            }
+            all &= feed.get(*i).is_some_and(|b| b.time == time);
+        }
+        if all {
+            let bars = feeds.iter().zip(cursors.iter_mut()).map(|(feed, i)| {
+                *i += 1;
+                feed[*i - 1].clone()
+            });
+            aligned.push(AlignedBars {
+                time,
+                bars: bars.collect(),
+            });
+        }
+    }
+}
+
+/// 时间戳对应的北京时间日期
+pub fn bar_date(time: i64) -> NaiveDate {
+    let utc_time = Utc.timestamp_opt(time, 0).single().unwrap_or_default();
+    (utc_time + Duration::hours(8)).date_naive()
+}
+
+/// 保留日期在 [start, end] 内的 K 线，None 表示不限
+pub fn filter_dates(bars: Vec<KLine>, start: Option<NaiveDate>, end: Option<NaiveDate>) -> Vec<KLine> {
+    bars.into_iter()
+        .filter(|b| {
+            let date = bar_date(b.time);
+            start.is_none_or(|s| date >= s) && end.is_none_or(|e| date <= e)
+        })
+        .collect()
+}
+
+/// 数据目录中的一个标的
+#[derive(Debug, Clone, PartialEq)]
+pub struct SymbolFile {
+    /// 股票代码，取文件名末尾的数字，如 USHA601111.csv -> 601111
+    pub code: String,
+    pub path: PathBuf,
+}
+
+/// 从文件名中解析股票代码：末尾的连续数字，没有数字时取整个文件名
+fn code_from_stem(stem: &str) -> Option<String> {
+    let digits = stem.len() - stem.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+    let code = if digits > 0 { &stem[stem.len() - digits..] } else { stem };
+    // StockCode 最多 8 个字节
+    (!code.is_empty() && code.len() <= 8 && code.is_ascii()).then(|| code.to_string())
+}
+
+/// 列出目录中的 K 线 CSV，按代码排序
+pub fn list_symbols<P: AsRef<Path>>(dir: P) -> Vec<SymbolFile> {
+    let Ok(entries) = fs::read_dir(dir) else { return Vec::new() };
+    let mut symbols: Vec<SymbolFile> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("csv")))
+        .filter_map(|path| {
+            let code = code_from_stem(path.file_stem()?.to_str()?)?;
+            Some(SymbolFile { code, path })
+        })
+        .collect();
+    symbols.sort_by(|a, b| a.code.cmp(&b.code).then_with(|| a.path.cmp(&b.path)));
+    symbols
+}
+
+/// 在目录中查找代码对应的 CSV，如 USHA601111.csv
+pub fn find_symbol_file<P: AsRef<Path>>(dir: P, code: &str) -> Option<PathBuf> {
+    list_symbols(dir).into_iter().find(|s| s.code == code).map(|s| s.path)
+}
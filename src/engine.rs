@@ -0,0 +1,157 @@
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use crate::account::{Account, StockCode};
+use crate::data::{bar_date, AlignedBars};
+use crate::futures;
+use crate::margin;
+use crate::model::KLine;
+use crate::protect;
+use crate::strategy::{MultiStrategy, Strategy};
+
+/// 每根 K 线收盘后的持仓快照
+#[derive(Debug, Clone, Serialize)]
+pub struct DailyPosition {
+    pub time: i64,
+    pub code: StockCode,
+    /// 持仓数量
+    pub volume: i32,
+    /// 可用数量
+    pub available_vol: i32,
+    /// 成本价
+    pub cost_price: f64,
+    /// 当前价格
+    pub current_price: f64,
+    /// 市值
+    pub market_value: f64,
+}
+
+/// 一次回测的结果
+#[derive(Debug)]
+pub struct BacktestResult {
+    /// 初始资金
+    pub init_cash: f64,
+    /// 资金曲线 (时间戳, 总资产)
+    pub equity: Vec<(i64, f64)>,
+    /// 持仓快照，只记录有持仓的股票
+    pub positions: Vec<DailyPosition>,
+    /// 回测结束时的账户
+    pub account: Account,
+}
+
+impl BacktestResult {
+    /// 期末总资产
+    pub fn final_balance(&self) -> f64 {
+        self.equity.last().map(|e| e.1).unwrap_or(self.init_cash)
+    }
+}
+
+/// 用给定资金创建账户
+pub fn new_account(init_cash: f64) -> Account {
+    Account {
+        balance: init_cash,
+        available_balance: init_cash,
+        ..Default::default()
+    }
+}
+
+/// T+1：`time` 进入新的交易日时，之前买入的持仓全部变为可卖，`day` 记录上次解锁的交易日
+pub fn unlock_t1(account: &mut Account, day: &mut Option<NaiveDate>, time: i64) {
+    let today = bar_date(time);
+    if *day != Some(today) {
+        *day = Some(today);
+        for position in account.hold.values_mut() {
+            position.available_vol = position.volume;
+        }
+    }
+}
+
+/// 逐根 K 线运行策略，记录每根 K 线收盘后的总资产
+pub fn run(strategy: &mut dyn Strategy, bars: &[KLine], code: &str, init_cash: f64) -> BacktestResult {
+    run_with(strategy, bars, code, new_account(init_cash))
+}
+
+/// 用调用方准备好的账户（如设置了手续费）运行策略，初始资金取账户总资产
+pub fn run_with(strategy: &mut dyn Strategy, bars: &[KLine], code: &str, account: Account) -> BacktestResult {
+    run_until(strategy, bars, code, account, |_, _| true)
+}
+
+/// 每根 K 线处理完后调用 `on_bar(序号, 资金点)`，返回 false 时停止，结果只包含已处理的 K 线
+pub fn run_until<F>(strategy: &mut dyn Strategy, bars: &[KLine], code: &str, mut account: Account, mut on_bar: F) -> BacktestResult
+where
+    F: FnMut(usize, (i64, f64)) -> bool,
+{
+    let init_cash = account.balance;
+    let mut equity = Vec::with_capacity(bars.len());
+    let mut positions = Vec::new();
+    let mut day = None;
+    for (i, bar) in bars.iter().enumerate() {
+        unlock_t1(&mut account, &mut day, bar.time);
+        futures::roll_day(&mut account, bar.time);
+        protect::on_bar(&mut account, bar, code);
+        strategy.process_bar(bar, code, &mut account);
+        // 按收盘价更新市值，策略不需要自行调用 on_price_change
+        account.on_price_change(code, bar.close);
+        account.balance = account.net_assets();
+        margin::on_close(&mut account, bar.time);
+        account.update_risk(bar.time);
+        equity.push((bar.time, account.balance));
+        snapshot(bar.time, &account, &mut positions);
+        if !on_bar(i, (bar.time, account.balance)) {
+            break;
+        }
+    }
+    futures::finish(&mut account);
+    BacktestResult {
+        init_cash,
+        equity,
+        positions,
+        account,
+    }
+}
+
+/// 记录有持仓股票的快照，按代码排序
+fn snapshot(time: i64, account: &Account, positions: &mut Vec<DailyPosition>) {
+    let mut held: Vec<_> = account.hold.values().filter(|p| p.volume != 0).collect();
+    held.sort_by(|a, b| a.code.cmp(&b.code));
+    positions.extend(held.into_iter().map(|p| DailyPosition {
+        time,
+        code: p.code.clone(),
+        volume: p.volume,
+        available_vol: p.available_vol,
+        cost_price: p.cost_price,
+        current_price: p.current_price,
+        market_value: p.market_value,
+    }));
+}
+
+/// 多标的回测：steps 为 `align_feeds` 对齐后的 K 线，codes 与其中的顺序一致
+pub fn run_multi(strategy: &mut dyn MultiStrategy, steps: &[AlignedBars], codes: &[String], mut account: Account) -> BacktestResult {
+    let init_cash = account.balance;
+    let mut equity = Vec::with_capacity(steps.len());
+    let mut positions = Vec::new();
+    let mut day = None;
+    for step in steps {
+        unlock_t1(&mut account, &mut day, step.time);
+        futures::roll_day(&mut account, step.time);
+        for (code, bar) in codes.iter().zip(&step.bars) {
+            protect::on_bar(&mut account, bar, code);
+        }
+        strategy.process_bars(&step.bars, codes, &mut account);
+        for (code, bar) in codes.iter().zip(&step.bars) {
+            account.on_price_change(code, bar.close);
+        }
+        account.balance = account.net_assets();
+        margin::on_close(&mut account, step.time);
+        account.update_risk(step.time);
+        equity.push((step.time, account.balance));
+        snapshot(step.time, &account, &mut positions);
+    }
+    futures::finish(&mut account);
+    BacktestResult {
+        init_cash,
+        equity,
+        positions,
+        account,
+    }
+}
@@ -0,0 +1,279 @@
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use parquet::data_type::{BoolType, ByteArray, ByteArrayType, DoubleType, Int32Type, Int64Type};
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::SerializedFileWriter;
+use parquet::schema::parser::parse_message_type;
+use serde_json::{Map, Value};
+
+use crate::data::bar_date;
+use crate::engine::BacktestResult;
+use crate::metrics::drawdown_series;
+
+pub type ExportError = Box<dyn std::error::Error + Send + Sync>;
+
+/// 导出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+    Parquet,
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 3] = [ExportFormat::Csv, ExportFormat::Json, ExportFormat::Parquet];
+
+    /// 配置中的名称，同时也是文件扩展名
+    pub fn key(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+            ExportFormat::Parquet => "parquet",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<ExportFormat> {
+        ExportFormat::ALL.into_iter().find(|f| f.key() == key)
+    }
+}
+
+/// 一列数据
+#[derive(Debug, Clone, PartialEq)]
+pub enum Column {
+    I64(Vec<i64>),
+    I32(Vec<i32>),
+    F64(Vec<f64>),
+    Str(Vec<String>),
+    Bool(Vec<bool>),
+}
+
+impl Column {
+    pub fn len(&self) -> usize {
+        match self {
+            Column::I64(v) => v.len(),
+            Column::I32(v) => v.len(),
+            Column::F64(v) => v.len(),
+            Column::Str(v) => v.len(),
+            Column::Bool(v) => v.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn text(&self, row: usize) -> String {
+        match self {
+            Column::I64(v) => v[row].to_string(),
+            Column::I32(v) => v[row].to_string(),
+            Column::F64(v) => v[row].to_string(),
+            Column::Str(v) => v[row].clone(),
+            Column::Bool(v) => v[row].to_string(),
+        }
+    }
+
+    fn json(&self, row: usize) -> Value {
+        match self {
+            Column::I64(v) => Value::from(v[row]),
+            Column::I32(v) => Value::from(v[row]),
+            Column::F64(v) => Value::from(v[row]),
+            Column::Str(v) => Value::from(v[row].as_str()),
+            Column::Bool(v) => Value::from(v[row]),
+        }
+    }
+
+    fn parquet_type(&self) -> &'static str {
+        match self {
+            Column::I64(_) => "INT64",
+            Column::I32(_) => "INT32",
+            Column::F64(_) => "DOUBLE",
+            Column::Str(_) => "BYTE_ARRAY",
+            Column::Bool(_) => "BOOLEAN",
+        }
+    }
+}
+
+/// 按列存放的表，三种格式都由它写出
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table {
+    pub name: &'static str,
+    pub columns: Vec<(&'static str, Column)>,
+}
+
+impl Table {
+    pub fn rows(&self) -> usize {
+        self.columns.first().map(|c| c.1.len()).unwrap_or(0)
+    }
+
+    pub fn write_csv(&self, path: &Path) -> Result<(), ExportError> {
+        let mut w = csv::Writer::from_path(path)?;
+        w.write_record(self.columns.iter().map(|c| c.0))?;
+        for row in 0..self.rows() {
+            w.write_record(self.columns.iter().map(|c| c.1.text(row)))?;
+        }
+        w.flush()?;
+        Ok(())
+    }
+
+    /// 写成对象数组
+    pub fn write_json(&self, path: &Path) -> Result<(), ExportError> {
+        let rows: Vec<Value> = (0..self.rows())
+            .map(|row| {
+                let obj: Map<String, Value> =
+                    self.columns.iter().map(|(name, col)| (name.to_string(), col.json(row))).collect();
+                Value::Object(obj)
+            })
+            .collect();
+        serde_json::to_writer_pretty(File::create(path)?, &rows)?;
+        Ok(())
+    }
+
+    pub fn write_parquet(&self, path: &Path) -> Result<(), ExportError> {
+        let fields: Vec<String> = self
+            .columns
+            .iter()
+            .map(|(name, col)| match col {
+                Column::Str(_) => format!("REQUIRED BYTE_ARRAY {} (UTF8);", name),
+                _ => format!("REQUIRED {} {};", col.parquet_type(), name),
+            })
+            .collect();
+        let schema = parse_message_type(&format!("message {} {{ {} }}", self.name, fields.join(" ")))?;
+        let props = Arc::new(WriterProperties::builder().build());
+        let mut writer = SerializedFileWriter::new(File::create(path)?, Arc::new(schema), props)?;
+        let mut row_group = writer.next_row_group()?;
+        let mut columns = self.columns.iter();
+        while let Some(mut col_writer) = row_group.next_column()? {
+            let Some((_, column)) = columns.next() else { break };
+            match column {
+                Column::I64(v) => {
+                    col_writer.typed::<Int64Type>().write_batch(v, None, None)?;
+                }
+                Column::I32(v) => {
+                    col_writer.typed::<Int32Type>().write_batch(v, None, None)?;
+                }
+                Column::F64(v) => {
+                    col_writer.typed::<DoubleType>().write_batch(v, None, None)?;
+                }
+                Column::Bool(v) => {
+                    col_writer.typed::<BoolType>().write_batch(v, None, None)?;
+                }
+                Column::Str(v) => {
+                    let bytes: Vec<ByteArray> = v.iter().map(|s| ByteArray::from(s.as_str())).collect();
+                    col_writer.typed::<ByteArrayType>().write_batch(&bytes, None, None)?;
+                }
+            }
+            col_writer.close()?;
+        }
+        row_group.close()?;
+        writer.close()?;
+        Ok(())
+    }
+
+    /// 写入 `dir/表名.扩展名`
+    pub fn write(&self, dir: &Path, format: ExportFormat) -> Result<PathBuf, ExportError> {
+        let path = dir.join(format!("{}.{}", self.name, format.key()));
+        match format {
+            ExportFormat::Csv => self.write_csv(&path)?,
+            ExportFormat::Json => self.write_json(&path)?,
+            ExportFormat::Parquet => self.write_parquet(&path)?,
+        }
+        Ok(path)
+    }
+}
+
+fn dates(times: &[i64]) -> Column {
+    Column::Str(times.iter().map(|&t| bar_date(t).format("%Y-%m-%d").to_string()).collect())
+}
+
+/// 交割单
+pub fn transactions_table(result: &BacktestResult) -> Table {
+    let t = &result.account.transactions;
+    let times: Vec<i64> = t.iter().map(|t| t.time).collect();
+    Table {
+        name: "transactions",
+        columns: vec![
+            ("date", dates(&times)),
+            ("time", Column::I64(times)),
+            ("code", Column::Str(t.iter().map(|t| t.code.to_string()).collect())),
+            ("order_type", Column::Str(t.iter().map(|t| t.order_type.to_string()).collect())),
+            ("price", Column::F64(t.iter().map(|t| t.price).collect())),
+            ("volume", Column::I32(t.iter().map(|t| t.volume).collect())),
+            ("fee", Column::F64(t.iter().map(|t| t.fee).collect())),
+            ("remain_vol", Column::I32(t.iter().map(|t| t.remain_vol).collect())),
+            ("remain_cost", Column::F64(t.iter().map(|t| t.remain_cost).collect())),
+        ],
+    }
+}
+
+/// 委托记录，包括未成交的委托
+pub fn orders_table(result: &BacktestResult) -> Table {
+    let o = &result.account.orders;
+    let times: Vec<i64> = o.iter().map(|r| r.order.time).collect();
+    Table {
+        name: "orders",
+        columns: vec![
+            ("date", dates(&times)),
+            ("time", Column::I64(times)),
+            ("code", Column::Str(o.iter().map(|r| r.order.code.to_string()).collect())),
+            ("market_type", Column::Str(o.iter().map(|r| r.order.market_type.to_string()).collect())),
+            ("order_type", Column::Str(o.iter().map(|r| r.order.order_type.to_string()).collect())),
+            ("price", Column::F64(o.iter().map(|r| r.order.price).collect())),
+            ("volume", Column::I32(o.iter().map(|r| r.order.volume).collect())),
+            ("filled", Column::Bool(o.iter().map(|r| r.filled).collect())),
+        ],
+    }
+}
+
+/// 每日持仓
+pub fn positions_table(result: &BacktestResult) -> Table {
+    let p = &result.positions;
+    let times: Vec<i64> = p.iter().map(|p| p.time).collect();
+    Table {
+        name: "positions",
+        columns: vec![
+            ("date", dates(&times)),
+            ("time", Column::I64(times)),
+            ("code", Column::Str(p.iter().map(|p| p.code.to_string()).collect())),
+            ("volume", Column::I32(p.iter().map(|p| p.volume).collect())),
+            ("available_vol", Column::I32(p.iter().map(|p| p.available_vol).collect())),
+            ("cost_price", Column::F64(p.iter().map(|p| p.cost_price).collect())),
+            ("current_price", Column::F64(p.iter().map(|p| p.current_price).collect())),
+            ("market_value", Column::F64(p.iter().map(|p| p.market_value).collect())),
+        ],
+    }
+}
+
+/// 资金曲线和回撤
+pub fn equity_table(result: &BacktestResult) -> Table {
+    let times: Vec<i64> = result.equity.iter().map(|e| e.0).collect();
+    let balances: Vec<f64> = result.equity.iter().map(|e| e.1).collect();
+    Table {
+        name: "equity",
+        columns: vec![
+            ("date", dates(&times)),
+            ("time", Column::I64(times)),
+            ("drawdown", Column::F64(drawdown_series(&balances))),
+            ("balance", Column::F64(balances)),
+        ],
+    }
+}
+
+/// 把交割单、委托、每日持仓和资金曲线按给定格式写入目录，返回写出的文件
+pub fn export_result(result: &BacktestResult, dir: &Path, formats: &[ExportFormat]) -> Result<Vec<PathBuf>, ExportError> {
+    fs::create_dir_all(dir)?;
+    let tables = [
+        transactions_table(result),
+        orders_table(result),
+        positions_table(result),
+        equity_table(result),
+    ];
+    let mut files = Vec::new();
+    for format in formats {
+        for table in &tables {
+            files.push(table.write(dir, *format)?);
+        }
+    }
+    Ok(files)
+}
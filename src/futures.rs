@@ -0,0 +1,431 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::account::Account;
+use crate::data::bar_date;
+
+/// 期货手续费：按成交金额比例加每手固定金额
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FuturesFee {
+    pub ratio: f64,
+    pub per_lot: f64,
+}
+
+impl FuturesFee {
+    pub fn calc(&self, turnover: f64, volume: i32) -> f64 {
+        turnover * self.ratio + volume as f64 * self.per_lot
+    }
+}
+
+/// 期货合约
+///
+/// ```toml
+/// [[futures]]
+/// code = "IF2406"
+/// multiplier = 300
+/// margin_rate = 0.12
+/// tick_size = 0.2
+/// open_fee = { ratio = 0.000023 }
+/// close_fee = { ratio = 0.000023 }
+/// close_today_fee = { ratio = 0.00023 }
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FuturesContract {
+    pub code: String,
+    /// 合约乘数，每点价值
+    pub multiplier: f64,
+    /// 保证金比例
+    pub margin_rate: f64,
+    /// 最小变动价位
+    pub tick_size: f64,
+    /// 开仓手续费
+    #[serde(default)]
+    pub open_fee: FuturesFee,
+    /// 平昨仓手续费
+    #[serde(default)]
+    pub close_fee: FuturesFee,
+    /// 平今仓手续费
+    #[serde(default)]
+    pub close_today_fee: FuturesFee,
+}
+
+impl FuturesContract {
+    /// 中金所股指期货：IF、IH 每点 300 元，IC、IM 每点 200 元，保证金 12%，最小变动 0.2 点，
+    /// 开仓和平昨万分之 0.23，平今万分之 2.3。其他代码返回 None
+    pub fn index_future(code: &str) -> Option<Self> {
+        let multiplier = match code.get(..2)? {
+            "IF" | "IH" => 300.0,
+            "IC" | "IM" => 200.0,
+            _ => return None,
+        };
+        let fee = |ratio| FuturesFee { ratio, per_lot: 0.0 };
+        Some(Self {
+            code: code.to_string(),
+            multiplier,
+            margin_rate: 0.12,
+            tick_size: 0.2,
+            open_fee: fee(0.000023),
+            close_fee: fee(0.000023),
+            close_today_fee: fee(0.00023),
+        })
+    }
+
+    /// 取最近的最小变动价位
+    pub fn round_price(&self, price: f64) -> f64 {
+        if self.tick_size > 0.0 { (price / self.tick_size).round() * self.tick_size } else { price }
+    }
+
+    /// 合约价值
+    pub fn notional(&self, price: f64, volume: i32) -> f64 {
+        price * self.multiplier * volume as f64
+    }
+
+    /// 检查取值，返回出错的字段名和原因
+    pub fn validate(&self) -> Result<(), (&'static str, String)> {
+        if self.code.is_empty() || self.code.len() > 8 || !self.code.is_ascii() {
+            return Err(("code", format!("无效的合约代码 {:?}", self.code)));
+        }
+        for (key, value) in [("multiplier", self.multiplier), ("tick_size", self.tick_size)] {
+            if value.is_nan() || value <= 0.0 {
+                return Err((key, "必须大于 0".to_string()));
+            }
+        }
+        if !(self.margin_rate > 0.0 && self.margin_rate <= 1.0) {
+            return Err(("margin_rate", format!("{} 超出范围 (0, 1]", self.margin_rate)));
+        }
+        Ok(())
+    }
+}
+
+/// 持仓方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Long,
+    Short,
+}
+
+impl Side {
+    fn sign(self) -> f64 {
+        match self {
+            Side::Long => 1.0,
+            Side::Short => -1.0,
+        }
+    }
+}
+
+/// 开平标志
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Offset {
+    Open,
+    Close,
+}
+
+/// 期货委托，按给定价格全部成交
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuturesOrder {
+    pub code: String,
+    pub time: i64,
+    pub price: f64,
+    /// 手数
+    pub volume: i32,
+    pub side: Side,
+    pub offset: Offset,
+}
+
+/// 期货成交记录
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuturesTrade {
+    pub time: i64,
+    pub code: String,
+    pub side: Side,
+    pub offset: Offset,
+    pub price: f64,
+    pub volume: i32,
+    pub fee: f64,
+    /// 平仓盈亏（相对开仓均价，逐笔对冲），开仓为 0
+    pub pnl: f64,
+}
+
+/// 单方向持仓
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FuturesLeg {
+    /// 手数
+    pub volume: i32,
+    /// 其中今日开仓的手数
+    pub today: i32,
+    /// 开仓均价
+    pub open_price: f64,
+    /// 盯市基准价：上次结算价，今日开仓部分取开仓价
+    pub basis: f64,
+    /// 最新价
+    pub last_price: f64,
+    /// 占用保证金
+    pub margin: f64,
+}
+
+/// 一个合约的多空持仓
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FuturesPosition {
+    pub long: FuturesLeg,
+    pub short: FuturesLeg,
+}
+
+impl FuturesPosition {
+    pub fn leg(&self, side: Side) -> &FuturesLeg {
+        match side {
+            Side::Long => &self.long,
+            Side::Short => &self.short,
+        }
+    }
+
+    fn leg_mut(&mut self, side: Side) -> &mut FuturesLeg {
+        match side {
+            Side::Long => &mut self.long,
+            Side::Short => &mut self.short,
+        }
+    }
+}
+
+/// 每日结算记录
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settlement {
+    pub time: i64,
+    pub code: String,
+    /// 结算价
+    pub price: f64,
+    /// 当日盯市盈亏
+    pub pnl: f64,
+    /// 结算后的可用资金，为负表示保证金不足
+    pub available: f64,
+}
+
+/// 期货账户：设置到 `Account::futures` 后与股票共用可用资金，保证金从可用资金中划出，
+/// 每个交易日按结算价逐日盯市
+#[derive(Debug, Clone, Default)]
+pub struct FuturesAccount {
+    pub contracts: HashMap<String, FuturesContract>,
+    pub positions: HashMap<String, FuturesPosition>,
+    pub trades: Vec<FuturesTrade>,
+    pub settlements: Vec<Settlement>,
+    /// 最近一次行情的交易日和时间
+    last: Option<(NaiveDate, i64)>,
+    /// 每个合约最近一次结算的交易日
+    settled: HashMap<String, NaiveDate>,
+}
+
+impl FuturesAccount {
+    pub fn new(contracts: impl IntoIterator<Item = FuturesContract>) -> Self {
+        Self {
+            contracts: contracts.into_iter().map(|c| (c.code.clone(), c)).collect(),
+            ..Default::default()
+        }
+    }
+
+    pub fn position(&self, code: &str) -> Option<&FuturesPosition> {
+        self.positions.get(code)
+    }
+
+    /// 占用保证金合计
+    pub fn margin(&self) -> f64 {
+        self.positions.values().map(|p| p.long.margin + p.short.margin).sum()
+    }
+
+    /// 相对盯市基准价的浮动盈亏合计
+    pub fn unrealized(&self) -> f64 {
+        let leg_pnl = |leg: &FuturesLeg, side: Side, multiplier: f64| {
+            (leg.last_price - leg.basis) * multiplier * leg.volume as f64 * side.sign()
+        };
+        self.positions
+            .iter()
+            .map(|(code, p)| {
+                let multiplier = self.contracts[code].multiplier;
+                leg_pnl(&p.long, Side::Long, multiplier) + leg_pnl(&p.short, Side::Short, multiplier)
+            })
+            .sum()
+    }
+
+    /// 计入账户净资产的部分：占用保证金加浮动盈亏
+    pub fn value(&self) -> f64 {
+        self.margin() + self.unrealized()
+    }
+
+    /// 开仓：保证金和手续费从可用资金扣除，资金不足或合约未知时拒绝
+    fn open(&mut self, cash: &mut f64, order: &FuturesOrder) -> bool {
+        let Some(contract) = self.contracts.get(&order.code) else { return false };
+        if order.volume <= 0 {
+            return false;
+        }
+        let price = contract.round_price(order.price);
+        let notional = contract.notional(price, order.volume);
+        let margin = notional * contract.margin_rate;
+        let fee = contract.open_fee.calc(notional, order.volume);
+        if *cash < margin + fee {
+            return false;
+        }
+        *cash -= margin + fee;
+
+        let leg = self.positions.entry(order.code.clone()).or_default().leg_mut(order.side);
+        let total = (leg.volume + order.volume) as f64;
+        leg.open_price = (leg.open_price * leg.volume as f64 + price * order.volume as f64) / total;
+        leg.basis = (leg.basis * leg.volume as f64 + price * order.volume as f64) / total;
+        leg.volume += order.volume;
+        leg.today += order.volume;
+        leg.margin += margin;
+        leg.last_price = price;
+        self.trades.push(FuturesTrade {
+            time: order.time,
+            code: order.code.clone(),
+            side: order.side,
+            offset: Offset::Open,
+            price,
+            volume: order.volume,
+            fee,
+            pnl: 0.0,
+        });
+        true
+    }
+
+    /// 平仓：先平昨仓再平今仓，按盯市基准价结算盈亏并释放保证金
+    fn close(&mut self, cash: &mut f64, order: &FuturesOrder) -> bool {
+        let Some(contract) = self.contracts.get(&order.code) else { return false };
+        let Some(position) = self.positions.get_mut(&order.code) else { return false };
+        let leg = position.leg_mut(order.side);
+        if order.volume <= 0 || order.volume > leg.volume {
+            return false;
+        }
+        let price = contract.round_price(order.price);
+        let old = order.volume.min(leg.volume - leg.today);
+        let today = order.volume - old;
+        let fee = contract.close_fee.calc(contract.notional(price, old), old)
+            + contract.close_today_fee.calc(contract.notional(price, today), today);
+        let sign = order.side.sign();
+        let settle_pnl = (price - leg.basis) * contract.multiplier * order.volume as f64 * sign;
+        let released = leg.margin * order.volume as f64 / leg.volume as f64;
+        *cash += settle_pnl + released - fee;
+
+        let pnl = (price - leg.open_price) * contract.multiplier * order.volume as f64 * sign;
+        leg.volume -= order.volume;
+        leg.today -= today;
+        leg.margin -= released;
+        leg.last_price = price;
+        if leg.volume == 0 {
+            *leg = FuturesLeg::default();
+        }
+        if position.long.volume == 0 && position.short.volume == 0 {
+            self.positions.remove(&order.code);
+        }
+        self.trades.push(FuturesTrade {
+            time: order.time,
+            code: order.code.clone(),
+            side: order.side,
+            offset: Offset::Close,
+            price,
+            volume: order.volume,
+            fee,
+            pnl,
+        });
+        true
+    }
+
+    /// 按结算价盯市：盈亏划入可用资金，按结算价重算保证金，今仓转为昨仓
+    fn settle(&mut self, cash: &mut f64, code: &str, price: f64, time: i64) {
+        let Some(contract) = self.contracts.get(code) else { return };
+        let Some(position) = self.positions.get_mut(code) else { return };
+        let mut pnl = 0.0;
+        for side in [Side::Long, Side::Short] {
+            let leg = position.leg_mut(side);
+            if leg.volume == 0 {
+                continue;
+            }
+            let leg_pnl = (price - leg.basis) * contract.multiplier * leg.volume as f64 * side.sign();
+            let margin = contract.notional(price, leg.volume) * contract.margin_rate;
+            *cash += leg_pnl + leg.margin - margin;
+            pnl += leg_pnl;
+            leg.basis = price;
+            leg.last_price = price;
+            leg.margin = margin;
+            leg.today = 0;
+        }
+        self.settled.insert(code.to_string(), bar_date(time));
+        self.settlements.push(Settlement {
+            time,
+            code: code.to_string(),
+            price,
+            pnl,
+            available: *cash,
+        });
+    }
+
+    /// 进入新的交易日时，把上一交易日未结算的合约按最新价结算
+    fn roll_day(&mut self, cash: &mut f64, time: i64) {
+        let day = bar_date(time);
+        if self.last.is_some_and(|(last_day, _)| last_day != day) {
+            self.settle_pending(cash);
+        }
+        self.last = Some((day, time));
+    }
+
+    /// 把最近一个交易日还未结算的合约按最新价结算
+    fn settle_pending(&mut self, cash: &mut f64) {
+        let Some((last_day, last_time)) = self.last else { return };
+        let mut pending: Vec<(String, f64)> = self
+            .positions
+            .iter()
+            .filter(|(code, _)| self.settled.get(*code) != Some(&last_day))
+            .map(|(code, p)| {
+                let leg = if p.long.volume > 0 { &p.long } else { &p.short };
+                (code.clone(), leg.last_price)
+            })
+            .collect();
+        pending.sort_by(|a, b| a.0.cmp(&b.0));
+        for (code, price) in pending {
+            self.settle(cash, &code, price, last_time);
+        }
+    }
+
+    /// 更新合约最新价
+    pub(crate) fn on_price_change(&mut self, code: &str, price: f64) -> bool {
+        let Some(position) = self.positions.get_mut(code) else { return false };
+        position.long.last_price = price;
+        position.short.last_price = price;
+        true
+    }
+}
+
+/// 提交期货委托，非期货账户或资金、持仓不足时返回 false；成交后刷新总资产
+pub fn submit(account: &mut Account, order: &FuturesOrder) -> bool {
+    let Some(futures) = account.futures.as_mut() else { return false };
+    let cash = &mut account.available_balance;
+    let filled = match order.offset {
+        Offset::Open => futures.open(cash, order),
+        Offset::Close => futures.close(cash, order),
+    };
+    if filled {
+        account.balance = account.net_assets();
+    }
+    filled
+}
+
+/// 用交易所公布的结算价结算一个合约，当日不再按最新价结算
+pub fn settle(account: &mut Account, code: &str, price: f64, time: i64) {
+    let Some(futures) = account.futures.as_mut() else { return };
+    futures.settle(&mut account.available_balance, code, price, time);
+    account.balance = account.net_assets();
+}
+
+/// 每根 K 线开始时调用，跨交易日时完成上一交易日的结算
+pub fn roll_day(account: &mut Account, time: i64) {
+    let Some(futures) = account.futures.as_mut() else { return };
+    futures.roll_day(&mut account.available_balance, time);
+}
+
+/// 回测结束时调用，按最新价结算最后一个交易日还未结算的合约
+pub fn finish(account: &mut Account) {
+    let Some(futures) = account.futures.as_mut() else { return };
+    futures.settle_pending(&mut account.available_balance);
+    account.balance = account.net_assets();
+}
@@ -0,0 +1,300 @@
+use std::collections::VecDeque;
+
+use crate::model::KLine;
+
+/// 固定长度的滑动窗口
+#[derive(Debug, Clone)]
+struct Window {
+    period: usize,
+    values: VecDeque<f64>,
+}
+
+impl Window {
+    fn new(period: usize) -> Self {
+        let period = period.max(1);
+        Self {
+            period,
+            values: VecDeque::with_capacity(period + 1),
+        }
+    }
+
+    /// 加入新值，返回被移出窗口的旧值
+    fn push(&mut self, value: f64) -> Option<f64> {
+        self.values.push_back(value);
+        if self.values.len() > self.period {
+            self.values.pop_front()
+        } else {
+            None
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        self.values.len() == self.period
+    }
+}
+
+/// 简单移动平均
+#[derive(Debug, Clone)]
+pub struct Sma {
+    window: Window,
+    sum: f64,
+}
+
+impl Sma {
+    pub fn new(period: usize) -> Self {
+        Self {
+            window: Window::new(period),
+            sum: 0.0,
+        }
+    }
+
+    /// 加入新值，满 period 个之后返回均值
+    pub fn update(&mut self, value: f64) -> Option<f64> {
+        self.sum += value;
+        if let Some(old) = self.window.push(value) {
+            self.sum -= old;
+        }
+        self.window.is_full().then(|| self.sum / self.window.period as f64)
+    }
+}
+
+/// 指数移动平均，alpha = 2 / (period + 1)，以前 period 个值的简单平均为初值
+#[derive(Debug, Clone)]
+pub struct Ema {
+    period: usize,
+    alpha: f64,
+    seed: Sma,
+    value: Option<f64>,
+}
+
+impl Ema {
+    pub fn new(period: usize) -> Self {
+        let period = period.max(1);
+        Self {
+            period,
+            alpha: 2.0 / (period as f64 + 1.0),
+            seed: Sma::new(period),
+            value: None,
+        }
+    }
+
+    pub fn period(&self) -> usize {
+        self.period
+    }
+
+    pub fn update(&mut self, value: f64) -> Option<f64> {
+        self.value = match self.value {
+            Some(prev) => Some(prev + self.alpha * (value - prev)),
+            None => self.seed.update(value),
+        };
+        self.value
+    }
+}
+
+/// 总体标准差
+#[derive(Debug, Clone)]
+pub struct StdDev {
+    window: Window,
+    sum: f64,
+    sum_sq: f64,
+}
+
+impl StdDev {
+    pub fn new(period: usize) -> Self {
+        Self {
+            window: Window::new(period),
+            sum: 0.0,
+            sum_sq: 0.0,
+        }
+    }
+
+    pub fn update(&mut self, value: f64) -> Option<f64> {
+        self.sum += value;
+        self.sum_sq += value * value;
+        if let Some(old) = self.window.push(value) {
+            self.sum -= old;
+            self.sum_sq -= old * old;
+        }
+        if !self.window.is_full() {
+            return None;
+        }
+        let n = self.window.period as f64;
+        let mean = self.sum / n;
+        Some((self.sum_sq / n - mean * mean).max(0.0).sqrt())
+    }
+}
+
+/// 布林带的一个值
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Band {
+    pub lower: f64,
+    pub middle: f64,
+    pub upper: f64,
+}
+
+/// 布林带：中轨为简单移动平均，上下轨为中轨 ± width 倍标准差
+#[derive(Debug, Clone)]
+pub struct Bollinger {
+    sma: Sma,
+    std: StdDev,
+    width: f64,
+}
+
+impl Bollinger {
+    pub fn new(period: usize, width: f64) -> Self {
+        Self {
+            sma: Sma::new(period),
+            std: StdDev::new(period),
+            width,
+        }
+    }
+
+    pub fn update(&mut self, value: f64) -> Option<Band> {
+        let middle = self.sma.update(value);
+        let std = self.std.update(value);
+        let (middle, std) = (middle?, std?);
+        Some(Band {
+            lower: middle - self.width * std,
+            middle,
+            upper: middle + self.width * std,
+        })
+    }
+}
+
+/// 相对强弱指标，Wilder 平滑，取值 0-100
+#[derive(Debug, Clone)]
+pub struct Rsi {
+    period: usize,
+    prev: Option<f64>,
+    count: usize,
+    avg_gain: f64,
+    avg_loss: f64,
+}
+
+impl Rsi {
+    pub fn new(period: usize) -> Self {
+        Self {
+            period: period.max(1),
+            prev: None,
+            count: 0,
+            avg_gain: 0.0,
+            avg_loss: 0.0,
+        }
+    }
+
+    /// 需要 period + 1 个值才有结果
+    pub fn update(&mut self, value: f64) -> Option<f64> {
+        let prev = self.prev.replace(value)?;
+        let change = value - prev;
+        let (gain, loss) = (change.max(0.0), (-change).max(0.0));
+        let n = self.period as f64;
+        self.count += 1;
+        if self.count <= self.period {
+            // 前 period 个变化取简单平均
+            self.avg_gain += gain / n;
+            self.avg_loss += loss / n;
+            if self.count < self.period {
+                return None;
+            }
+        } else {
+            self.avg_gain = (self.avg_gain * (n - 1.0) + gain) / n;
+            self.avg_loss = (self.avg_loss * (n - 1.0) + loss) / n;
+        }
+        Some(if self.avg_loss == 0.0 {
+            if self.avg_gain == 0.0 { 50.0 } else { 100.0 }
+        } else {
+            100.0 - 100.0 / (1.0 + self.avg_gain / self.avg_loss)
+        })
+    }
+}
+
+/// 真实波幅的 Wilder 平均
+#[derive(Debug, Clone)]
+pub struct Atr {
+    period: usize,
+    prev_close: Option<f64>,
+    count: usize,
+    value: f64,
+}
+
+impl Atr {
+    pub fn new(period: usize) -> Self {
+        Self {
+            period: period.max(1),
+            prev_close: None,
+            count: 0,
+            value: 0.0,
+        }
+    }
+
+    /// 当前值，不足 period 根时为 None
+    pub fn value(&self) -> Option<f64> {
+        (self.count >= self.period).then_some(self.value)
+    }
+
+    pub fn update(&mut self, bar: &KLine) -> Option<f64> {
+        let range = match self.prev_close.replace(bar.close) {
+            Some(pc) => (bar.high - bar.low).max((bar.high - pc).abs()).max((bar.low - pc).abs()),
+            None => bar.high - bar.low,
+        };
+        let n = self.period as f64;
+        self.count += 1;
+        if self.count <= self.period {
+            self.value += range / n;
+        } else {
+            self.value = (self.value * (n - 1.0) + range) / n;
+        }
+        self.value()
+    }
+}
+
+/// 最近 period 个值的最高值
+#[derive(Debug, Clone)]
+pub struct Highest {
+    window: Window,
+}
+
+impl Highest {
+    pub fn new(period: usize) -> Self {
+        Self {
+            window: Window::new(period),
+        }
+    }
+
+    pub fn update(&mut self, value: f64) -> Option<f64> {
+        self.window.push(value);
+        self.value()
+    }
+
+    /// 当前窗口的最高值，未满时为 None
+    pub fn value(&self) -> Option<f64> {
+        self.window
+            .is_full()
+            .then(|| self.window.values.iter().copied().fold(f64::NEG_INFINITY, f64::max))
+    }
+}
+
+/// 最近 period 个值的最低值
+#[derive(Debug, Clone)]
+pub struct Lowest {
+    window: Window,
+}
+
+impl Lowest {
+    pub fn new(period: usize) -> Self {
+        Self {
+            window: Window::new(period),
+        }
+    }
+
+    pub fn update(&mut self, value: f64) -> Option<f64> {
+        self.window.push(value);
+        self.value()
+    }
+
+    pub fn value(&self) -> Option<f64> {
+        self.window
+            .is_full()
+            .then(|| self.window.values.iter().copied().fold(f64::INFINITY, f64::min))
+    }
+}
@@ -1,3 +1,19 @@
 pub mod account;
+pub mod analysis;
+pub mod cli;
+pub mod config;
+pub mod data;
+pub mod engine;
+pub mod export;
+pub mod futures;
+pub mod indicator;
+pub mod margin;
+pub mod metrics;
+pub mod optimize;
+pub mod protect;
+pub mod replay;
+pub mod report;
+pub mod risk;
+pub mod sizing;
 pub mod strategy;
-pub mod model;
\ No newline at end of file
+pub mod model;
@@ -1,7 +1,4 @@
 #![windows_subsystem = "windows"]
-mod account;
-mod model;
-mod strategy;
 mod ui;
 
 fn main() -> Result<(), eframe::Error> {
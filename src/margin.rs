@@ -0,0 +1,374 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::account::{Account, Order, OrderRecord, StockCode, Transaction};
+use crate::data::bar_date;
+
+/// 交割单上融券卖出的交易类型
+pub const SHORT_SELL: char = 'Q';
+/// 交割单上买券还券的交易类型
+pub const BUY_TO_COVER: char = 'H';
+
+/// 融资融券设置
+///
+/// ```toml
+/// [margin]
+/// cash_rate = 0.06
+/// stock_rate = 0.08
+/// margin_ratio = 1.0
+/// maintenance_ratio = 1.3
+/// grace_days = 2
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MarginConfig {
+    /// 融资年利率，按自然日计息
+    pub cash_rate: f64,
+    /// 融券年费率，按融券市值自然日计息
+    pub stock_rate: f64,
+    /// 保证金比例：融资负债加融券市值不超过净资产 / 保证金比例
+    pub margin_ratio: f64,
+    /// 维持担保比例下限，收盘低于时追加担保
+    pub maintenance_ratio: f64,
+    /// 追保期限（交易日），期满仍低于下限时强制平仓，0 表示立即平仓
+    pub grace_days: u32,
+}
+
+impl Default for MarginConfig {
+    fn default() -> Self {
+        Self {
+            cash_rate: 0.06,
+            stock_rate: 0.08,
+            margin_ratio: 1.0,
+            maintenance_ratio: 1.3,
+            grace_days: 2,
+        }
+    }
+}
+
+impl MarginConfig {
+    /// 检查取值，返回出错的字段名和原因
+    pub fn validate(&self) -> Result<(), (&'static str, String)> {
+        for (key, value) in [("cash_rate", self.cash_rate), ("stock_rate", self.stock_rate)] {
+            if !(0.0..1.0).contains(&value) {
+                return Err((key, format!("利率 {} 超出范围 [0, 1)", value)));
+            }
+        }
+        if self.margin_ratio.is_nan() || self.margin_ratio <= 0.0 {
+            return Err(("margin_ratio", "必须大于 0".to_string()));
+        }
+        if self.maintenance_ratio.is_nan() || self.maintenance_ratio <= 1.0 {
+            return Err(("maintenance_ratio", "必须大于 1".to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// 融券负债
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ShortPosition {
+    /// 未归还的股数
+    pub volume: i32,
+    /// 融券卖出所得（扣除费用），只能用于买券还券
+    pub proceeds: f64,
+    /// 最新价格
+    pub price: f64,
+}
+
+impl ShortPosition {
+    pub fn market_value(&self) -> f64 {
+        self.volume as f64 * self.price
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarginEventKind {
+    /// 维持担保比例低于下限，追加担保
+    Call,
+    /// 追保期满强制平仓
+    Liquidation,
+    /// 维持担保比例恢复
+    Restored,
+}
+
+/// 追保、平仓记录
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarginEvent {
+    pub time: i64,
+    pub kind: MarginEventKind,
+    /// 事件发生后的维持担保比例
+    pub ratio: f64,
+}
+
+/// 信用账户：设置到 `Account::margin` 后，买入资金不足时融资，卖出超过可卖数量时融券
+///
+/// 卖出所得优先偿还利息和融资负债，买入时优先买券还券。
+#[derive(Debug, Clone, Default)]
+pub struct MarginAccount {
+    pub config: MarginConfig,
+    /// 融资负债
+    pub cash_debt: f64,
+    /// 未付利息（融资利息和融券费用）
+    pub interest: f64,
+    /// 融券负债
+    pub shorts: HashMap<StockCode, ShortPosition>,
+    pub log: Vec<MarginEvent>,
+    /// 上次收盘的交易日和按当时负债计算的每日利息
+    last_close: Option<(NaiveDate, f64)>,
+    /// 追保以来经过的交易日
+    call_days: Option<u32>,
+}
+
+impl MarginAccount {
+    pub fn new(config: MarginConfig) -> Self {
+        Self {
+            config,
+            ..Default::default()
+        }
+    }
+
+    /// 融券市值
+    pub fn short_value(&self) -> f64 {
+        self.shorts.values().map(ShortPosition::market_value).sum()
+    }
+
+    pub fn short_volume(&self, code: &str) -> i32 {
+        self.shorts.get(&StockCode::from(code)).map(|s| s.volume).unwrap_or(0)
+    }
+
+    /// 总负债 = 融资负债 + 未付利息 + 融券市值
+    pub fn liabilities(&self) -> f64 {
+        self.cash_debt + self.interest + self.short_value()
+    }
+
+    /// 计入账户净资产的部分：融券卖出所得减去总负债
+    pub fn net_value(&self) -> f64 {
+        self.shorts.values().map(|s| s.proceeds).sum::<f64>() - self.liabilities()
+    }
+
+    /// 担保资产 = 现金 + 持仓市值 + 融券卖出所得
+    pub fn collateral(&self, account: &Account) -> f64 {
+        let proceeds: f64 = self.shorts.values().map(|s| s.proceeds).sum();
+        account.available_balance + account.freeze_balance + account.market_value_with(&[]) + proceeds
+    }
+
+    /// 维持担保比例 = 担保资产 / 总负债，没有负债时为无穷大
+    pub fn ratio(&self, account: &Account) -> f64 {
+        let liabilities = self.liabilities();
+        if liabilities > 0.0 { self.collateral(account) / liabilities } else { f64::INFINITY }
+    }
+
+    /// 按保证金比例还能借入的金额
+    pub fn capacity(&self, account: &Account) -> f64 {
+        let net = self.collateral(account) - self.liabilities();
+        (net / self.config.margin_ratio - self.cash_debt - self.short_value()).max(0.0)
+    }
+
+    /// 买入：先买券还券，其余买入持仓，资金不足的部分融资；买入持仓失败时撤销融资和买券还券
+    pub(crate) fn buy(&mut self, account: &mut Account, order: &Order) -> bool {
+        if order.volume <= 0 {
+            return false;
+        }
+        let short = self.shorts.get(&order.code).cloned().unwrap_or_default();
+        let cover = order.volume.min(short.volume);
+        let long = order.volume - cover;
+        let cost = |volume: i32| {
+            let turnover = order.price * volume as f64;
+            if volume > 0 { turnover + account.fee.calc(turnover, 'B') } else { 0.0 }
+        };
+        let (cover_cost, long_cost) = (cost(cover), cost(long));
+        let released = if cover > 0 { short.proceeds * cover as f64 / short.volume as f64 } else { 0.0 };
+
+        // 融资按分向上取整，避免浮点误差导致资金检查失败
+        let shortfall = cover_cost + long_cost - released - account.available_balance;
+        let borrow = if shortfall > 0.0 { (shortfall * 100.0).ceil() / 100.0 } else { 0.0 };
+        if borrow > self.capacity(account) {
+            return false;
+        }
+        let (debt, cash, recorded) = (self.cash_debt, account.available_balance, account.transactions.len());
+        self.cash_debt += borrow;
+        account.available_balance += borrow;
+
+        if cover > 0 {
+            account.available_balance += released - cover_cost;
+            let remain = short.volume - cover;
+            if remain > 0 {
+                let s = self.shorts.get_mut(&order.code).expect("融券负债存在");
+                s.volume = remain;
+                s.proceeds -= released;
+            } else {
+                self.shorts.remove(&order.code);
+            }
+            let held = account.hold.get(&order.code).map(|p| p.volume).unwrap_or(0);
+            account.transactions.push(Transaction {
+                code: order.code.clone(),
+                time: order.time,
+                price: order.price,
+                volume: cover,
+                order_type: BUY_TO_COVER,
+                remain_vol: held - remain,
+                remain_cost: if remain > 0 { (short.proceeds - released) / remain as f64 } else { 0.0 },
+                fee: cover_cost - order.price * cover as f64,
+            });
+        }
+        if long > 0 && !account.fill_buy(&Order { volume: long, ..order.clone() }) {
+            self.cash_debt = debt;
+            account.available_balance = cash;
+            if cover > 0 {
+                self.shorts.insert(order.code.clone(), short);
+            }
+            account.transactions.truncate(recorded);
+            return false;
+        }
+        true
+    }
+
+    /// 卖出：可卖部分卖出持仓并偿还负债，超出部分融券卖出
+    pub(crate) fn sell(&mut self, account: &mut Account, order: &Order) -> bool {
+        if order.volume <= 0 {
+            return false;
+        }
+        let available = account.hold.get(&order.code).map(|p| p.available_vol).unwrap_or(0);
+        let long = order.volume.min(available);
+        let short = order.volume - long;
+        // 融券卖出的市值和费用都不能超过可融额度
+        let turnover = order.price * short as f64;
+        let fee = if short > 0 { account.fee.calc(turnover, 'S') } else { 0.0 };
+        if short > 0 && turnover + fee > self.capacity(account) {
+            return false;
+        }
+
+        if long > 0 {
+            let before = account.available_balance;
+            if !account.fill_sell(&Order { volume: long, ..order.clone() }) {
+                return false;
+            }
+            self.repay(account, account.available_balance - before);
+        }
+        if short > 0 {
+            let s = self.shorts.entry(order.code.clone()).or_default();
+            s.volume += short;
+            s.proceeds += turnover - fee;
+            s.price = order.price;
+            let (volume, avg) = (s.volume, s.proceeds / s.volume as f64);
+            let held = account.hold.get(&order.code).map(|p| p.volume).unwrap_or(0);
+            account.transactions.push(Transaction {
+                code: order.code.clone(),
+                time: order.time,
+                price: order.price,
+                volume: -short,
+                order_type: SHORT_SELL,
+                remain_vol: held - volume,
+                remain_cost: avg,
+                fee,
+            });
+        }
+        true
+    }
+
+    /// 用可用资金偿还，先还利息再还融资
+    fn repay(&mut self, account: &mut Account, amount: f64) {
+        let amount = amount.min(account.available_balance).max(0.0);
+        let interest = amount.min(self.interest);
+        let debt = (amount - interest).min(self.cash_debt);
+        self.interest -= interest;
+        self.cash_debt -= debt;
+        account.available_balance -= interest + debt;
+    }
+
+    /// 收盘结算：按自然日计息，检查维持担保比例，追保期满后强制平仓
+    fn settle(&mut self, account: &mut Account, time: i64) {
+        let day = bar_date(time);
+        let new_day = match self.last_close {
+            Some((last, daily)) if last != day => {
+                self.interest += daily * (day - last).num_days() as f64;
+                true
+            }
+            Some(_) => false,
+            None => true,
+        };
+
+        let ratio = self.ratio(account);
+        if ratio >= self.config.maintenance_ratio {
+            if self.call_days.take().is_some() {
+                self.log.push(MarginEvent { time, kind: MarginEventKind::Restored, ratio });
+            }
+        } else {
+            match &mut self.call_days {
+                None => {
+                    self.call_days = Some(0);
+                    self.log.push(MarginEvent { time, kind: MarginEventKind::Call, ratio });
+                }
+                Some(days) if new_day => *days += 1,
+                Some(_) => {}
+            }
+            if self.call_days.is_some_and(|d| d >= self.config.grace_days) {
+                self.liquidate(account, time);
+                let ratio = self.ratio(account);
+                self.log.push(MarginEvent { time, kind: MarginEventKind::Liquidation, ratio });
+                if ratio >= self.config.maintenance_ratio {
+                    self.call_days = None;
+                }
+            }
+        }
+
+        let daily = (self.cash_debt * self.config.cash_rate + self.short_value() * self.config.stock_rate) / 365.0;
+        self.last_close = Some((day, daily));
+    }
+
+    /// 按市值从大到小卖出可卖持仓、买券还券，直到维持担保比例恢复
+    fn liquidate(&mut self, account: &mut Account, time: i64) {
+        let mut orders: Vec<Order> = account
+            .hold
+            .values()
+            .filter(|p| p.available_vol > 0)
+            .map(|p| Order {
+                market_type: ' ',
+                code: p.code.clone(),
+                time,
+                price: p.mark_price(),
+                volume: p.available_vol,
+                order_type: 'S',
+            })
+            .collect();
+        orders.sort_by(|a, b| (b.price * b.volume as f64).total_cmp(&(a.price * a.volume as f64)));
+        let mut covers: Vec<Order> = self
+            .shorts
+            .iter()
+            .map(|(code, s)| Order {
+                market_type: ' ',
+                code: code.clone(),
+                time,
+                price: s.price,
+                volume: s.volume,
+                order_type: 'B',
+            })
+            .collect();
+        covers.sort_by(|a, b| (b.price * b.volume as f64).total_cmp(&(a.price * a.volume as f64)));
+        orders.extend(covers);
+
+        for order in orders {
+            if self.ratio(account) >= self.config.maintenance_ratio {
+                break;
+            }
+            let filled = match order.order_type {
+                'B' => self.buy(account, &order),
+                _ => self.sell(account, &order),
+            };
+            if filled {
+                account.on_price_change(order.code.as_str(), order.price);
+            }
+            account.orders.push(OrderRecord { order, filled });
+        }
+    }
+}
+
+/// 每根 K 线收盘后调用，非信用账户不做处理
+pub fn on_close(account: &mut Account, time: i64) {
+    let Some(mut margin) = account.margin.take() else { return };
+    margin.settle(account, time);
+    account.margin = Some(margin);
+    account.balance = account.net_assets();
+}
@@ -0,0 +1,189 @@
+use chrono::Datelike;
+
+use crate::data::bar_date;
+use crate::engine::BacktestResult;
+
+/// 每年交易日数，用于年化
+pub const TRADING_DAYS_PER_YEAR: f64 = 252.0;
+
+/// 回测绩效指标
+#[derive(Debug, Clone, Default)]
+pub struct Metrics {
+    /// 总收益率
+    pub total_return: f64,
+    /// 年化收益率
+    pub annual_return: f64,
+    /// 最大回撤 (正数，0.2 表示 20%)
+    pub max_drawdown: f64,
+    /// 年化波动率
+    pub volatility: f64,
+    /// 夏普比率 (无风险利率按 0)
+    pub sharpe: f64,
+    /// 成交笔数
+    pub trade_count: usize,
+    /// 期末总资产
+    pub final_balance: f64,
+}
+
+impl Metrics {
+    pub fn from_result(result: &BacktestResult) -> Self {
+        let balances: Vec<f64> = result.equity.iter().map(|e| e.1).collect();
+        let mut metrics = Self::from_equity(result.init_cash, &balances);
+        metrics.trade_count = result.account.transactions.len();
+        metrics
+    }
+
+    /// 由资金序列计算指标，序列按日频处理
+    pub fn from_equity(init_cash: f64, balances: &[f64]) -> Self {
+        let final_balance = balances.last().copied().unwrap_or(init_cash);
+        let total_return = if init_cash > 0.0 { final_balance / init_cash - 1.0 } else { 0.0 };
+        let years = balances.len() as f64 / TRADING_DAYS_PER_YEAR;
+        let annual_return = if years > 0.0 && total_return > -1.0 {
+            (1.0 + total_return).powf(1.0 / years) - 1.0
+        } else {
+            0.0
+        };
+
+        let returns = daily_returns(init_cash, balances);
+        let (mean, std) = mean_std(&returns);
+        let volatility = std * TRADING_DAYS_PER_YEAR.sqrt();
+        let sharpe = if std > 0.0 { mean / std * TRADING_DAYS_PER_YEAR.sqrt() } else { 0.0 };
+
+        Metrics {
+            total_return,
+            annual_return,
+            max_drawdown: max_drawdown(balances),
+            volatility,
+            sharpe,
+            trade_count: 0,
+            final_balance,
+        }
+    }
+}
+
+/// 逐期收益率，第一期相对初始资金
+pub fn daily_returns(init_cash: f64, balances: &[f64]) -> Vec<f64> {
+    let mut prev = init_cash;
+    balances
+        .iter()
+        .map(|&b| {
+            let r = if prev != 0.0 { b / prev - 1.0 } else { 0.0 };
+            prev = b;
+            r
+        })
+        .collect()
+}
+
+/// 最大回撤
+pub fn max_drawdown(balances: &[f64]) -> f64 {
+    let mut peak = f64::MIN;
+    let mut max_dd = 0.0_f64;
+    for &b in balances {
+        peak = peak.max(b);
+        if peak > 0.0 {
+            max_dd = max_dd.max((peak - b) / peak);
+        }
+    }
+    max_dd
+}
+
+/// 回撤序列，每点为相对历史最高点的跌幅 (负数或 0)
+pub fn drawdown_series(balances: &[f64]) -> Vec<f64> {
+    let mut peak = f64::MIN;
+    balances
+        .iter()
+        .map(|&b| {
+            peak = peak.max(b);
+            if peak > 0.0 { b / peak - 1.0 } else { 0.0 }
+        })
+        .collect()
+}
+
+/// 月度收益率 (年, 月, 收益率)，按北京时间划分月份
+pub fn monthly_returns(init_cash: f64, equity: &[(i64, f64)]) -> Vec<(i32, u32, f64)> {
+    let mut months: Vec<(i32, u32, f64)> = Vec::new();
+    // 当月期初资产，即上月最后一个点的资产
+    let mut start = init_cash;
+    let mut prev = init_cash;
+    for &(time, balance) in equity {
+        let date = bar_date(time);
+        let key = (date.year(), date.month());
+        if months.last().is_none_or(|m| (m.0, m.1) != key) {
+            start = prev;
+            months.push((key.0, key.1, 0.0));
+        }
+        if let Some(m) = months.last_mut() {
+            m.2 = if start != 0.0 { balance / start - 1.0 } else { 0.0 };
+        }
+        prev = balance;
+    }
+    months
+}
+
+/// 均值和样本标准差
+pub fn mean_std(values: &[f64]) -> (f64, f64) {
+    if values.is_empty() {
+        return (0.0, 0.0);
+    }
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    if values.len() < 2 {
+        return (mean, 0.0);
+    }
+    let var = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    (mean, var.sqrt())
+}
+
+/// 参数寻优时使用的目标指标
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    TotalReturn,
+    AnnualReturn,
+    MaxDrawdown,
+    Sharpe,
+}
+
+impl Metric {
+    pub const ALL: [Metric; 4] = [Metric::TotalReturn, Metric::AnnualReturn, Metric::MaxDrawdown, Metric::Sharpe];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Metric::TotalReturn => "总收益率",
+            Metric::AnnualReturn => "年化收益率",
+            Metric::MaxDrawdown => "最大回撤",
+            Metric::Sharpe => "夏普比率",
+        }
+    }
+
+    /// 配置和命令行中使用的英文键
+    pub fn key(&self) -> &'static str {
+        match self {
+            Metric::TotalReturn => "total_return",
+            Metric::AnnualReturn => "annual_return",
+            Metric::MaxDrawdown => "max_drawdown",
+            Metric::Sharpe => "sharpe",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Metric> {
+        Metric::ALL.into_iter().find(|m| m.key() == key)
+    }
+
+    /// 指标原值
+    pub fn value(&self, metrics: &Metrics) -> f64 {
+        match self {
+            Metric::TotalReturn => metrics.total_return,
+            Metric::AnnualReturn => metrics.annual_return,
+            Metric::MaxDrawdown => metrics.max_drawdown,
+            Metric::Sharpe => metrics.sharpe,
+        }
+    }
+
+    /// 打分，越大越好（回撤取负）
+    pub fn score(&self, metrics: &Metrics) -> f64 {
+        match self {
+            Metric::MaxDrawdown => -metrics.max_drawdown,
+            _ => self.value(metrics),
+        }
+    }
+}
@@ -44,3 +44,26 @@ pub struct TickData {
     pub bid5_volume: i32,
 }
 
+impl TickData {
+    /// 卖一到卖五 (价格, 数量)
+    pub fn asks(&self) -> [(f64, i32); 5] {
+        [
+            (self.ask1_price, self.ask1_volume),
+            (self.ask2_price, self.ask2_volume),
+            (self.ask3_price, self.ask3_volume),
+            (self.ask4_price, self.ask4_volume),
+            (self.ask5_price, self.ask5_volume),
+        ]
+    }
+
+    /// 买一到买五 (价格, 数量)
+    pub fn bids(&self) -> [(f64, i32); 5] {
+        [
+            (self.bid1_price, self.bid1_volume),
+            (self.bid2_price, self.bid2_volume),
+            (self.bid3_price, self.bid3_volume),
+            (self.bid4_price, self.bid4_volume),
+            (self.bid5_price, self.bid5_volume),
+        ]
+    }
+}
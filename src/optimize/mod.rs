@@ -0,0 +1,113 @@
+use std::collections::BTreeMap;
+
+pub mod search;
+pub mod walk_forward;
+
+/// 一组参数取值，参数名 -> 值
+pub type ParamSet = BTreeMap<String, f64>;
+
+/// 单个参数的取值范围 [start, end]，按 step 等距取值
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParamRange {
+    pub name: String,
+    pub start: f64,
+    pub end: f64,
+    pub step: f64,
+}
+
+impl ParamRange {
+    pub fn new(name: &str, start: f64, end: f64, step: f64) -> Self {
+        Self {
+            name: name.to_string(),
+            start,
+            end,
+            step,
+        }
+    }
+
+    /// 取值个数
+    pub fn count(&self) -> usize {
+        if self.step <= 0.0 || self.end < self.start {
+            return 1;
+        }
+        (((self.end - self.start) / self.step + 1e-9).floor() as usize).saturating_add(1)
+    }
+
+    /// 第 i 个取值
+    pub fn value(&self, i: usize) -> f64 {
+        self.start + self.step * i as f64
+    }
+
+    /// 所有取值
+    pub fn values(&self) -> Vec<f64> {
+        (0..self.count()).map(|i| self.value(i)).collect()
+    }
+}
+
+/// 参数空间，由多个参数范围组成
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParamSpace {
+    pub ranges: Vec<ParamRange>,
+}
+
+impl ParamSpace {
+    pub fn new(ranges: Vec<ParamRange>) -> Self {
+        Self { ranges }
+    }
+
+    /// 网格点总数，超出 usize 范围时返回 `usize::MAX`
+    pub fn grid_size(&self) -> usize {
+        self.ranges.iter().try_fold(1usize, |n, r| n.checked_mul(r.count())).unwrap_or(usize::MAX)
+    }
+
+    /// 枚举所有网格点
+    pub fn grid(&self) -> Vec<ParamSet> {
+        let mut sets = vec![ParamSet::new()];
+        for range in &self.ranges {
+            let mut next = Vec::with_capacity(sets.len() * range.count());
+            for set in &sets {
+                for v in range.values() {
+                    let mut s = set.clone();
+                    s.insert(range.name.clone(), v);
+                    next.push(s);
+                }
+            }
+            sets = next;
+        }
+        sets
+    }
+}
+
+/// 一个参数点的评估结果
+#[derive(Debug, Clone)]
+pub struct Evaluation {
+    pub params: ParamSet,
+    pub score: f64,
+}
+
+/// 网格搜索，按得分从高到低返回全部结果
+pub fn grid_search<F>(space: &ParamSpace, mut objective: F) -> Vec<Evaluation>
+where
+    F: FnMut(&ParamSet) -> f64,
+{
+    let mut evaluations: Vec<Evaluation> = space
+        .grid()
+        .into_iter()
+        .map(|params| {
+            let score = objective(&params);
+            Evaluation { params, score }
+        })
+        .collect();
+    sort_by_score(&mut evaluations);
+    evaluations
+}
+
+/// 按得分降序排序，NaN 排在最后
+pub fn sort_by_score(evaluations: &mut [Evaluation]) {
+    evaluations.sort_by(|a, b| match (a.score.is_nan(), b.score.is_nan()) {
+        (true, true) => std::cmp::Ordering::Equal,
+        (true, false) => std::cmp::Ordering::Greater,
+        (false, true) => std::cmp::Ordering::Less,
+        _ => b.score.partial_cmp(&a.score).unwrap(),
+    });
+}
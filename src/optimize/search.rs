@@ -0,0 +1,241 @@
+use std::collections::HashMap;
+
+use crate::optimize::{sort_by_score, Evaluation, ParamSet, ParamSpace};
+
+/// 可复现的伪随机数生成器 (SplitMix64)
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// [0, 1) 均匀分布
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// 标准正态分布 (Box-Muller)
+    pub fn next_normal(&mut self) -> f64 {
+        let u1 = self.next_f64().max(f64::MIN_POSITIVE);
+        let u2 = self.next_f64();
+        (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+    }
+
+    /// [0, n) 的整数
+    pub fn below(&mut self, n: usize) -> usize {
+        if n == 0 { 0 } else { (self.next_u64() % n as u64) as usize }
+    }
+}
+
+/// 随机搜索 / 遗传算法的评估记录
+#[derive(Debug, Clone, Default)]
+pub struct SearchResult {
+    /// 按评估顺序记录的所有参数点（重复点只评估一次）
+    pub evaluations: Vec<Evaluation>,
+    /// 得分最高的参数点
+    pub best: Option<Evaluation>,
+    /// 是否因连续无改进而提前结束
+    pub stopped_early: bool,
+}
+
+impl SearchResult {
+    /// 按得分从高到低排序的结果
+    pub fn ranked(&self) -> Vec<Evaluation> {
+        let mut evaluations = self.evaluations.clone();
+        sort_by_score(&mut evaluations);
+        evaluations
+    }
+}
+
+/// 随机搜索配置
+#[derive(Debug, Clone)]
+pub struct RandomSearchConfig {
+    /// 最多评估次数
+    pub max_evals: usize,
+    /// 连续多少次没有改进就停止，0 表示不提前停止
+    pub patience: usize,
+    pub seed: u64,
+}
+
+/// 遗传算法配置
+#[derive(Debug, Clone)]
+pub struct GeneticConfig {
+    /// 种群大小
+    pub population: usize,
+    /// 最多迭代代数
+    pub generations: usize,
+    /// 每个基因的变异概率
+    pub mutation_rate: f64,
+    /// 锦标赛选择的规模
+    pub tournament: usize,
+    /// 直接保留到下一代的最优个体数
+    pub elitism: usize,
+    /// 连续多少代最优得分没有改进就停止，0 表示不提前停止
+    pub patience: usize,
+    pub seed: u64,
+}
+
+impl Default for GeneticConfig {
+    fn default() -> Self {
+        Self {
+            population: 20,
+            generations: 30,
+            mutation_rate: 0.1,
+            tournament: 3,
+            elitism: 2,
+            patience: 5,
+            seed: 42,
+        }
+    }
+}
+
+/// 参数点在网格上的下标，每个参数一个
+type Genome = Vec<usize>;
+
+/// 带缓存的目标函数，记录评估日志
+struct Evaluator<'a, F> {
+    space: &'a ParamSpace,
+    objective: F,
+    cache: HashMap<Genome, f64>,
+    result: SearchResult,
+}
+
+impl<F: FnMut(&ParamSet) -> f64> Evaluator<'_, F> {
+    fn params(&self, genome: &Genome) -> ParamSet {
+        self.space
+            .ranges
+            .iter()
+            .zip(genome)
+            .map(|(r, &i)| (r.name.clone(), r.value(i)))
+            .collect()
+    }
+
+    /// 评估一个参数点，返回得分和是否刷新了最优值
+    fn eval(&mut self, genome: &Genome) -> (f64, bool) {
+        if let Some(&score) = self.cache.get(genome) {
+            return (score, false);
+        }
+        let params = self.params(genome);
+        let score = (self.objective)(&params);
+        self.cache.insert(genome.clone(), score);
+        let improved = match &self.result.best {
+            Some(best) => score > best.score,
+            None => !score.is_nan(),
+        };
+        let evaluation = Evaluation { params, score };
+        if improved {
+            self.result.best = Some(evaluation.clone());
+        }
+        self.result.evaluations.push(evaluation);
+        (score, improved)
+    }
+}
+
+fn random_genome(space: &ParamSpace, rng: &mut Rng) -> Genome {
+    space.ranges.iter().map(|r| rng.below(r.count())).collect()
+}
+
+/// 随机搜索：在参数网格上均匀抽样
+pub fn random_search<F>(space: &ParamSpace, config: &RandomSearchConfig, objective: F) -> SearchResult
+where
+    F: FnMut(&ParamSet) -> f64,
+{
+    let mut rng = Rng::new(config.seed);
+    let mut evaluator = Evaluator {
+        space,
+        objective,
+        cache: HashMap::new(),
+        result: SearchResult::default(),
+    };
+    let total = space.grid_size();
+    let mut since_improved = 0;
+    // 抽样次数上限，避免网格较小时在重复点上空转
+    let mut draws = 0;
+    while evaluator.cache.len() < config.max_evals.min(total) && draws < config.max_evals.saturating_mul(10) {
+        draws += 1;
+        let genome = random_genome(space, &mut rng);
+        if evaluator.cache.contains_key(&genome) {
+            continue;
+        }
+        let (_, improved) = evaluator.eval(&genome);
+        since_improved = if improved { 0 } else { since_improved + 1 };
+        if config.patience > 0 && since_improved >= config.patience {
+            evaluator.result.stopped_early = true;
+            break;
+        }
+    }
+    evaluator.result
+}
+
+/// 遗传算法：锦标赛选择、均匀交叉、按基因变异，保留精英
+pub fn genetic<F>(space: &ParamSpace, config: &GeneticConfig, objective: F) -> SearchResult
+where
+    F: FnMut(&ParamSet) -> f64,
+{
+    let mut rng = Rng::new(config.seed);
+    let mut evaluator = Evaluator {
+        space,
+        objective,
+        cache: HashMap::new(),
+        result: SearchResult::default(),
+    };
+    let size = config.population.max(2);
+    let mut population: Vec<(Genome, f64)> = (0..size)
+        .map(|_| {
+            let genome = random_genome(space, &mut rng);
+            let (score, _) = evaluator.eval(&genome);
+            (genome, score)
+        })
+        .collect();
+
+    let mut since_improved = 0;
+    for _ in 0..config.generations {
+        population.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        let mut next: Vec<(Genome, f64)> = population.iter().take(config.elitism.min(size)).cloned().collect();
+        let mut improved = false;
+        while next.len() < size {
+            let a = tournament(&population, config.tournament, &mut rng);
+            let b = tournament(&population, config.tournament, &mut rng);
+            let child: Genome = a
+                .iter()
+                .zip(b)
+                .zip(&space.ranges)
+                .map(|((&x, &y), range)| {
+                    let gene = if rng.next_f64() < 0.5 { x } else { y };
+                    if rng.next_f64() < config.mutation_rate { rng.below(range.count()) } else { gene }
+                })
+                .collect();
+            let (score, better) = evaluator.eval(&child);
+            improved |= better;
+            next.push((child, score));
+        }
+        population = next;
+        since_improved = if improved { 0 } else { since_improved + 1 };
+        if config.patience > 0 && since_improved >= config.patience {
+            evaluator.result.stopped_early = true;
+            break;
+        }
+    }
+    evaluator.result
+}
+
+fn tournament<'a>(population: &'a [(Genome, f64)], k: usize, rng: &mut Rng) -> &'a Genome {
+    let mut best = &population[rng.below(population.len())];
+    for _ in 1..k.max(1) {
+        let other = &population[rng.below(population.len())];
+        if other.1 > best.1 || best.1.is_nan() {
+            best = other;
+        }
+    }
+    &best.0
+}
@@ -0,0 +1,169 @@
+use crate::engine::BacktestResult;
+use crate::metrics::{mean_std, Metric, Metrics};
+use crate::model::KLine;
+use crate::optimize::{grid_search, ParamSet, ParamSpace};
+
+/// 滚动优化配置，窗口长度以 K 线根数计
+#[derive(Debug, Clone)]
+pub struct WalkForwardConfig {
+    /// 样本内窗口长度
+    pub in_sample: usize,
+    /// 样本外窗口长度，也是每次向前滚动的步长
+    pub out_of_sample: usize,
+    /// true: 锚定窗口，样本内始终从第一根开始；false: 滚动窗口
+    pub anchored: bool,
+    /// 样本内选参所用指标
+    pub metric: Metric,
+}
+
+/// 单个窗口的结果
+#[derive(Debug, Clone)]
+pub struct WindowResult {
+    /// 样本内 [开始, 结束] 时间
+    pub in_sample: (i64, i64),
+    /// 样本外 [开始, 结束] 时间
+    pub out_of_sample: (i64, i64),
+    /// 样本内选出的参数
+    pub params: ParamSet,
+    /// 样本内得分
+    pub in_sample_score: f64,
+    /// 样本外绩效
+    pub out_of_sample_metrics: Metrics,
+}
+
+/// 单个参数在各窗口间的稳定性
+#[derive(Debug, Clone)]
+pub struct ParamStability {
+    pub name: String,
+    pub mean: f64,
+    pub std_dev: f64,
+    pub min: f64,
+    pub max: f64,
+    /// 相邻窗口参数发生变化的次数
+    pub changes: usize,
+}
+
+/// 滚动优化结果
+#[derive(Debug, Clone)]
+pub struct WalkForwardResult {
+    pub windows: Vec<WindowResult>,
+    /// 拼接后的样本外资金曲线
+    pub equity: Vec<(i64, f64)>,
+    /// 拼接后的样本外绩效
+    pub metrics: Metrics,
+    pub stability: Vec<ParamStability>,
+}
+
+/// 滚动优化 (walk-forward)
+///
+/// 每个窗口在样本内做网格搜索选出得分最高的参数，再用该参数跑紧随其后的样本外窗口。
+/// 样本外窗口的期初资金承接上一个窗口的期末资产，拼成一条连续的资金曲线。
+/// `run` 用给定参数、K 线和初始资金执行一次回测，参数组合无效时返回 None，样本内得分记为 NaN，样本外无法回测时提前结束。
+pub fn walk_forward<F>(
+    bars: &[KLine],
+    space: &ParamSpace,
+    config: &WalkForwardConfig,
+    init_cash: f64,
+    run: F,
+) -> WalkForwardResult
+where
+    F: Fn(&ParamSet, &[KLine], f64) -> Option<BacktestResult>,
+{
+    let mut windows = Vec::new();
+    let mut equity = Vec::new();
+    let mut cash = init_cash;
+
+    if config.in_sample > 0 && config.out_of_sample > 0 {
+        let mut oos_start = config.in_sample;
+        while oos_start < bars.len() {
+            let is_start = if config.anchored { 0 } else { oos_start - config.in_sample };
+            let in_bars = &bars[is_start..oos_start];
+            let oos_end = (oos_start + config.out_of_sample).min(bars.len());
+            let out_bars = &bars[oos_start..oos_end];
+
+            let best = grid_search(space, |params| match run(params, in_bars, init_cash) {
+                Some(result) => config.metric.score(&Metrics::from_result(&result)),
+                None => f64::NAN,
+            })
+            .into_iter()
+            .next();
+            let Some(best) = best else { break };
+
+            let Some(result) = run(&best.params, out_bars, cash) else { break };
+            cash = result.final_balance();
+            equity.extend_from_slice(&result.equity);
+
+            windows.push(WindowResult {
+                in_sample: (in_bars[0].time, in_bars[in_bars.len() - 1].time),
+                out_of_sample: (out_bars[0].time, out_bars[out_bars.len() - 1].time),
+                params: best.params,
+                in_sample_score: best.score,
+                out_of_sample_metrics: Metrics::from_result(&result),
+            });
+            oos_start = oos_end;
+        }
+    }
+
+    let balances: Vec<f64> = equity.iter().map(|e| e.1).collect();
+    let mut metrics = Metrics::from_equity(init_cash, &balances);
+    metrics.trade_count = windows.iter().map(|w| w.out_of_sample_metrics.trade_count).sum();
+    let stability = param_stability(space, &windows);
+
+    WalkForwardResult {
+        windows,
+        equity,
+        metrics,
+        stability,
+    }
+}
+
+/// 统计各参数在窗口间的分布
+fn param_stability(space: &ParamSpace, windows: &[WindowResult]) -> Vec<ParamStability> {
+    space
+        .ranges
+        .iter()
+        .map(|range| {
+            let values: Vec<f64> = windows.iter().filter_map(|w| w.params.get(&range.name).copied()).collect();
+            let (mean, std_dev) = mean_std(&values);
+            ParamStability {
+                name: range.name.clone(),
+                mean,
+                std_dev,
+                min: values.iter().copied().fold(f64::NAN, f64::min),
+                max: values.iter().copied().fold(f64::NAN, f64::max),
+                changes: values.windows(2).filter(|w| w[0] != w[1]).count(),
+            }
+        })
+        .collect()
+}
+
+impl WalkForwardResult {
+    /// 打印窗口明细和参数稳定性
+    pub fn print_report(&self) {
+        println!("\n滚动优化窗口：");
+        for (i, w) in self.windows.iter().enumerate() {
+            let params: Vec<String> = w.params.iter().map(|(k, v)| format!("{}={:.4}", k, v)).collect();
+            println!(
+                "#{} 样本内得分 {:.4}  样本外收益 {:.2}%  回撤 {:.2}%  参数 [{}]",
+                i + 1,
+                w.in_sample_score,
+                w.out_of_sample_metrics.total_return * 100.0,
+                w.out_of_sample_metrics.max_drawdown * 100.0,
+                params.join(", ")
+            );
+        }
+        println!("\n参数稳定性：");
+        for s in &self.stability {
+            println!(
+                "{}: 均值 {:.4} 标准差 {:.4} 区间 [{:.4}, {:.4}] 变化 {} 次",
+                s.name, s.mean, s.std_dev, s.min, s.max, s.changes
+            );
+        }
+        println!(
+            "\n样本外总收益：{:.2}%  最大回撤：{:.2}%  夏普：{:.3}",
+            self.metrics.total_return * 100.0,
+            self.metrics.max_drawdown * 100.0,
+            self.metrics.sharpe
+        );
+    }
+}
@@ -0,0 +1,181 @@
+use std::collections::HashMap;
+
+use crate::account::{Account, Order, StockCode};
+use crate::indicator::Atr;
+use crate::model::{KLine, TickData};
+
+/// 挂在持仓上的保护单，触发后卖出全部可卖数量
+#[derive(Debug, Clone)]
+pub enum ProtectiveOrder {
+    /// 价格跌到给定价格止损
+    FixedStop(f64),
+    /// 价格跌破成本价给定比例止损
+    PercentStop(f64),
+    /// 跟踪止损：持仓期间最高价减 multiple 倍 ATR
+    ///
+    /// ATR 随 K 线更新，不足周期时不生效，可传入已预热的 `Atr`；tick 回放中只更新最高价。
+    AtrTrailing { multiple: f64, atr: Atr, highest: f64 },
+    /// 价格涨到给定价格止盈
+    TakeProfit(f64),
+    /// 价格高于成本价给定比例止盈
+    PercentTakeProfit(f64),
+}
+
+impl ProtectiveOrder {
+    pub fn atr_trailing(period: usize, multiple: f64) -> Self {
+        ProtectiveOrder::AtrTrailing {
+            multiple,
+            atr: Atr::new(period),
+            highest: 0.0,
+        }
+    }
+
+    /// 是否为止损（价格下跌时触发）
+    pub fn is_stop(&self) -> bool {
+        !matches!(self, ProtectiveOrder::TakeProfit(_) | ProtectiveOrder::PercentTakeProfit(_))
+    }
+
+    /// 触发价，None 表示尚未生效
+    pub fn trigger_price(&self, cost_price: f64) -> Option<f64> {
+        match self {
+            ProtectiveOrder::FixedStop(price) | ProtectiveOrder::TakeProfit(price) => Some(*price),
+            ProtectiveOrder::PercentStop(pct) => Some(cost_price * (1.0 - pct)),
+            ProtectiveOrder::PercentTakeProfit(pct) => Some(cost_price * (1.0 + pct)),
+            ProtectiveOrder::AtrTrailing { multiple, atr, highest } => {
+                atr.value().filter(|_| *highest > 0.0).map(|a| highest - multiple * a)
+            }
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ProtectiveOrder::FixedStop(_) => "固定止损",
+            ProtectiveOrder::PercentStop(_) => "比例止损",
+            ProtectiveOrder::AtrTrailing { .. } => "ATR 跟踪止损",
+            ProtectiveOrder::TakeProfit(_) => "固定止盈",
+            ProtectiveOrder::PercentTakeProfit(_) => "比例止盈",
+        }
+    }
+
+    /// 本根 K 线检查完后更新最高价和 ATR
+    fn update(&mut self, high: f64, bar: Option<&KLine>) {
+        if let ProtectiveOrder::AtrTrailing { atr, highest, .. } = self {
+            *highest = highest.max(high);
+            if let Some(bar) = bar {
+                atr.update(bar);
+            }
+        }
+    }
+}
+
+/// 保护单触发的成交
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProtectiveFill {
+    pub time: i64,
+    pub code: StockCode,
+    /// 触发的保护单，见 `ProtectiveOrder::name`
+    pub name: &'static str,
+    pub price: f64,
+    pub volume: i32,
+}
+
+/// 账户上所有持仓的保护单，由回测引擎在每根 K 线 / 每笔 tick 开始时检查
+#[derive(Debug, Clone, Default)]
+pub struct Protections {
+    orders: HashMap<StockCode, Vec<ProtectiveOrder>>,
+    /// 触发记录
+    pub fills: Vec<ProtectiveFill>,
+}
+
+impl Protections {
+    /// 给持仓挂保护单，持仓清空后自动撤销
+    pub fn attach(&mut self, code: &str, order: ProtectiveOrder) {
+        self.orders.entry(StockCode::from(code)).or_default().push(order);
+    }
+
+    /// 撤销某只股票的全部保护单
+    pub fn cancel(&mut self, code: &str) {
+        self.orders.remove(&StockCode::from(code));
+    }
+
+    pub fn get(&self, code: &str) -> &[ProtectiveOrder] {
+        self.orders.get(&StockCode::from(code)).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// 用一段价格区间检查保护单，触发时按触发价成交，跳空越过触发价时按开盘价成交
+    ///
+    /// 同一区间内止损和止盈都触发时按止损处理。
+    fn check(&mut self, account: &mut Account, code: &str, time: i64, (open, high, low): (f64, f64, f64), bar: Option<&KLine>) {
+        let key = StockCode::from(code);
+        if !self.orders.contains_key(&key) {
+            return;
+        }
+        let Some(position) = account.hold.get(&key).filter(|p| p.volume > 0) else {
+            self.orders.remove(&key);
+            return;
+        };
+        let (cost, available, mark) = (position.cost_price, position.available_vol, position.mark_price());
+        let Some(orders) = self.orders.get_mut(&key) else { return };
+
+        let mut hit: Option<(&'static str, f64)> = None;
+        for order in orders.iter_mut() {
+            if let ProtectiveOrder::AtrTrailing { highest, .. } = order {
+                *highest = highest.max(mark);
+            }
+            let Some(trigger) = order.trigger_price(cost) else { continue };
+            let fill = if order.is_stop() {
+                (low <= trigger).then(|| open.min(trigger))
+            } else {
+                (high >= trigger).then(|| open.max(trigger))
+            };
+            if let Some(price) = fill
+                && hit.is_none_or(|_| order.is_stop())
+            {
+                hit = Some((order.name(), price));
+            }
+        }
+        for order in orders.iter_mut() {
+            order.update(high, bar);
+        }
+
+        let Some((name, price)) = hit else { return };
+        if available <= 0 {
+            return;
+        }
+        let order = Order {
+            market_type: ' ',
+            code: key.clone(),
+            time,
+            price,
+            volume: available,
+            order_type: 'S',
+        };
+        if account.sell(&order) {
+            self.fills.push(ProtectiveFill {
+                time,
+                code: key.clone(),
+                name,
+                price,
+                volume: available,
+            });
+            if account.hold.get(&key).is_none_or(|p| p.volume == 0) {
+                self.orders.remove(&key);
+            }
+        }
+    }
+}
+
+/// 每根 K 线开始时用最高、最低价检查保护单
+pub fn on_bar(account: &mut Account, bar: &KLine, code: &str) {
+    let mut protect = std::mem::take(&mut account.protect);
+    protect.check(account, code, bar.time, (bar.open, bar.high, bar.low), Some(bar));
+    account.protect = protect;
+}
+
+/// 每笔 tick 用最新价检查保护单
+pub fn on_tick(account: &mut Account, tick: &TickData, code: &str) {
+    let price = tick.last_price;
+    let mut protect = std::mem::take(&mut account.protect);
+    protect.check(account, code, tick.time, (price, price, price), None);
+    account.protect = protect;
+}
@@ -0,0 +1,188 @@
+use chrono::NaiveDate;
+
+use crate::account::{Account, Order, StockCode};
+use crate::engine;
+use crate::model::TickData;
+use crate::protect;
+use crate::strategy::TickStrategy;
+
+/// 策略挂在盘口上的限价单
+#[derive(Debug, Clone, PartialEq)]
+pub struct RestingOrder {
+    pub id: u64,
+    /// 委托时间
+    pub time: i64,
+    pub price: f64,
+    /// 委托数量，始终为正
+    pub volume: i32,
+    /// 委托类型 B S
+    pub order_type: char,
+}
+
+/// 策略的挂单簿
+#[derive(Debug, Default)]
+pub struct OrderBook {
+    orders: Vec<RestingOrder>,
+    next_id: u64,
+}
+
+impl OrderBook {
+    /// 挂一笔限价单，返回委托编号
+    pub fn place(&mut self, time: i64, price: f64, volume: i32, order_type: char) -> u64 {
+        self.next_id += 1;
+        self.orders.push(RestingOrder {
+            id: self.next_id,
+            time,
+            price,
+            volume,
+            order_type,
+        });
+        self.next_id
+    }
+
+    /// 撤单，委托不存在时返回 false
+    pub fn cancel(&mut self, id: u64) -> bool {
+        let before = self.orders.len();
+        self.orders.retain(|o| o.id != id);
+        self.orders.len() != before
+    }
+
+    pub fn cancel_all(&mut self) {
+        self.orders.clear();
+    }
+
+    /// 当前挂单，按委托先后排列
+    pub fn orders(&self) -> &[RestingOrder] {
+        &self.orders
+    }
+
+    /// 是否有该方向的挂单
+    pub fn has(&self, order_type: char) -> bool {
+        self.orders.iter().any(|o| o.order_type == order_type)
+    }
+}
+
+/// 回放中的一笔成交
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fill {
+    pub order_id: u64,
+    pub time: i64,
+    pub price: f64,
+    pub volume: i32,
+    pub order_type: char,
+}
+
+/// 挂单在这笔 tick 上的成交价，不成交时返回 None
+///
+/// 买单：卖一不高于委托价时按卖一成交；最新价跌破委托价时按委托价成交。卖单对称。
+fn match_price(order: &RestingOrder, tick: &TickData) -> Option<f64> {
+    match order.order_type {
+        'B' if tick.ask1_price > 0.0 && tick.ask1_price <= order.price => Some(tick.ask1_price),
+        'B' if tick.last_price < order.price => Some(order.price),
+        'S' if tick.bid1_price > 0.0 && tick.bid1_price >= order.price => Some(tick.bid1_price),
+        'S' if tick.last_price > order.price => Some(order.price),
+        _ => None,
+    }
+}
+
+/// Tick 回放：逐笔撮合挂单并调用策略，可单步推进
+pub struct TickReplay {
+    ticks: Vec<TickData>,
+    code: String,
+    strategy: Box<dyn TickStrategy + Send>,
+    /// 已处理的 tick 数
+    pos: usize,
+    day: Option<NaiveDate>,
+    pub account: Account,
+    pub book: OrderBook,
+    /// 全部成交，按时间先后
+    pub fills: Vec<Fill>,
+}
+
+impl TickReplay {
+    pub fn new(ticks: Vec<TickData>, code: &str, strategy: Box<dyn TickStrategy + Send>, account: Account) -> Self {
+        Self {
+            ticks,
+            code: code.to_string(),
+            strategy,
+            pos: 0,
+            day: None,
+            account,
+            book: OrderBook::default(),
+            fills: Vec::new(),
+        }
+    }
+
+    pub fn ticks(&self) -> &[TickData] {
+        &self.ticks
+    }
+
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    /// 已处理的 tick 数
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.pos >= self.ticks.len()
+    }
+
+    /// 最近处理的一笔 tick
+    pub fn current(&self) -> Option<&TickData> {
+        self.pos.checked_sub(1).map(|i| &self.ticks[i])
+    }
+
+    /// 处理下一笔 tick：新交易日解锁持仓，撮合挂单，检查止损止盈，再调用策略。返回本笔挂单的成交数
+    pub fn step(&mut self) -> usize {
+        let Some(tick) = self.ticks.get(self.pos) else { return 0 };
+        self.pos += 1;
+
+        engine::unlock_t1(&mut self.account, &mut self.day, tick.time);
+
+        let code = StockCode::from(self.code.as_str());
+        let before = self.fills.len();
+        let mut rest = Vec::new();
+        for order in std::mem::take(&mut self.book.orders) {
+            let Some(price) = match_price(&order, tick) else {
+                rest.push(order);
+                continue;
+            };
+            let account_order = Order {
+                market_type: ' ',
+                code: code.clone(),
+                time: tick.time,
+                price,
+                volume: order.volume,
+                order_type: order.order_type,
+            };
+            let filled = match order.order_type {
+                'B' => self.account.buy(&account_order),
+                _ => self.account.sell(&account_order),
+            };
+            // 资金或可卖数量不足时账户拒绝，挂单作废
+            if filled {
+                self.fills.push(Fill {
+                    order_id: order.id,
+                    time: tick.time,
+                    price,
+                    volume: order.volume,
+                    order_type: order.order_type,
+                });
+            }
+        }
+        self.book.orders = rest;
+
+        protect::on_tick(&mut self.account, tick, &self.code);
+        self.account.on_price_change(&self.code, tick.last_price);
+        self.strategy.on_tick(tick, &self.code, &self.account, &mut self.book);
+        self.fills.len() - before
+    }
+
+    /// 连续处理 n 笔，返回新增成交数
+    pub fn advance(&mut self, n: usize) -> usize {
+        (0..n).map(|_| self.step()).sum()
+    }
+}
@@ -0,0 +1,360 @@
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use crate::account::Transaction;
+use crate::analysis::benchmark::RelativeMetrics;
+use crate::data::bar_date;
+use crate::engine::BacktestResult;
+use crate::metrics::{drawdown_series, monthly_returns, Metrics};
+use crate::model::KLine;
+use crate::optimize::ParamSet;
+
+/// 图表宽度 (px)
+const WIDTH: f64 = 1000.0;
+const PAD_LEFT: f64 = 70.0;
+const PAD_RIGHT: f64 = 10.0;
+const PAD_TOP: f64 = 10.0;
+const PAD_BOTTOM: f64 = 24.0;
+
+/// A 股习惯：红涨绿跌
+const UP_COLOR: &str = "#d62728";
+const DOWN_COLOR: &str = "#2ca02c";
+
+/// 生成独立的 HTML 报告，图表为内嵌 SVG，不依赖外部资源；K 线图只标注 `code` 的成交
+pub fn html_report(
+    title: &str,
+    code: &str,
+    bars: &[KLine],
+    result: &BacktestResult,
+    params: &ParamSet,
+    relative: Option<&RelativeMetrics>,
+) -> String {
+    let metrics = Metrics::from_result(result);
+    let times: Vec<i64> = result.equity.iter().map(|e| e.0).collect();
+    let balances: Vec<f64> = result.equity.iter().map(|e| e.1).collect();
+
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        r#"<!DOCTYPE html>
+<html lang="zh-CN">
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<style>
+body {{ font-family: "Microsoft YaHei", "PingFang SC", sans-serif; margin: 24px; color: #222; }}
+h1 {{ font-size: 22px; }}
+h2 {{ font-size: 17px; margin-top: 28px; border-bottom: 1px solid #ddd; padding-bottom: 4px; }}
+table {{ border-collapse: collapse; font-size: 13px; }}
+td, th {{ border: 1px solid #ddd; padding: 3px 8px; text-align: right; }}
+th {{ background: #f4f4f4; }}
+.summary td:first-child {{ text-align: left; color: #555; }}
+svg text {{ font-size: 11px; fill: #555; }}
+</style>
+</head>
+<body>
+<h1>{title}</h1>
+"#,
+        title = escape(title)
+    );
+
+    html.push_str("<h2>绩效指标</h2>\n<table class=\"summary\">\n");
+    let mut row = |name: &str, value: String| {
+        let _ = writeln!(html, "<tr><td>{}</td><td>{}</td></tr>", name, value);
+    };
+    row("初始资金", format!("{:.2}", result.init_cash));
+    row("期末总资产", format!("{:.2}", metrics.final_balance));
+    row("总收益率", pct(metrics.total_return));
+    row("年化收益率", pct(metrics.annual_return));
+    row("最大回撤", pct(metrics.max_drawdown));
+    row("年化波动率", pct(metrics.volatility));
+    row("夏普比率", format!("{:.3}", metrics.sharpe));
+    row("成交笔数", metrics.trade_count.to_string());
+    if let Some(r) = relative {
+        row("基准", escape(&r.benchmark_name));
+        row("基准收益率", pct(r.benchmark_return));
+        row("超额收益率", pct(r.excess_return));
+        row("Alpha / Beta", format!("{:.4} / {:.4}", r.alpha, r.beta));
+        row("跟踪误差 / 信息比率", format!("{:.4} / {:.4}", r.tracking_error, r.information_ratio));
+    }
+    for (name, value) in params {
+        row(&escape(name), format!("{}", value));
+    }
+    html.push_str("</table>\n");
+
+    html.push_str("<h2>资金曲线</h2>\n");
+    let mut series = vec![("策略", balances.clone(), "#1f77b4")];
+    if let Some(r) = relative {
+        let bm = r.benchmark_equity.iter().map(|e| e.1).collect::<Vec<_>>();
+        // 基准只覆盖有价格的区间，补齐到同样长度
+        let pad = balances.len().saturating_sub(bm.len());
+        let mut aligned = vec![f64::NAN; pad];
+        aligned.extend(bm);
+        series.push(("基准", aligned, "#ff7f0e"));
+    }
+    html.push_str(&line_chart(&times, &series, 280.0, |v| format!("{:.0}", v)));
+
+    html.push_str("<h2>回撤</h2>\n");
+    let drawdown = drawdown_series(&balances);
+    html.push_str(&line_chart(&times, &[("回撤", drawdown, DOWN_COLOR)], 180.0, pct));
+
+    html.push_str("<h2>月度收益</h2>\n");
+    html.push_str(&monthly_table(&monthly_returns(result.init_cash, &result.equity)));
+
+    html.push_str("<h2>K 线与成交</h2>\n");
+    html.push_str(&candle_chart(bars, code, &result.account.transactions, 360.0));
+
+    html.push_str("<h2>交易记录</h2>\n");
+    html.push_str(&transaction_table(&result.account.transactions));
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+/// 写入 HTML 报告
+pub fn write_html_report<P: AsRef<Path>>(
+    path: P,
+    title: &str,
+    code: &str,
+    bars: &[KLine],
+    result: &BacktestResult,
+    params: &ParamSet,
+    relative: Option<&RelativeMetrics>,
+) -> std::io::Result<()> {
+    fs::write(path, html_report(title, code, bars, result, params, relative))
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn pct(v: f64) -> String {
+    format!("{:.2}%", v * 100.0)
+}
+
+fn date_str(time: i64) -> String {
+    bar_date(time).format("%Y-%m-%d").to_string()
+}
+
+/// 把数据区间映射到绘图区域
+struct Frame {
+    height: f64,
+    n: usize,
+    min: f64,
+    max: f64,
+}
+
+impl Frame {
+    fn new(height: f64, n: usize, min: f64, max: f64) -> Self {
+        let (min, max) = if max > min { (min, max) } else { (min - 1.0, max + 1.0) };
+        Self { height, n, min, max }
+    }
+
+    /// 第 i 个点中心的横坐标
+    fn x(&self, i: usize) -> f64 {
+        PAD_LEFT + (i as f64 + 0.5) * self.slot()
+    }
+
+    /// 每个点占据的宽度
+    fn slot(&self) -> f64 {
+        (WIDTH - PAD_LEFT - PAD_RIGHT) / self.n.max(1) as f64
+    }
+
+    fn y(&self, v: f64) -> f64 {
+        let h = self.height - PAD_TOP - PAD_BOTTOM;
+        PAD_TOP + (self.max - v) / (self.max - self.min) * h
+    }
+
+    /// SVG 开头、网格、纵轴刻度和日期刻度
+    fn axes(&self, times: &[i64], fmt_y: impl Fn(f64) -> String) -> String {
+        let mut svg = format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+            w = WIDTH,
+            h = self.height
+        );
+        for k in 0..=4 {
+            let v = self.min + (self.max - self.min) * k as f64 / 4.0;
+            let y = self.y(v);
+            let _ = write!(
+                svg,
+                r##"<line x1="{l}" y1="{y:.1}" x2="{r}" y2="{y:.1}" stroke="#eee"/><text x="{tx}" y="{ty:.1}" text-anchor="end">{label}</text>"##,
+                l = PAD_LEFT,
+                r = WIDTH - PAD_RIGHT,
+                tx = PAD_LEFT - 6.0,
+                ty = y + 4.0,
+                label = fmt_y(v)
+            );
+        }
+        let ticks = 6.min(times.len());
+        for k in 0..ticks {
+            let i = if ticks > 1 { k * (times.len() - 1) / (ticks - 1) } else { 0 };
+            let _ = write!(
+                svg,
+                r#"<text x="{:.1}" y="{:.1}" text-anchor="middle">{}</text>"#,
+                self.x(i),
+                self.height - 6.0,
+                date_str(times[i])
+            );
+        }
+        svg
+    }
+}
+
+fn min_max(values: impl Iterator<Item = f64>) -> (f64, f64) {
+    values
+        .filter(|v| v.is_finite())
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| (lo.min(v), hi.max(v)))
+}
+
+/// 折线图，NaN 处断开
+fn line_chart(times: &[i64], series: &[(&str, Vec<f64>, &str)], height: f64, fmt_y: impl Fn(f64) -> String) -> String {
+    if times.is_empty() {
+        return "<p>无数据</p>\n".to_string();
+    }
+    let (min, max) = min_max(series.iter().flat_map(|s| s.1.iter().copied()));
+    let frame = Frame::new(height, times.len(), min, max);
+    let mut svg = frame.axes(times, fmt_y);
+    for (name, values, color) in series {
+        let mut d = String::new();
+        let mut pen_down = false;
+        for (i, v) in values.iter().enumerate() {
+            if !v.is_finite() {
+                pen_down = false;
+                continue;
+            }
+            let _ = write!(d, "{}{:.1},{:.1} ", if pen_down { "L" } else { "M" }, frame.x(i), frame.y(*v));
+            pen_down = true;
+        }
+        let _ = write!(
+            svg,
+            r#"<path d="{}" fill="none" stroke="{}" stroke-width="1.2"><title>{}</title></path>"#,
+            d, color, name
+        );
+    }
+    svg.push_str("</svg>\n");
+    if series.len() > 1 {
+        let legend: Vec<String> = series
+            .iter()
+            .map(|(name, _, color)| format!(r#"<span style="color:{}">■ {}</span>"#, color, name))
+            .collect();
+        svg.push_str(&format!("<div>{}</div>\n", legend.join(" &nbsp; ")));
+    }
+    svg
+}
+
+/// K 线图，`code` 的买入标在最低价下方，卖出标在最高价上方
+fn candle_chart(bars: &[KLine], code: &str, transactions: &[Transaction], height: f64) -> String {
+    if bars.is_empty() {
+        return "<p>无数据</p>\n".to_string();
+    }
+    let (min, max) = min_max(bars.iter().flat_map(|b| [b.low, b.high]));
+    let margin = (max - min) * 0.05;
+    let frame = Frame::new(height, bars.len(), min - margin, max + margin);
+    let times: Vec<i64> = bars.iter().map(|b| b.time).collect();
+    let mut svg = frame.axes(&times, |v| format!("{:.2}", v));
+
+    let body = (frame.slot() * 0.7).max(1.0);
+    for (i, bar) in bars.iter().enumerate() {
+        let color = if bar.close >= bar.open { UP_COLOR } else { DOWN_COLOR };
+        let x = frame.x(i);
+        let top = frame.y(bar.open.max(bar.close));
+        let bottom = frame.y(bar.open.min(bar.close));
+        let _ = write!(
+            svg,
+            r#"<line x1="{x:.1}" y1="{:.1}" x2="{x:.1}" y2="{:.1}" stroke="{color}"/><rect x="{:.1}" y="{top:.1}" width="{body:.1}" height="{:.1}" fill="{color}"/>"#,
+            frame.y(bar.high),
+            frame.y(bar.low),
+            x - body / 2.0,
+            (bottom - top).max(0.5),
+        );
+    }
+
+    let size = 5.0;
+    for t in transactions.iter().filter(|t| t.code.as_str() == code) {
+        let Ok(i) = times.binary_search(&t.time) else { continue };
+        let x = frame.x(i);
+        let label = format!("{} {}股 @ {:.2}", date_str(t.time), t.volume, t.price);
+        if t.volume > 0 {
+            let y = frame.y(bars[i].low) + 4.0;
+            let _ = write!(
+                svg,
+                r##"<polygon points="{x:.1},{y:.1} {:.1},{:.1} {:.1},{:.1}" fill="{UP_COLOR}" stroke="#fff"><title>买入 {label}</title></polygon>"##,
+                x - size,
+                y + size * 1.6,
+                x + size,
+                y + size * 1.6,
+            );
+        } else {
+            let y = frame.y(bars[i].high) - 4.0;
+            let _ = write!(
+                svg,
+                r##"<polygon points="{x:.1},{y:.1} {:.1},{:.1} {:.1},{:.1}" fill="{DOWN_COLOR}" stroke="#fff"><title>卖出 {label}</title></polygon>"##,
+                x - size,
+                y - size * 1.6,
+                x + size,
+                y - size * 1.6,
+            );
+        }
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// 月度收益热力表，行为年份，列为月份
+fn monthly_table(months: &[(i32, u32, f64)]) -> String {
+    if months.is_empty() {
+        return "<p>无数据</p>\n".to_string();
+    }
+    let max_abs = months.iter().map(|m| m.2.abs()).fold(0.0, f64::max).max(1e-9);
+    let mut html = String::from("<table>\n<tr><th>年份</th>");
+    for m in 1..=12 {
+        let _ = write!(html, "<th>{}月</th>", m);
+    }
+    html.push_str("<th>全年</th></tr>\n");
+
+    let mut years: Vec<i32> = months.iter().map(|m| m.0).collect();
+    years.dedup();
+    for year in years {
+        let _ = write!(html, "<tr><th>{}</th>", year);
+        let mut year_return = 1.0;
+        for month in 1..=12 {
+            match months.iter().find(|m| m.0 == year && m.1 == month) {
+                Some(&(_, _, r)) => {
+                    year_return *= 1.0 + r;
+                    // 收益越大颜色越深，正红负绿
+                    let alpha = (r.abs() / max_abs * 0.8).min(0.8) + 0.05;
+                    let rgb = if r >= 0.0 { "214,39,40" } else { "44,160,44" };
+                    let _ = write!(html, r#"<td style="background:rgba({},{:.2})">{}</td>"#, rgb, alpha, pct(r));
+                }
+                None => html.push_str("<td></td>"),
+            }
+        }
+        let _ = writeln!(html, "<td>{}</td></tr>", pct(year_return - 1.0));
+    }
+    html.push_str("</table>\n");
+    html
+}
+
+fn transaction_table(transactions: &[Transaction]) -> String {
+    let mut html = String::from(
+        "<table>\n<tr><th>日期</th><th>方向</th><th>价格</th><th>数量</th><th>金额</th><th>手续费</th><th>成交后数量</th><th>成交后成本</th></tr>\n",
+    );
+    for t in transactions {
+        let _ = writeln!(
+            html,
+            r#"<tr><td>{}</td><td style="color:{}">{}</td><td>{:.2}</td><td>{}</td><td>{:.2}</td><td>{:.2}</td><td>{}</td><td>{:.3}</td></tr>"#,
+            date_str(t.time),
+            if t.volume > 0 { UP_COLOR } else { DOWN_COLOR },
+            if t.volume > 0 { "买入" } else { "卖出" },
+            t.price,
+            t.volume.abs(),
+            t.price * t.volume.abs() as f64,
+            t.fee,
+            t.remain_vol,
+            t.remain_cost
+        );
+    }
+    html.push_str("</table>\n");
+    html
+}
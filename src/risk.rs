@@ -0,0 +1,258 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::account::{Account, Order, StockCode};
+use crate::data::bar_date;
+use crate::sizing::{equity, round_lots, SizeRequest};
+
+/// 风控限制，未设置的项不检查
+///
+/// ```toml
+/// [risk]
+/// max_position_pct = 0.3
+/// max_gross_exposure = 0.8
+/// max_adds = 3
+/// daily_loss_limit = 0.03
+/// max_drawdown = 0.2
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RiskLimits {
+    /// 单只股票持仓市值占总资产的上限
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_position_pct: Option<f64>,
+    /// 全部持仓和融券市值占总资产的上限
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_gross_exposure: Option<f64>,
+    /// 建仓后最多加仓次数，清仓后重新计数
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_adds: Option<u32>,
+    /// 当日亏损达到开盘时总资产的该比例后，当天不再买入
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub daily_loss_limit: Option<f64>,
+    /// 总资产从最高点回撤达到该比例后，停止买入直到回测结束
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_drawdown: Option<f64>,
+}
+
+impl RiskLimits {
+    /// 检查取值，返回出错的字段名和原因
+    pub fn validate(&self) -> Result<(), (&'static str, String)> {
+        for (key, value, max) in [
+            ("max_position_pct", self.max_position_pct, f64::INFINITY),
+            ("max_gross_exposure", self.max_gross_exposure, f64::INFINITY),
+            ("daily_loss_limit", self.daily_loss_limit, 1.0),
+            ("max_drawdown", self.max_drawdown, 1.0),
+        ] {
+            if let Some(v) = value
+                && !(v > 0.0 && v <= max)
+            {
+                return Err((key, format!("{} 超出范围 (0, {}]", v, max)));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 触发的风控规则
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RiskRule {
+    MaxPosition,
+    MaxGrossExposure,
+    MaxAdds,
+    DailyLoss,
+    MaxDrawdown,
+}
+
+impl fmt::Display for RiskRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            RiskRule::MaxPosition => "单票仓位上限",
+            RiskRule::MaxGrossExposure => "总仓位上限",
+            RiskRule::MaxAdds => "加仓次数上限",
+            RiskRule::DailyLoss => "单日亏损限制",
+            RiskRule::MaxDrawdown => "最大回撤止损",
+        })
+    }
+}
+
+/// 一次被拒绝或被缩量的委托
+#[derive(Debug, Clone, PartialEq)]
+pub struct RiskEvent {
+    pub time: i64,
+    pub code: StockCode,
+    /// 委托类型：'B' 买入，`SHORT_SELL` 融券卖出
+    pub order_type: char,
+    pub rule: RiskRule,
+    /// 委托数量
+    pub requested: i32,
+    /// 放行数量，0 表示拒绝
+    pub allowed: i32,
+    pub reason: String,
+}
+
+/// 风控检查结果
+#[derive(Debug, Clone, PartialEq)]
+pub enum RiskDecision {
+    Accept,
+    /// 缩量到给定数量
+    Resize(i32),
+    Reject,
+}
+
+/// 策略与账户之间的风控层，设置到 `Account::risk` 后对每笔买入和融券卖出生效，卖出持仓不受限制
+#[derive(Debug, Clone, Default)]
+pub struct RiskManager {
+    pub limits: RiskLimits,
+    /// 触发记录
+    pub log: Vec<RiskEvent>,
+    /// 每只股票本轮持仓的加仓次数
+    adds: HashMap<StockCode, u32>,
+    /// 总资产最高点
+    peak: f64,
+    /// 当前交易日及开盘时总资产（上一交易日最后记录的总资产）
+    day: Option<NaiveDate>,
+    day_start: f64,
+    /// 最近一次记录的总资产
+    last: f64,
+    /// 已触发最大回撤止损
+    halted: bool,
+}
+
+impl RiskManager {
+    pub fn new(limits: RiskLimits) -> Self {
+        Self {
+            limits,
+            ..Default::default()
+        }
+    }
+
+    /// 是否已触发最大回撤止损
+    pub fn is_halted(&self) -> bool {
+        self.halted
+    }
+
+    /// 记录某一时刻的总资产，更新最高点、当日开盘资产和回撤止损状态
+    pub fn observe(&mut self, time: i64, equity: f64) {
+        let day = bar_date(time);
+        if self.day != Some(day) {
+            // 日线上当日第一次记录已是收盘后，开盘资产取上一交易日最后一次记录
+            self.day_start = if self.day.is_some() { self.last } else { equity };
+            self.day = Some(day);
+        }
+        self.last = equity;
+        self.peak = self.peak.max(equity);
+        if let Some(max) = self.limits.max_drawdown
+            && self.peak > 0.0
+            && equity <= self.peak * (1.0 - max)
+        {
+            self.halted = true;
+        }
+    }
+
+    /// 检查一笔买入，拒绝或缩量时写入日志
+    pub fn check_buy(&mut self, order: &Order, account: &Account) -> RiskDecision {
+        let total = match self.check_loss(order, account) {
+            Ok(total) => total,
+            Err(decision) => return decision,
+        };
+
+        let held = account.hold.get(&order.code).map(|p| p.volume).unwrap_or(0);
+        if held == 0 {
+            self.adds.remove(&order.code);
+        }
+        if let Some(max) = self.limits.max_adds
+            && held > 0
+            && self.adds.get(&order.code).copied().unwrap_or(0) >= max
+        {
+            return self.reject(order, RiskRule::MaxAdds, format!("已加仓 {} 次", max));
+        }
+        self.check_caps(order, account, total, held)
+    }
+
+    /// 检查一笔融券卖出：与买入相同的回撤、单日亏损和仓位上限，单票仓位按已融券数量计算，不计加仓次数
+    pub fn check_short(&mut self, order: &Order, account: &Account) -> RiskDecision {
+        let total = match self.check_loss(order, account) {
+            Ok(total) => total,
+            Err(decision) => return decision,
+        };
+        let held = account.margin.as_ref().map(|m| m.short_volume(order.code.as_str())).unwrap_or(0);
+        self.check_caps(order, account, total, held)
+    }
+
+    /// 记录总资产并检查回撤止损和单日亏损，通过时返回总资产
+    fn check_loss(&mut self, order: &Order, account: &Account) -> Result<f64, RiskDecision> {
+        let req = SizeRequest::new(order.code.as_str(), order.price);
+        let total = equity(account, &req);
+        self.observe(order.time, total);
+
+        if self.halted {
+            let reason = format!("总资产 {:.2} 自最高点 {:.2} 回撤超过限制", total, self.peak);
+            return Err(self.reject(order, RiskRule::MaxDrawdown, reason));
+        }
+        if let Some(limit) = self.limits.daily_loss_limit
+            && total <= self.day_start * (1.0 - limit)
+        {
+            let reason = format!("总资产 {:.2} 较当日开盘 {:.2} 亏损超过限制", total, self.day_start);
+            return Err(self.reject(order, RiskRule::DailyLoss, reason));
+        }
+        Ok(total)
+    }
+
+    /// 按仓位上限计算还能成交的数量，`held` 为该股票已有的持仓或融券数量
+    fn check_caps(&mut self, order: &Order, account: &Account, total: f64, held: i32) -> RiskDecision {
+        let mut allowed = order.volume;
+        let mut rule = None;
+        if let Some(pct) = self.limits.max_position_pct {
+            let room = round_lots((total * pct - held as f64 * order.price) / order.price);
+            if room < allowed {
+                allowed = room;
+                rule = Some((RiskRule::MaxPosition, format!("单票市值上限 {:.2}", total * pct)));
+            }
+        }
+        if let Some(pct) = self.limits.max_gross_exposure {
+            let shorts = account.margin.as_ref().map(|m| m.short_value()).unwrap_or(0.0);
+            let exposure = account.market_value_with(&[(order.code.as_str(), order.price)]) + shorts;
+            let room = round_lots((total * pct - exposure) / order.price);
+            if room < allowed {
+                allowed = room;
+                rule = Some((RiskRule::MaxGrossExposure, format!("总市值上限 {:.2}", total * pct)));
+            }
+        }
+        match rule {
+            Some((rule, reason)) if allowed <= 0 => self.reject(order, rule, reason),
+            Some((rule, reason)) => {
+                self.record(order, rule, allowed, reason);
+                RiskDecision::Resize(allowed)
+            }
+            None => RiskDecision::Accept,
+        }
+    }
+
+    /// 买入成交后调用，持仓中的再次买入计为一次加仓
+    pub fn on_buy_filled(&mut self, code: &StockCode, held_before: i32) {
+        if held_before > 0 {
+            *self.adds.entry(code.clone()).or_insert(0) += 1;
+        }
+    }
+
+    fn reject(&mut self, order: &Order, rule: RiskRule, reason: String) -> RiskDecision {
+        self.record(order, rule, 0, reason);
+        RiskDecision::Reject
+    }
+
+    fn record(&mut self, order: &Order, rule: RiskRule, allowed: i32, reason: String) {
+        self.log.push(RiskEvent {
+            time: order.time,
+            code: order.code.clone(),
+            order_type: order.order_type,
+            rule,
+            requested: order.volume,
+            allowed,
+            reason,
+        });
+    }
+}
@@ -0,0 +1,173 @@
+use crate::account::{Account, StockCode};
+use crate::analysis::trades::RoundTrip;
+
+/// 每手股数
+pub const LOT: i32 = 100;
+
+/// 向下取整到整手，负数和非有限值取 0
+pub fn round_lots(shares: f64) -> i32 {
+    if !shares.is_finite() || shares <= 0.0 {
+        return 0;
+    }
+    (shares.min(i32::MAX as f64) as i32) / LOT * LOT
+}
+
+/// 可用资金（含买入手续费）买得起的股数：volume 先向下取整到整手再按手减少，买不起时为 0
+pub fn affordable(account: &Account, price: f64, volume: i32) -> i32 {
+    let mut volume = volume / LOT * LOT;
+    while volume > 0 {
+        let turnover = price * volume as f64;
+        if account.available_balance >= turnover + account.fee.calc(turnover, 'B') {
+            break;
+        }
+        volume -= LOT;
+    }
+    volume.max(0)
+}
+
+/// 下单时的行情信息
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SizeRequest<'a> {
+    pub code: &'a str,
+    /// 预计成交价
+    pub price: f64,
+    /// 止损价，按风险定仓时需要
+    pub stop_price: Option<f64>,
+    /// 当前 ATR，按 ATR 风险定仓时需要
+    pub atr: Option<f64>,
+}
+
+impl<'a> SizeRequest<'a> {
+    pub fn new(code: &'a str, price: f64) -> Self {
+        Self {
+            code,
+            price,
+            stop_price: None,
+            atr: None,
+        }
+    }
+
+    pub fn with_stop(self, stop_price: f64) -> Self {
+        Self {
+            stop_price: Some(stop_price),
+            ..self
+        }
+    }
+
+    pub fn with_atr(self, atr: f64) -> Self {
+        Self { atr: Some(atr), ..self }
+    }
+}
+
+/// 账户净资产：可用资金加持仓市值，请求的标的按请求价格计，其余按 `Position::mark_price`；信用账户扣除负债，期货计入保证金和浮动盈亏
+pub fn equity(account: &Account, req: &SizeRequest) -> f64 {
+    account.equity_with(&[(req.code, req.price)])
+}
+
+/// 仓位计算：把目标转换成整手股数
+pub trait Sizer {
+    /// 目标持仓股数（整手），无法计算时为 0
+    fn target_shares(&self, req: &SizeRequest, account: &Account) -> i32;
+
+    /// 达到目标还需买入的股数：扣除已有持仓，受可用资金（含手续费）限制，整手
+    fn order_shares(&self, req: &SizeRequest, account: &Account) -> i32 {
+        let held = account.hold.get(&StockCode::from(req.code)).map(|p| p.volume).unwrap_or(0);
+        let volume = round_lots((self.target_shares(req, account) - held) as f64);
+        affordable(account, req.price, volume)
+    }
+}
+
+/// 固定比例：持仓市值为总资产的 fraction
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixedFraction {
+    pub fraction: f64,
+}
+
+impl Sizer for FixedFraction {
+    fn target_shares(&self, req: &SizeRequest, account: &Account) -> i32 {
+        if req.price <= 0.0 {
+            return 0;
+        }
+        round_lots(equity(account, req) * self.fraction / req.price)
+    }
+}
+
+/// 按风险定仓：打到止损时亏损为总资产的 risk_fraction
+#[derive(Debug, Clone, PartialEq)]
+pub struct RiskPerTrade {
+    pub risk_fraction: f64,
+}
+
+impl Sizer for RiskPerTrade {
+    fn target_shares(&self, req: &SizeRequest, account: &Account) -> i32 {
+        let Some(stop) = req.stop_price else { return 0 };
+        let risk_per_share = req.price - stop;
+        if risk_per_share <= 0.0 {
+            return 0;
+        }
+        round_lots(equity(account, req) * self.risk_fraction / risk_per_share)
+    }
+}
+
+/// 按 ATR 风险定仓：以 atr_multiple 倍 ATR 作为每股风险，单笔风险为总资产的 risk_fraction
+#[derive(Debug, Clone, PartialEq)]
+pub struct AtrRiskPerTrade {
+    pub risk_fraction: f64,
+    pub atr_multiple: f64,
+}
+
+impl Sizer for AtrRiskPerTrade {
+    fn target_shares(&self, req: &SizeRequest, account: &Account) -> i32 {
+        let Some(atr) = req.atr else { return 0 };
+        let risk_per_share = atr * self.atr_multiple;
+        if risk_per_share <= 0.0 {
+            return 0;
+        }
+        round_lots(equity(account, req) * self.risk_fraction / risk_per_share)
+    }
+}
+
+/// 凯利公式：f = p - (1 - p) / b，按 fraction 倍（如半凯利 0.5）投入，最多满仓
+#[derive(Debug, Clone, PartialEq)]
+pub struct Kelly {
+    /// 胜率
+    pub win_rate: f64,
+    /// 平均盈利 / 平均亏损
+    pub win_loss_ratio: f64,
+    pub fraction: f64,
+}
+
+impl Kelly {
+    /// 用历史买卖回合估计胜率和盈亏比，没有盈利或没有亏损的回合时返回 None
+    pub fn from_trades(trips: &[RoundTrip], fraction: f64) -> Option<Self> {
+        let wins: Vec<f64> = trips.iter().filter(|t| t.pnl > 0.0).map(|t| t.pnl).collect();
+        let losses: Vec<f64> = trips.iter().filter(|t| t.pnl < 0.0).map(|t| -t.pnl).collect();
+        if wins.is_empty() || losses.is_empty() {
+            return None;
+        }
+        let avg = |v: &[f64]| v.iter().sum::<f64>() / v.len() as f64;
+        Some(Self {
+            win_rate: wins.len() as f64 / (wins.len() + losses.len()) as f64,
+            win_loss_ratio: avg(&wins) / avg(&losses),
+            fraction,
+        })
+    }
+
+    /// 建议投入比例，期望为负时为 0
+    pub fn optimal_fraction(&self) -> f64 {
+        if self.win_loss_ratio <= 0.0 {
+            return 0.0;
+        }
+        let f = self.win_rate - (1.0 - self.win_rate) / self.win_loss_ratio;
+        (f * self.fraction).clamp(0.0, 1.0)
+    }
+}
+
+impl Sizer for Kelly {
+    fn target_shares(&self, req: &SizeRequest, account: &Account) -> i32 {
+        if req.price <= 0.0 {
+            return 0;
+        }
+        round_lots(equity(account, req) * self.optimal_fraction() / req.price)
+    }
+}
@@ -0,0 +1,58 @@
+use crate::account::Account;
+use crate::indicator::Bollinger;
+use crate::model::KLine;
+use crate::strategy::long_only::LongOnly;
+use crate::strategy::Strategy;
+
+/// 布林带均值回归参数
+#[derive(Debug, Clone, PartialEq)]
+pub struct BollingerParams {
+    pub period: usize,
+    /// 上下轨距中轨的标准差倍数
+    pub width: f64,
+}
+
+impl Default for BollingerParams {
+    fn default() -> Self {
+        Self { period: 20, width: 2.0 }
+    }
+}
+
+impl BollingerParams {
+    pub const NAMES: [&'static str; 2] = ["period", "width"];
+
+    pub fn set(&mut self, name: &str, value: f64) -> bool {
+        match name {
+            "period" if value >= 2.0 => self.period = value.round() as usize,
+            "width" if value > 0.0 => self.width = value,
+            _ => return false,
+        }
+        true
+    }
+}
+
+/// 布林带均值回归：收盘价跌破下轨全仓买入，回到中轨上方卖出
+pub struct BollingerStrategy {
+    band: Bollinger,
+}
+
+impl BollingerStrategy {
+    pub fn new(params: &BollingerParams) -> Self {
+        Self {
+            band: Bollinger::new(params.period, params.width),
+        }
+    }
+}
+
+impl Strategy for BollingerStrategy {
+    fn process_bar(&mut self, bar: &KLine, code: &str, account: &mut Account) {
+        if let Some(band) = self.band.update(bar.close) {
+            let holding = LongOnly::volume(code, account) > 0;
+            if !holding && bar.close < band.lower {
+                LongOnly::buy_all(bar, code, account);
+            } else if holding && bar.close >= band.middle {
+                LongOnly::sell_all(bar, code, account);
+            }
+        }
+    }
+}
@@ -0,0 +1,271 @@
+use chrono::{Datelike, NaiveDate};
+
+use crate::account::{Account, Order, StockCode};
+use crate::data::bar_date;
+use crate::model::KLine;
+use crate::optimize::ParamSet;
+use crate::sizing::{affordable, round_lots, LOT};
+use crate::strategy::Strategy;
+
+/// 金额可买的整手股数，加一点余量避免 7999.999 按 7900 计
+fn lots(amount: f64, price: f64) -> i32 {
+    round_lots(amount / price + 1e-6)
+}
+
+/// 定投日程，以有 K 线的日期为交易日，遇到非交易日顺延到下一个交易日
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Schedule {
+    /// 每 n 个交易日一次，从第一个交易日开始
+    EveryDays(u32),
+    /// 每周一次，周几 1-5（大于 5 按周五），当周该日及之后的第一个交易日
+    Weekly(u32),
+    /// 每月一次，几号 1-31（超过当月天数按月末），当月该日及之后的第一个交易日。
+    /// 当月该日之后没有交易日时跳过当月
+    Monthly(u32),
+}
+
+impl Schedule {
+    fn interval(&self) -> u32 {
+        match *self {
+            Schedule::EveryDays(n) | Schedule::Weekly(n) | Schedule::Monthly(n) => n,
+        }
+    }
+}
+
+/// 当月天数
+fn days_in_month(date: NaiveDate) -> u32 {
+    let (y, m) = if date.month() == 12 { (date.year() + 1, 1) } else { (date.year(), date.month() + 1) };
+    NaiveDate::from_ymd_opt(y, m, 1)
+        .and_then(|d| d.pred_opt())
+        .map(|d| d.day())
+        .unwrap_or(28)
+}
+
+/// 定投方式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DcaMode {
+    /// 每期固定金额
+    FixedAmount,
+    /// 价值平均：持仓市值每期增加 amount，不足补足，超出部分在 allow_sell 时卖出
+    ValueAveraging,
+    /// 回撤加权：金额乘以 1 + multiplier × 价格距最高点的回撤，最多 max_multiple 倍
+    DrawdownWeighted,
+}
+
+/// 定投参数
+#[derive(Debug, Clone, PartialEq)]
+pub struct DcaParams {
+    pub mode: DcaMode,
+    pub schedule: Schedule,
+    /// 每期金额
+    pub amount: f64,
+    /// 回撤加权系数
+    pub drawdown_multiplier: f64,
+    /// 回撤加权的最大倍数
+    pub max_multiple: f64,
+    /// 价值平均是否卖出超出目标的部分
+    pub allow_sell: bool,
+}
+
+impl Default for DcaParams {
+    fn default() -> Self {
+        Self {
+            mode: DcaMode::FixedAmount,
+            schedule: Schedule::Monthly(1),
+            amount: 10_000.0,
+            drawdown_multiplier: 5.0,
+            max_multiple: 3.0,
+            allow_sell: false,
+        }
+    }
+}
+
+impl DcaParams {
+    /// 参数名。mode 取 0 定额、1 价值平均、2 回撤加权；
+    /// schedule 取 0 每 n 个交易日、1 每周、2 每月，interval 为对应的 n、周几或几号
+    pub const NAMES: [&'static str; 7] = [
+        "mode",
+        "schedule",
+        "interval",
+        "amount",
+        "drawdown_multiplier",
+        "max_multiple",
+        "allow_sell",
+    ];
+
+    /// 用参数组覆盖同名参数，未出现的保持不变
+    pub fn with(&self, set: &ParamSet) -> Self {
+        let mut params = self.clone();
+        for (name, &value) in set {
+            params.set(name, value);
+        }
+        params
+    }
+
+    /// 按名称设置参数，名称不存在或取值无效时返回 false
+    pub fn set(&mut self, name: &str, value: f64) -> bool {
+        let n = value.round();
+        let interval = self.schedule.interval();
+        match name {
+            "mode" => {
+                self.mode = match n as i64 {
+                    0 => DcaMode::FixedAmount,
+                    1 => DcaMode::ValueAveraging,
+                    2 => DcaMode::DrawdownWeighted,
+                    _ => return false,
+                }
+            }
+            "schedule" => {
+                self.schedule = match n as i64 {
+                    0 => Schedule::EveryDays(interval),
+                    1 => Schedule::Weekly(interval),
+                    2 => Schedule::Monthly(interval),
+                    _ => return false,
+                }
+            }
+            // 与 schedule 的设置顺序无关，超出范围的按 Schedule 的说明处理
+            "interval" => {
+                if !(1.0..=u32::MAX as f64).contains(&n) {
+                    return false;
+                }
+                let n = n as u32;
+                self.schedule = match self.schedule {
+                    Schedule::EveryDays(_) => Schedule::EveryDays(n),
+                    Schedule::Weekly(_) => Schedule::Weekly(n),
+                    Schedule::Monthly(_) => Schedule::Monthly(n),
+                };
+            }
+            "amount" if value > 0.0 => self.amount = value,
+            "drawdown_multiplier" if value >= 0.0 => self.drawdown_multiplier = value,
+            "max_multiple" if value >= 1.0 => self.max_multiple = value,
+            "allow_sell" => self.allow_sell = value != 0.0,
+            _ => return false,
+        }
+        true
+    }
+}
+
+/// 定投策略
+#[derive(Debug)]
+pub struct DcaStrategy {
+    params: DcaParams,
+    day: Option<NaiveDate>,
+    /// 已经过的交易日数
+    trading_days: u32,
+    /// 最近一次定投的日期
+    last_invest: Option<NaiveDate>,
+    /// 已定投期数
+    periods: u32,
+    /// 不足一手而留到下期的金额
+    carry: f64,
+    /// 期间最高收盘价
+    peak: f64,
+}
+
+impl DcaStrategy {
+    pub fn new(params: DcaParams) -> Self {
+        Self {
+            params,
+            day: None,
+            trading_days: 0,
+            last_invest: None,
+            periods: 0,
+            carry: 0.0,
+            peak: 0.0,
+        }
+    }
+
+    pub fn params(&self) -> &DcaParams {
+        &self.params
+    }
+
+    /// 已定投期数
+    pub fn periods(&self) -> u32 {
+        self.periods
+    }
+
+    /// 今天（某个交易日的第一根 K 线）是否该定投
+    fn is_due(&self, date: NaiveDate) -> bool {
+        let last = self.last_invest;
+        match self.params.schedule {
+            Schedule::EveryDays(n) => (self.trading_days - 1).is_multiple_of(n.max(1)),
+            Schedule::Weekly(weekday) => {
+                let same_week = last.is_some_and(|d| d.iso_week() == date.iso_week());
+                !same_week && date.weekday().number_from_monday() >= weekday.min(5)
+            }
+            Schedule::Monthly(day) => {
+                let same_month = last.is_some_and(|d| (d.year(), d.month()) == (date.year(), date.month()));
+                !same_month && date.day() >= day.min(days_in_month(date))
+            }
+        }
+    }
+
+    /// 本期的目标金额，正数买入、负数卖出
+    fn target_amount(&self, price: f64, market_value: f64) -> f64 {
+        let p = &self.params;
+        match p.mode {
+            DcaMode::FixedAmount => p.amount,
+            DcaMode::ValueAveraging => {
+                let target = p.amount * (self.periods + 1) as f64;
+                let diff = target - market_value;
+                if diff < 0.0 && !p.allow_sell { 0.0 } else { diff }
+            }
+            DcaMode::DrawdownWeighted => {
+                let drawdown = if self.peak > 0.0 { 1.0 - price / self.peak } else { 0.0 };
+                p.amount * (1.0 + p.drawdown_multiplier * drawdown).min(p.max_multiple)
+            }
+        }
+    }
+
+    fn invest(&mut self, bar: &KLine, code: &str, account: &mut Account) {
+        let price = bar.close;
+        let (market_value, available) = account
+            .hold
+            .get(&StockCode::from(code))
+            .map(|p| (p.volume as f64 * price, p.available_vol))
+            .unwrap_or((0.0, 0));
+        let amount = self.target_amount(price, market_value);
+        let order = |volume: i32, order_type: char| Order {
+            market_type: ' ',
+            code: StockCode::from(code),
+            time: bar.time,
+            price,
+            volume,
+            order_type,
+        };
+
+        if amount < 0.0 {
+            // 价值平均超出目标，按手卖出
+            let volume = lots(-amount, price).min(available / LOT * LOT);
+            if volume > 0 {
+                account.sell(&order(volume, 'S'));
+            }
+            return;
+        }
+
+        // 按手买入，资金不够时减少手数，不足一手的金额留到下期（价值平均下期重新计算差额）
+        let budget = amount + self.carry;
+        let volume = affordable(account, price, lots(budget, price));
+        let spent = if volume > 0 && account.buy(&order(volume, 'B')) { price * volume as f64 } else { 0.0 };
+        self.carry = match self.params.mode {
+            DcaMode::ValueAveraging => 0.0,
+            _ => (budget - spent).max(0.0),
+        };
+    }
+}
+
+impl Strategy for DcaStrategy {
+    fn process_bar(&mut self, bar: &KLine, code: &str, account: &mut Account) {
+        self.peak = self.peak.max(bar.close);
+        let date = bar_date(bar.time);
+        if self.day != Some(date) {
+            self.day = Some(date);
+            self.trading_days += 1;
+            if self.is_due(date) {
+                self.invest(bar, code, account);
+                self.last_invest = Some(date);
+                self.periods += 1;
+            }
+        }
+    }
+}
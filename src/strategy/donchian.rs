@@ -0,0 +1,66 @@
+use crate::account::Account;
+use crate::indicator::{Highest, Lowest};
+use crate::model::KLine;
+use crate::strategy::long_only::LongOnly;
+use crate::strategy::Strategy;
+
+/// 唐奇安通道参数
+#[derive(Debug, Clone, PartialEq)]
+pub struct DonchianParams {
+    /// 突破前 entry 根最高价时买入
+    pub entry: usize,
+    /// 跌破前 exit 根最低价时卖出
+    pub exit: usize,
+}
+
+impl Default for DonchianParams {
+    fn default() -> Self {
+        Self { entry: 20, exit: 10 }
+    }
+}
+
+impl DonchianParams {
+    pub const NAMES: [&'static str; 2] = ["entry", "exit"];
+
+    pub fn set(&mut self, name: &str, value: f64) -> bool {
+        match name {
+            "entry" if value >= 1.0 => self.entry = value.round() as usize,
+            "exit" if value >= 1.0 => self.exit = value.round() as usize,
+            _ => return false,
+        }
+        true
+    }
+}
+
+/// 唐奇安通道突破：收盘价高于前 entry 根最高价全仓买入，低于前 exit 根最低价卖出
+pub struct DonchianStrategy {
+    highest: Highest,
+    lowest: Lowest,
+}
+
+impl DonchianStrategy {
+    pub fn new(params: &DonchianParams) -> Self {
+        Self {
+            highest: Highest::new(params.entry),
+            lowest: Lowest::new(params.exit),
+        }
+    }
+}
+
+impl Strategy for DonchianStrategy {
+    fn process_bar(&mut self, bar: &KLine, code: &str, account: &mut Account) {
+        // 通道取之前的 K 线，不含当前
+        let holding = LongOnly::volume(code, account) > 0;
+        match (self.highest.value(), self.lowest.value()) {
+            (Some(high), _) if !holding && bar.close > high => {
+                LongOnly::buy_all(bar, code, account);
+            }
+            (_, Some(low)) if holding && bar.close < low => {
+                LongOnly::sell_all(bar, code, account);
+            }
+            _ => {}
+        }
+        self.highest.update(bar.high);
+        self.lowest.update(bar.low);
+    }
+}
@@ -0,0 +1,97 @@
+use crate::account::Account;
+use crate::indicator::{Ema, Sma};
+use crate::model::KLine;
+use crate::strategy::long_only::LongOnly;
+use crate::strategy::Strategy;
+
+/// 双均线参数
+#[derive(Debug, Clone, PartialEq)]
+pub struct DualMaParams {
+    /// 短均线周期
+    pub fast: usize,
+    /// 长均线周期
+    pub slow: usize,
+    /// 使用指数均线
+    pub exponential: bool,
+}
+
+impl Default for DualMaParams {
+    fn default() -> Self {
+        Self {
+            fast: 5,
+            slow: 20,
+            exponential: false,
+        }
+    }
+}
+
+impl DualMaParams {
+    /// 参数名，exponential 取 0/1
+    pub const NAMES: [&'static str; 3] = ["fast", "slow", "exponential"];
+
+    pub fn set(&mut self, name: &str, value: f64) -> bool {
+        match name {
+            "fast" if value >= 1.0 => self.fast = value.round() as usize,
+            "slow" if value >= 1.0 => self.slow = value.round() as usize,
+            "exponential" => self.exponential = value != 0.0,
+            _ => return false,
+        }
+        true
+    }
+}
+
+enum Average {
+    Simple(Sma),
+    Exponential(Ema),
+}
+
+impl Average {
+    fn new(period: usize, exponential: bool) -> Self {
+        if exponential { Average::Exponential(Ema::new(period)) } else { Average::Simple(Sma::new(period)) }
+    }
+
+    fn update(&mut self, value: f64) -> Option<f64> {
+        match self {
+            Average::Simple(ma) => ma.update(value),
+            Average::Exponential(ma) => ma.update(value),
+        }
+    }
+}
+
+/// 双均线交叉：短均线上穿长均线全仓买入，下穿卖出
+pub struct DualMaStrategy {
+    fast: Average,
+    slow: Average,
+    /// 上一根 K 线短均线是否在长均线上方
+    above: Option<bool>,
+}
+
+impl DualMaStrategy {
+    pub fn new(params: &DualMaParams) -> Self {
+        Self {
+            fast: Average::new(params.fast, params.exponential),
+            slow: Average::new(params.slow, params.exponential),
+            above: None,
+        }
+    }
+}
+
+impl Strategy for DualMaStrategy {
+    fn process_bar(&mut self, bar: &KLine, code: &str, account: &mut Account) {
+        let fast = self.fast.update(bar.close);
+        let slow = self.slow.update(bar.close);
+        if let (Some(fast), Some(slow)) = (fast, slow) {
+            let above = fast > slow;
+            match (self.above, above) {
+                (Some(false), true) => {
+                    LongOnly::buy_all(bar, code, account);
+                }
+                (Some(true), false) => {
+                    LongOnly::sell_all(bar, code, account);
+                }
+                _ => {}
+            }
+            self.above = Some(above);
+        }
+    }
+}
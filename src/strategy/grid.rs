@@ -0,0 +1,301 @@
+use crate::account::{Account, Order, StockCode};
+use crate::model::KLine;
+use crate::optimize::ParamSet;
+use crate::strategy::Strategy;
+
+/// 网格间距
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GridSpacing {
+    /// 等差，相邻格价差相同
+    Arithmetic,
+    /// 等比，相邻格涨幅相同
+    Geometric,
+}
+
+/// 价格越过上下边界时的处理
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoundAction {
+    /// 暂停交易，回到区间后继续
+    Pause,
+    /// 停止策略，保留持仓
+    Stop,
+    /// 清仓（含底仓）后停止，T+1 卖不出的部分之后继续卖
+    Liquidate,
+}
+
+impl BoundAction {
+    const ALL: [BoundAction; 3] = [BoundAction::Pause, BoundAction::Stop, BoundAction::Liquidate];
+}
+
+/// 网格策略参数
+#[derive(Debug, Clone, PartialEq)]
+pub struct GridParams {
+    /// 网格下边界
+    pub lower: f64,
+    /// 网格上边界
+    pub upper: f64,
+    /// 格数，价位数为格数 + 1
+    pub grid_count: usize,
+    pub spacing: GridSpacing,
+    /// 每格买卖数量
+    pub volume_per_grid: i32,
+    /// 底仓数量，首次进入区间时买入，网格不卖出
+    pub base_volume: i32,
+    /// 价格高于上边界时的处理
+    pub upper_action: BoundAction,
+    /// 价格低于下边界时的处理
+    pub lower_action: BoundAction,
+}
+
+impl Default for GridParams {
+    fn default() -> Self {
+        Self {
+            lower: 5.0,
+            upper: 8.0,
+            grid_count: 10,
+            spacing: GridSpacing::Arithmetic,
+            volume_per_grid: 1000,
+            base_volume: 0,
+            upper_action: BoundAction::Pause,
+            lower_action: BoundAction::Pause,
+        }
+    }
+}
+
+impl GridParams {
+    /// 参数名。geometric 取 0/1；upper_action、lower_action 取 0 暂停、1 停止、2 清仓
+    pub const NAMES: [&'static str; 8] = [
+        "lower",
+        "upper",
+        "grid_count",
+        "geometric",
+        "volume_per_grid",
+        "base_volume",
+        "upper_action",
+        "lower_action",
+    ];
+
+    /// 用参数组覆盖同名参数，未出现的保持不变
+    pub fn with(&self, set: &ParamSet) -> Self {
+        let mut params = self.clone();
+        for (name, &value) in set {
+            params.set(name, value);
+        }
+        params
+    }
+
+    /// 按名称设置参数，名称不存在或取值无效时返回 false
+    pub fn set(&mut self, name: &str, value: f64) -> bool {
+        let action = || BoundAction::ALL.get(value.round() as usize).copied();
+        match name {
+            "lower" => self.lower = value,
+            "upper" => self.upper = value,
+            "grid_count" => self.grid_count = value.round().max(1.0) as usize,
+            "geometric" => {
+                self.spacing = if value != 0.0 { GridSpacing::Geometric } else { GridSpacing::Arithmetic }
+            }
+            "volume_per_grid" => self.volume_per_grid = value.round() as i32,
+            "base_volume" => self.base_volume = value.round() as i32,
+            "upper_action" => match action() {
+                Some(a) => self.upper_action = a,
+                None => return false,
+            },
+            "lower_action" => match action() {
+                Some(a) => self.lower_action = a,
+                None => return false,
+            },
+            _ => return false,
+        }
+        true
+    }
+
+    /// 从下到上的网格价位，共 grid_count + 1 个
+    pub fn levels(&self) -> Vec<f64> {
+        let n = self.grid_count.max(1);
+        (0..=n)
+            .map(|i| {
+                let t = i as f64 / n as f64;
+                match self.spacing {
+                    GridSpacing::Arithmetic => self.lower + (self.upper - self.lower) * t,
+                    GridSpacing::Geometric => self.lower * (self.upper / self.lower).powf(t),
+                }
+            })
+            .collect()
+    }
+}
+
+/// 一格买入的持仓，涨到上一格价位时卖出
+#[derive(Debug, Clone, PartialEq)]
+pub struct GridLot {
+    /// 买入所在价位序号
+    pub level: usize,
+    pub volume: i32,
+    pub time: i64,
+}
+
+/// 网格策略：每跌一格买入一份，涨回上一格卖出该份，底仓不动
+#[derive(Debug)]
+pub struct GridStrategy {
+    params: GridParams,
+    levels: Vec<f64>,
+    /// 基准价位序号，价格跌到其下方的价位时买入
+    reference: Option<usize>,
+    /// 未卖出的网格持仓
+    lots: Vec<GridLot>,
+    /// 底仓是否已买入
+    base_bought: bool,
+    /// 已停止
+    stopped: bool,
+    /// 正在清仓
+    liquidating: bool,
+}
+
+impl GridStrategy {
+    pub fn new(params: GridParams) -> Self {
+        Self {
+            levels: params.levels(),
+            params,
+            reference: None,
+            lots: Vec::new(),
+            base_bought: false,
+            stopped: false,
+            liquidating: false,
+        }
+    }
+
+    pub fn params(&self) -> &GridParams {
+        &self.params
+    }
+
+    /// 未卖出的网格持仓
+    pub fn lots(&self) -> &[GridLot] {
+        &self.lots
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.stopped
+    }
+
+    /// 不低于 price 的最低价位序号，高于上边界时为 None
+    fn ceil_level(&self, price: f64) -> Option<usize> {
+        self.levels.iter().position(|&l| l >= price - 1e-9)
+    }
+
+    fn order(bar: &KLine, code: &str, volume: i32, order_type: char) -> Order {
+        Order {
+            market_type: ' ',
+            code: StockCode::from(code),
+            time: bar.time,
+            price: bar.close,
+            volume,
+            order_type,
+        }
+    }
+
+    /// 越界处理，返回 true 表示本根 K 线不做网格
+    fn check_bounds(&mut self, price: f64) -> bool {
+        let action = if price > self.params.upper {
+            self.params.upper_action
+        } else if price < self.params.lower {
+            self.params.lower_action
+        } else {
+            return false;
+        };
+        match action {
+            BoundAction::Pause => {
+                // 回到区间后按当时的价位重新开始
+                self.reference = None;
+            }
+            BoundAction::Stop => self.stopped = true,
+            BoundAction::Liquidate => {
+                self.stopped = true;
+                self.liquidating = true;
+            }
+        }
+        true
+    }
+
+    /// 卖出全部可卖持仓，全部卖完后结束清仓
+    fn liquidate(&mut self, bar: &KLine, code: &str, account: &mut Account) {
+        let (volume, available) = match account.hold.get(&StockCode::from(code)) {
+            Some(p) => (p.volume, p.available_vol),
+            None => (0, 0),
+        };
+        if available > 0 && account.sell(&Self::order(bar, code, available, 'S')) {
+            self.lots.clear();
+            if available == volume {
+                self.liquidating = false;
+            }
+        } else if volume == 0 {
+            self.liquidating = false;
+        }
+    }
+
+    fn trade_grid(&mut self, bar: &KLine, code: &str, account: &mut Account) {
+        let price = bar.close;
+        let Some(ceil) = self.ceil_level(price) else { return };
+
+        // 首次进入区间：买底仓，以当前价位为基准
+        if !self.base_bought {
+            self.base_bought = self.params.base_volume <= 0
+                || account.buy(&Self::order(bar, code, self.params.base_volume, 'B'));
+        }
+        let reference = *self.reference.get_or_insert(ceil);
+
+        // 卖出：涨到上一格的网格持仓，受可卖数量（T+1）和底仓限制
+        let (volume, available) = account
+            .hold
+            .get(&StockCode::from(code))
+            .map(|p| (p.volume, p.available_vol))
+            .unwrap_or((0, 0));
+        let base = if self.base_bought { self.params.base_volume.max(0) } else { 0 };
+        let mut limit = available.min(volume - base);
+        let mut sell_volume = 0;
+        let mut sold = Vec::new();
+        for (i, lot) in self.lots.iter().enumerate() {
+            let target = self.levels[(lot.level + 1).min(self.levels.len() - 1)];
+            if price + 1e-9 >= target && lot.volume <= limit {
+                limit -= lot.volume;
+                sell_volume += lot.volume;
+                sold.push(i);
+            }
+        }
+        if sell_volume > 0 && account.sell(&Self::order(bar, code, sell_volume, 'S')) {
+            for i in sold.into_iter().rev() {
+                self.lots.remove(i);
+            }
+        }
+
+        // 买入：从基准往下每跌到一格买一份，已有持仓的价位不重复买
+        let mut new_lots = Vec::new();
+        let mut level = reference;
+        while level > 0 && price <= self.levels[level - 1] + 1e-9 {
+            level -= 1;
+            if !self.lots.iter().any(|l| l.level == level) {
+                new_lots.push(level);
+            }
+        }
+        let buy_volume = self.params.volume_per_grid * new_lots.len() as i32;
+        if buy_volume > 0 && account.buy(&Self::order(bar, code, buy_volume, 'B')) {
+            self.lots.extend(new_lots.into_iter().map(|level| GridLot {
+                level,
+                volume: self.params.volume_per_grid,
+                time: bar.time,
+            }));
+        }
+
+        // 基准跟随：下跌时移到最后跌到的价位，上涨时移到当前价上方的价位
+        self.reference = Some(if level < reference { level } else { reference.max(ceil) });
+    }
+}
+
+impl Strategy for GridStrategy {
+    fn process_bar(&mut self, bar: &KLine, code: &str, account: &mut Account) {
+        if !self.stopped && !self.check_bounds(bar.close) {
+            self.trade_grid(bar, code, account);
+        }
+        if self.liquidating {
+            self.liquidate(bar, code, account);
+        }
+    }
+}
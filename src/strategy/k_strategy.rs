@@ -1,7 +1,95 @@
 use crate::account::{Account, Order, Position, Transaction,StockCode};
 use crate::model::{KLine};
+use crate::optimize::ParamSet;
+use crate::strategy::Strategy;
 use chrono::{TimeZone, Utc, Duration};
 
+/// KStrategy 参数，名称与 `ParamSet` 中的键一致
+#[derive(Debug, Clone, PartialEq)]
+pub struct KStrategyParams {
+    /// 买入价格区间 最低
+    pub buy_price_low: f64,
+    /// 买入价格区间 最高
+    pub buy_price_high: f64,
+    /// 初始底仓数量
+    pub init_base_volume: i32,
+    /// 回调补仓百分比
+    pub add_pos_drawdown_pct: f64,
+    /// 初始止盈
+    pub init_stop_profit: f64,
+    /// 清仓价格
+    pub liquidation_price: f64,
+}
+
+impl Default for KStrategyParams {
+    fn default() -> Self {
+        Self {
+            buy_price_low: 5.9,
+            buy_price_high: 7.8,
+            init_base_volume: 20000,
+            add_pos_drawdown_pct: 0.02,
+            init_stop_profit: 0.1,
+            liquidation_price: 8.5,
+        }
+    }
+}
+
+impl KStrategyParams {
+    /// 可寻优的参数名
+    pub const NAMES: [&'static str; 6] = [
+        "buy_price_low",
+        "buy_price_high",
+        "init_base_volume",
+        "add_pos_drawdown_pct",
+        "init_stop_profit",
+        "liquidation_price",
+    ];
+
+    /// 用参数组覆盖同名参数，未出现的保持不变
+    pub fn with(&self, set: &ParamSet) -> Self {
+        let mut params = self.clone();
+        for (name, &value) in set {
+            params.set(name, value);
+        }
+        params
+    }
+
+    /// 按名称设置参数，名称不存在时返回 false
+    pub fn set(&mut self, name: &str, value: f64) -> bool {
+        match name {
+            "buy_price_low" => self.buy_price_low = value,
+            "buy_price_high" => self.buy_price_high = value,
+            "init_base_volume" => self.init_base_volume = value.round() as i32,
+            "add_pos_drawdown_pct" => self.add_pos_drawdown_pct = value,
+            "init_stop_profit" => self.init_stop_profit = value,
+            "liquidation_price" => self.liquidation_price = value,
+            _ => return false,
+        }
+        true
+    }
+
+    /// 全部参数组成的参数组
+    pub fn to_param_set(&self) -> ParamSet {
+        Self::NAMES
+            .iter()
+            .filter_map(|&name| Some((name.to_string(), self.get(name)?)))
+            .collect()
+    }
+
+    /// 按名称读取参数
+    pub fn get(&self, name: &str) -> Option<f64> {
+        Some(match name {
+            "buy_price_low" => self.buy_price_low,
+            "buy_price_high" => self.buy_price_high,
+            "init_base_volume" => self.init_base_volume as f64,
+            "add_pos_drawdown_pct" => self.add_pos_drawdown_pct,
+            "init_stop_profit" => self.init_stop_profit,
+            "liquidation_price" => self.liquidation_price,
+            _ => return None,
+        })
+    }
+}
+
 /// 一个低位区间做T策略
 #[derive(Debug, Default)]
 pub struct KStrategy {
@@ -39,19 +127,13 @@ pub struct KStrategy {
     
     /// 清仓价格，达到清仓价格时清仓
     liquidation_price: f64,
-    /// 清仓百分比，达到清仓百分比时清仓
-    
-
-    /// 上一个 k线时间
-    last_bar_time: i64,
-    
+    // 清仓百分比，达到清仓百分比时清仓
 }
 
 impl KStrategy {
     pub fn new(buy_price_low: f64, buy_price_high:f64, init_base_volume: i32, add_pos_drawdown_pct: f64, init_stop_profit: f64, liquidation_price:f64) -> Self {
         Self {
             init_position_volume: init_base_volume,
-            last_bar_time: 0,
             buy_times: 0,
             buy_price_low,
             buy_price_high,
@@ -65,8 +147,19 @@ impl KStrategy {
         }
     }
 
+    pub fn from_params(params: &KStrategyParams) -> Self {
+        Self::new(
+            params.buy_price_low,
+            params.buy_price_high,
+            params.init_base_volume,
+            params.add_pos_drawdown_pct,
+            params.init_stop_profit,
+            params.liquidation_price,
+        )
+    }
+
     pub fn process_bar(&mut self, bar: &KLine, code: &str, account: &mut Account) {
-        let volume = self.get_vol(bar, code, account);
+        let volume = self.get_vol(code, account);
 
         if volume == 0 {
             self.initial_entry(bar, code, account);
@@ -74,22 +167,11 @@ impl KStrategy {
             self.check_reentry(bar, code, account);
             self.check_profit(bar, code, account);
         }
-
-        // Update final values
-        // if let Some(position) = account.positions.get_mut(code) {
-        account.on_price_change(code, bar.close);
-            
-        // }
-        self.last_bar_time = bar.time;
     }
 
-    fn get_vol(&mut self, bar: &KLine, code: &str, account: &mut Account) -> i32 {
+    fn get_vol(&self, code: &str, account: &mut Account) -> i32 {
         // Get position volume in a separate scope
         let position = account.get_position(StockCode::from(code));
-        // Check new trading day, update available volume
-        if bar.time - self.last_bar_time > 12 * 60 * 60 {
-            position.available_vol = position.volume;
-        }
         position.volume
     }
 
@@ -205,4 +287,8 @@ impl KStrategy {
     }
 }
 
-
+impl Strategy for KStrategy {
+    fn process_bar(&mut self, bar: &KLine, code: &str, account: &mut Account) {
+        KStrategy::process_bar(self, bar, code, account)
+    }
+}
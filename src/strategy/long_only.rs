@@ -0,0 +1,37 @@
+use crate::account::{Account, Order, StockCode};
+use crate::model::KLine;
+use crate::sizing::{affordable, round_lots};
+
+/// 单标的只做多、全仓进出的下单辅助，经典策略共用
+pub struct LongOnly;
+
+impl LongOnly {
+    /// 持仓数量
+    pub fn volume(code: &str, account: &Account) -> i32 {
+        account.hold.get(&StockCode::from(code)).map(|p| p.volume).unwrap_or(0)
+    }
+
+    /// 用全部可用资金按收盘价整手买入（含手续费），买不起一手时返回 false
+    pub fn buy_all(bar: &KLine, code: &str, account: &mut Account) -> bool {
+        let price = bar.close;
+        let volume = affordable(account, price, round_lots(account.available_balance / price));
+        volume > 0 && account.buy(&Self::order(bar, code, volume, 'B'))
+    }
+
+    /// 按收盘价卖出全部可卖持仓
+    pub fn sell_all(bar: &KLine, code: &str, account: &mut Account) -> bool {
+        let available = account.hold.get(&StockCode::from(code)).map(|p| p.available_vol).unwrap_or(0);
+        available > 0 && account.sell(&Self::order(bar, code, available, 'S'))
+    }
+
+    fn order(bar: &KLine, code: &str, volume: i32, order_type: char) -> Order {
+        Order {
+            market_type: ' ',
+            code: StockCode::from(code),
+            time: bar.time,
+            price: bar.close,
+            volume,
+            order_type,
+        }
+    }
+}
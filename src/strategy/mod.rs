@@ -1 +1,112 @@
+use crate::account::Account;
+use crate::model::{KLine, TickData};
+use crate::optimize::ParamSet;
+use crate::replay::OrderBook;
+use crate::strategy::bollinger::{BollingerParams, BollingerStrategy};
+use crate::strategy::dca::{DcaParams, DcaStrategy};
+use crate::strategy::donchian::{DonchianParams, DonchianStrategy};
+use crate::strategy::dual_ma::{DualMaParams, DualMaStrategy};
+use crate::strategy::grid::{GridParams, GridStrategy};
+use crate::strategy::k_strategy::{KStrategy, KStrategyParams};
+use crate::strategy::rsi::{RsiParams, RsiStrategy};
+
+pub mod bollinger;
+pub mod dca;
+pub mod donchian;
+pub mod dual_ma;
+pub mod grid;
 pub mod k_strategy;
+pub mod long_only;
+pub mod pairs;
+pub mod rsi;
+pub mod tick_t;
+
+/// 策略接口，回测引擎逐根 K 线调用
+pub trait Strategy {
+    /// 处理一根 K 线，在账户上下单；引擎在调用前解锁 T+1 持仓，调用后按收盘价更新市值
+    fn process_bar(&mut self, bar: &KLine, code: &str, account: &mut Account);
+}
+
+/// 多标的策略接口，引擎按时间戳对齐后逐个时间点调用
+pub trait MultiStrategy {
+    /// bars 与 codes 一一对应，为同一时间点的 K 线
+    fn process_bars(&mut self, bars: &[KLine], codes: &[String], account: &mut Account);
+}
+
+/// 逐笔策略接口，Tick 回放时调用。策略只挂限价单，由回放按盘口撮合
+pub trait TickStrategy {
+    /// 处理一笔 tick，account 为撮合本笔挂单之后的账户
+    fn on_tick(&mut self, tick: &TickData, code: &str, account: &Account, book: &mut OrderBook);
+}
+
+/// 可按名称创建的策略
+pub const STRATEGY_NAMES: [&str; 7] = ["k", "grid", "dca", "dual_ma", "donchian", "bollinger", "rsi"];
+
+/// 用参数组逐项设置参数，参数名不存在或取值无效时报错
+fn apply<P>(name: &str, mut p: P, params: &ParamSet, set: fn(&mut P, &str, f64) -> bool) -> Result<P, String> {
+    for (key, &value) in params {
+        if !set(&mut p, key, value) {
+            return Err(format!("策略 {} 没有参数 {} 或取值 {} 无效", name, key, value));
+        }
+    }
+    Ok(p)
+}
+
+/// 检查单个参数的名称和取值，不检查参数之间的关系
+pub fn check_param(name: &str, key: &str, value: f64) -> Result<(), String> {
+    let single = ParamSet::from([(key.to_string(), value)]);
+    match name {
+        "k" => apply(name, KStrategyParams::default(), &single, KStrategyParams::set).map(|_| ()),
+        "grid" => apply(name, GridParams::default(), &single, GridParams::set).map(|_| ()),
+        "dca" => apply(name, DcaParams::default(), &single, DcaParams::set).map(|_| ()),
+        "dual_ma" => apply(name, DualMaParams::default(), &single, DualMaParams::set).map(|_| ()),
+        "donchian" => apply(name, DonchianParams::default(), &single, DonchianParams::set).map(|_| ()),
+        "bollinger" => apply(name, BollingerParams::default(), &single, BollingerParams::set).map(|_| ()),
+        "rsi" => apply(name, RsiParams::default(), &single, RsiParams::set).map(|_| ()),
+        _ => Err(format!("未知策略 {}，可选：{}", name, STRATEGY_NAMES.join(", "))),
+    }
+}
+
+/// 按名称创建策略，参数组覆盖默认参数
+pub fn build(name: &str, params: &ParamSet) -> Result<Box<dyn Strategy>, String> {
+    match name {
+        "k" => {
+            let p = apply(name, KStrategyParams::default(), params, KStrategyParams::set)?;
+            Ok(Box::new(KStrategy::from_params(&p)))
+        }
+        "grid" => {
+            let p = apply(name, GridParams::default(), params, GridParams::set)?;
+            if !(p.lower > 0.0 && p.lower < p.upper) {
+                return Err(format!("网格边界无效：lower {} upper {}", p.lower, p.upper));
+            }
+            Ok(Box::new(GridStrategy::new(p)))
+        }
+        "dca" => {
+            let p = apply(name, DcaParams::default(), params, DcaParams::set)?;
+            Ok(Box::new(DcaStrategy::new(p)))
+        }
+        "dual_ma" => {
+            let p = apply(name, DualMaParams::default(), params, DualMaParams::set)?;
+            if p.fast >= p.slow {
+                return Err(format!("短均线周期 {} 应小于长均线周期 {}", p.fast, p.slow));
+            }
+            Ok(Box::new(DualMaStrategy::new(&p)))
+        }
+        "donchian" => {
+            let p = apply(name, DonchianParams::default(), params, DonchianParams::set)?;
+            Ok(Box::new(DonchianStrategy::new(&p)))
+        }
+        "bollinger" => {
+            let p = apply(name, BollingerParams::default(), params, BollingerParams::set)?;
+            Ok(Box::new(BollingerStrategy::new(&p)))
+        }
+        "rsi" => {
+            let p = apply(name, RsiParams::default(), params, RsiParams::set)?;
+            if p.oversold >= p.overbought {
+                return Err(format!("超卖线 {} 应低于超买线 {}", p.oversold, p.overbought));
+            }
+            Ok(Box::new(RsiStrategy::new(&p)))
+        }
+        _ => Err(format!("未知策略 {}，可选：{}", name, STRATEGY_NAMES.join(", "))),
+    }
+}
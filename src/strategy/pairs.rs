@@ -0,0 +1,191 @@
+use std::collections::VecDeque;
+
+use crate::account::{Account, Order, StockCode};
+use crate::analysis::pairs::{ols, zscore};
+use crate::model::KLine;
+use crate::sizing::{affordable, round_lots};
+use crate::strategy::MultiStrategy;
+
+/// 配对交易参数
+#[derive(Debug, Clone, PartialEq)]
+pub struct PairsParams {
+    /// 滚动回归和 z 分数的窗口长度
+    pub window: usize,
+    /// |z| 超过该值时开始偏离中性仓位
+    pub entry_z: f64,
+    /// |z| 回到该值以内时恢复中性仓位
+    pub exit_z: f64,
+    /// 投入两条腿的资金占总资产的比例
+    pub allocation: f64,
+    /// 偏离程度：1 表示贵的一腿全部换成便宜的一腿
+    pub tilt: f64,
+}
+
+impl Default for PairsParams {
+    fn default() -> Self {
+        Self {
+            window: 60,
+            entry_z: 2.0,
+            exit_z: 0.5,
+            allocation: 0.9,
+            tilt: 1.0,
+        }
+    }
+}
+
+impl PairsParams {
+    pub const NAMES: [&'static str; 5] = ["window", "entry_z", "exit_z", "allocation", "tilt"];
+
+    pub fn set(&mut self, name: &str, value: f64) -> bool {
+        match name {
+            "window" if value >= 10.0 => self.window = value.round() as usize,
+            "entry_z" if value > 0.0 => self.entry_z = value,
+            "exit_z" if value >= 0.0 => self.exit_z = value,
+            "allocation" if value > 0.0 && value <= 1.0 => self.allocation = value,
+            "tilt" if (0.0..=1.0).contains(&value) => self.tilt = value,
+            _ => return false,
+        }
+        true
+    }
+}
+
+/// 两腿配对交易：滚动回归 ln a = alpha + beta * ln b 求对冲比例，按价差 z 分数调仓。
+///
+/// A 股现货不能卖空，先按对冲比例建立两腿中性底仓；z 分数偏高（a 相对贵）时
+/// 卖出 a 买入 b，偏低时反向，回到 exit_z 以内恢复中性。codes[0] 为 a，codes[1] 为 b。
+pub struct PairsStrategy {
+    params: PairsParams,
+    la: VecDeque<f64>,
+    lb: VecDeque<f64>,
+    /// 1：a 相对贵，偏向 b；-1：偏向 a；0：中性
+    state: i8,
+    /// 已建立底仓
+    started: bool,
+    beta: Option<f64>,
+    z: Option<f64>,
+}
+
+impl PairsStrategy {
+    pub fn new(params: PairsParams) -> Self {
+        Self {
+            la: VecDeque::with_capacity(params.window + 1),
+            lb: VecDeque::with_capacity(params.window + 1),
+            params,
+            state: 0,
+            started: false,
+            beta: None,
+            z: None,
+        }
+    }
+
+    /// 最近一次的对冲比例
+    pub fn hedge_ratio(&self) -> Option<f64> {
+        self.beta
+    }
+
+    /// 最近一次的价差 z 分数
+    pub fn zscore(&self) -> Option<f64> {
+        self.z
+    }
+
+    /// 当前状态：1 偏向 b，-1 偏向 a，0 中性
+    pub fn state(&self) -> i8 {
+        self.state
+    }
+
+    /// 按状态计算两腿目标市值
+    fn targets(&self, equity: f64, beta: f64) -> [f64; 2] {
+        let capital = equity * self.params.allocation;
+        let a = capital / (1.0 + beta);
+        let b = capital - a;
+        let t = self.params.tilt * self.state as f64;
+        [a * (1.0 - t), b * (1.0 + t)]
+    }
+
+    /// 调到目标市值：先卖后买，卖出受可卖数量限制，买入受可用资金限制
+    fn rebalance(&self, bars: &[KLine], codes: &[String], account: &mut Account, targets: [f64; 2]) {
+        let mut deltas = [0i32; 2];
+        for i in 0..2 {
+            let held = account.hold.get(&StockCode::from(codes[i].as_str())).map(|p| p.volume).unwrap_or(0);
+            let target = round_lots(targets[i] / bars[i].close);
+            deltas[i] = target - held;
+        }
+        for i in (0..2).filter(|&i| deltas[i] < 0) {
+            let code = StockCode::from(codes[i].as_str());
+            let available = account.hold.get(&code).map(|p| p.available_vol).unwrap_or(0);
+            let volume = (-deltas[i]).min(available);
+            if volume > 0 {
+                account.sell(&order(&bars[i], &codes[i], volume, 'S'));
+            }
+        }
+        for i in (0..2).filter(|&i| deltas[i] > 0) {
+            let volume = affordable(account, bars[i].close, deltas[i]);
+            if volume > 0 {
+                account.buy(&order(&bars[i], &codes[i], volume, 'B'));
+            }
+        }
+    }
+}
+
+fn order(bar: &KLine, code: &str, volume: i32, order_type: char) -> Order {
+    Order {
+        market_type: ' ',
+        code: StockCode::from(code),
+        time: bar.time,
+        price: bar.close,
+        volume,
+        order_type,
+    }
+}
+
+impl MultiStrategy for PairsStrategy {
+    fn process_bars(&mut self, bars: &[KLine], codes: &[String], account: &mut Account) {
+        let (Some(a), Some(b)) = (bars.first(), bars.get(1)) else { return };
+        if codes.len() < 2 || a.close <= 0.0 || b.close <= 0.0 {
+            return;
+        }
+
+        self.la.push_back(a.close.ln());
+        self.lb.push_back(b.close.ln());
+        if self.la.len() > self.params.window {
+            self.la.pop_front();
+            self.lb.pop_front();
+        }
+        if self.la.len() < self.params.window {
+            return;
+        }
+
+        let la: Vec<f64> = self.la.iter().copied().collect();
+        let lb: Vec<f64> = self.lb.iter().copied().collect();
+        let (alpha, beta) = ols(&lb, &la);
+        let spread: Vec<f64> = la.iter().zip(&lb).map(|(x, y)| x - alpha - beta * y).collect();
+        self.beta = Some(beta);
+        self.z = zscore(&spread);
+        let Some(z) = self.z else { return };
+        // 负相关时无法用两腿多头对冲
+        if beta <= 0.0 {
+            return;
+        }
+
+        let p = &self.params;
+        let state = if z > p.entry_z {
+            1
+        } else if z < -p.entry_z {
+            -1
+        } else if z.abs() < p.exit_z {
+            0
+        } else {
+            self.state
+        };
+        if state == self.state && self.started {
+            return;
+        }
+        self.state = state;
+        self.started = true;
+
+        let quotes: Vec<(&str, f64)> = codes[..2].iter().zip(bars).map(|(code, bar)| (code.as_str(), bar.close)).collect();
+        let equity = account.equity_with(&quotes);
+        let targets = self.targets(equity, beta);
+        self.rebalance(bars, codes, account, targets);
+    }
+}
@@ -0,0 +1,69 @@
+use crate::account::Account;
+use crate::indicator::Rsi;
+use crate::model::KLine;
+use crate::strategy::long_only::LongOnly;
+use crate::strategy::Strategy;
+
+/// RSI 反转参数
+#[derive(Debug, Clone, PartialEq)]
+pub struct RsiParams {
+    pub period: usize,
+    /// 低于该值视为超卖
+    pub oversold: f64,
+    /// 高于该值视为超买
+    pub overbought: f64,
+}
+
+impl Default for RsiParams {
+    fn default() -> Self {
+        Self {
+            period: 14,
+            oversold: 30.0,
+            overbought: 70.0,
+        }
+    }
+}
+
+impl RsiParams {
+    pub const NAMES: [&'static str; 3] = ["period", "oversold", "overbought"];
+
+    pub fn set(&mut self, name: &str, value: f64) -> bool {
+        match name {
+            "period" if value >= 1.0 => self.period = value.round() as usize,
+            "oversold" if (0.0..=100.0).contains(&value) => self.oversold = value,
+            "overbought" if (0.0..=100.0).contains(&value) => self.overbought = value,
+            _ => return false,
+        }
+        true
+    }
+}
+
+/// RSI 反转：超卖全仓买入，超买卖出
+pub struct RsiStrategy {
+    rsi: Rsi,
+    oversold: f64,
+    overbought: f64,
+}
+
+impl RsiStrategy {
+    pub fn new(params: &RsiParams) -> Self {
+        Self {
+            rsi: Rsi::new(params.period),
+            oversold: params.oversold,
+            overbought: params.overbought,
+        }
+    }
+}
+
+impl Strategy for RsiStrategy {
+    fn process_bar(&mut self, bar: &KLine, code: &str, account: &mut Account) {
+        if let Some(rsi) = self.rsi.update(bar.close) {
+            let holding = LongOnly::volume(code, account) > 0;
+            if !holding && rsi < self.oversold {
+                LongOnly::buy_all(bar, code, account);
+            } else if holding && rsi > self.overbought {
+                LongOnly::sell_all(bar, code, account);
+            }
+        }
+    }
+}
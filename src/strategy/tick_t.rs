@@ -0,0 +1,53 @@
+use crate::account::{Account, StockCode};
+use crate::model::TickData;
+use crate::replay::OrderBook;
+use crate::strategy::TickStrategy;
+
+/// 挂单做 T：在最新价下方挂买单，成交后在成本上方挂卖单
+#[derive(Debug, Clone, PartialEq)]
+pub struct TickTStrategy {
+    /// 买单挂在最新价下方的百分比
+    pub buy_drawdown_pct: f64,
+    /// 卖单挂在成本价上方的百分比
+    pub stop_profit_pct: f64,
+    /// 每笔委托数量
+    pub volume: i32,
+}
+
+impl Default for TickTStrategy {
+    fn default() -> Self {
+        Self {
+            buy_drawdown_pct: 0.005,
+            stop_profit_pct: 0.005,
+            volume: 1000,
+        }
+    }
+}
+
+/// 按 A 股最小变动价位 0.01 取整
+fn round_price(price: f64) -> f64 {
+    (price * 100.0).round() / 100.0
+}
+
+impl TickStrategy for TickTStrategy {
+    fn on_tick(&mut self, tick: &TickData, code: &str, account: &Account, book: &mut OrderBook) {
+        let (available, cost) = account
+            .hold
+            .get(&StockCode::from(code))
+            .map(|p| (p.available_vol, p.cost_price))
+            .unwrap_or((0, 0.0));
+
+        // 有可卖持仓时挂止盈卖单
+        if available > 0 && !book.has('S') {
+            let price = round_price(cost * (1.0 + self.stop_profit_pct));
+            book.place(tick.time, price, available.min(self.volume), 'S');
+        }
+        // 没有买单时在下方挂买单
+        if !book.has('B') {
+            let price = round_price(tick.last_price * (1.0 - self.buy_drawdown_pct));
+            if account.available_balance >= price * self.volume as f64 {
+                book.place(tick.time, price, self.volume, 'B');
+            }
+        }
+    }
+}
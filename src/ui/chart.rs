@@ -0,0 +1,114 @@
+use backtest::data::bar_date;
+use backtest::engine::BacktestResult;
+use backtest::model::KLine;
+use eframe::egui::{self, Color32, Stroke};
+use egui_plot::{Bar, BarChart, BoxElem, BoxPlot, BoxSpread, HLine, Legend, Line, MarkerShape, Plot, PlotPoints, Points};
+
+/// A 股习惯：红涨绿跌
+pub const UP_COLOR: Color32 = Color32::from_rgb(214, 39, 40);
+pub const DOWN_COLOR: Color32 = Color32::from_rgb(44, 160, 44);
+
+/// K 线图上叠加的策略参数
+pub struct KLineOverlay {
+    /// 入场价格区间
+    pub entry_range: [f64; 2],
+    /// 清仓价格
+    pub liquidation_price: f64,
+}
+
+/// 横轴为 K 线序号，刻度显示为日期
+fn date_label(bars: &[KLine], x: f64) -> String {
+    let i = x.round();
+    if i < 0.0 || i as usize >= bars.len() {
+        return String::new();
+    }
+    bar_date(bars[i as usize].time).format("%Y-%m-%d").to_string()
+}
+
+/// 时间戳对应的 K 线序号
+fn bar_index(bars: &[KLine], time: i64) -> Option<usize> {
+    bars.binary_search_by_key(&time, |b| b.time).ok()
+}
+
+/// K 线 + 成交量，买卖点、入场区间、清仓价和持仓成本线叠加在 K 线上
+pub fn kline_chart(ui: &mut egui::Ui, bars: &[KLine], result: Option<&BacktestResult>, overlay: &KLineOverlay, height: f32) {
+    let candles: Vec<BoxElem> = bars
+        .iter()
+        .enumerate()
+        .map(|(i, b)| {
+            let color = if b.close >= b.open { UP_COLOR } else { DOWN_COLOR };
+            let (lo, hi) = (b.open.min(b.close), b.open.max(b.close));
+            BoxElem::new(i as f64, BoxSpread::new(b.low, lo, (lo + hi) / 2.0, hi, b.high))
+                .name(date_label(bars, i as f64))
+                .whisker_width(0.0)
+                .box_width(0.7)
+                .fill(color)
+                .stroke(Stroke::new(1.0, color))
+        })
+        .collect();
+
+    let mut buys = Vec::new();
+    let mut sells = Vec::new();
+    let mut cost_segments: Vec<Vec<[f64; 2]>> = Vec::new();
+    if let Some(result) = result {
+        for t in &result.account.transactions {
+            let Some(i) = bar_index(bars, t.time) else { continue };
+            if t.volume > 0 {
+                buys.push([i as f64, bars[i].low * 0.99]);
+            } else {
+                sells.push([i as f64, bars[i].high * 1.01]);
+            }
+        }
+        // 持仓成本线，空仓处断开
+        let mut last_index = None;
+        for p in &result.positions {
+            let Some(i) = bar_index(bars, p.time) else { continue };
+            if last_index.is_none_or(|last| last + 1 != i) {
+                cost_segments.push(Vec::new());
+            }
+            if let Some(segment) = cost_segments.last_mut() {
+                segment.push([i as f64, p.cost_price]);
+            }
+            last_index = Some(i);
+        }
+    }
+
+    Plot::new("kline")
+        .height(height * 0.75)
+        .legend(Legend::default())
+        .link_axis("kline_axis", [true, false])
+        .link_cursor("kline_axis", [true, false])
+        .x_axis_formatter(|mark, _range| date_label(bars, mark.value))
+        .show(ui, |plot_ui| {
+            plot_ui.box_plot(BoxPlot::new("K线", candles));
+            plot_ui.hline(HLine::new("入场下限", overlay.entry_range[0]).color(Color32::from_rgb(31, 119, 180)));
+            plot_ui.hline(HLine::new("入场上限", overlay.entry_range[1]).color(Color32::from_rgb(31, 119, 180)));
+            plot_ui.hline(HLine::new("清仓价格", overlay.liquidation_price).color(Color32::from_rgb(148, 103, 189)));
+            for segment in cost_segments {
+                plot_ui.line(Line::new("持仓成本", PlotPoints::new(segment)).color(Color32::from_rgb(255, 127, 14)));
+            }
+            if !buys.is_empty() {
+                plot_ui.points(Points::new("买入", buys).shape(MarkerShape::Up).radius(5.0).filled(true).color(UP_COLOR));
+            }
+            if !sells.is_empty() {
+                plot_ui.points(Points::new("卖出", sells).shape(MarkerShape::Down).radius(5.0).filled(true).color(DOWN_COLOR));
+            }
+        });
+
+    let volumes: Vec<Bar> = bars
+        .iter()
+        .enumerate()
+        .map(|(i, b)| {
+            let color = if b.close >= b.open { UP_COLOR } else { DOWN_COLOR };
+            Bar::new(i as f64, b.volume as f64).width(0.7).fill(color)
+        })
+        .collect();
+    Plot::new("volume")
+        .height(height * 0.25)
+        .link_axis("kline_axis", [true, false])
+        .link_cursor("kline_axis", [true, false])
+        .x_axis_formatter(|mark, _range| date_label(bars, mark.value))
+        .show(ui, |plot_ui| {
+            plot_ui.bar_chart(BarChart::new("成交量", volumes));
+        });
+}
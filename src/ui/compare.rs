@@ -0,0 +1,144 @@
+use backtest::data::bar_date;
+use backtest::engine::BacktestResult;
+use backtest::metrics::Metrics;
+use backtest::strategy::k_strategy::KStrategyParams;
+use chrono::NaiveDate;
+use eframe::egui;
+use egui_plot::{Legend, Line, Plot, PlotPoints};
+
+use crate::ui::worker::RunJob;
+
+/// 一次完成的回测，用于多次运行的对比
+pub struct RunRecord {
+    /// 运行编号，从 1 开始
+    pub id: usize,
+    pub code: String,
+    pub start: Option<NaiveDate>,
+    pub end: Option<NaiveDate>,
+    pub params: KStrategyParams,
+    pub metrics: Metrics,
+    /// 净值曲线 (时间戳, 资产/初始资金)
+    pub nav: Vec<(i64, f64)>,
+    /// 是否在对比图中显示
+    pub visible: bool,
+}
+
+impl RunRecord {
+    pub fn label(&self) -> String {
+        format!("#{} {}", self.id, self.code)
+    }
+
+    fn range_text(&self) -> String {
+        let fmt = |d: Option<NaiveDate>| d.map(|d| d.to_string()).unwrap_or_else(|| "…".to_string());
+        format!("{} ~ {}", fmt(self.start), fmt(self.end))
+    }
+
+    fn params_text(&self) -> String {
+        let p = &self.params;
+        format!(
+            "区间[{:.2}, {:.2}] 底仓{} 加仓回撤{:.3} 止盈{:.3} 清仓价{:.2}",
+            p.buy_price_low,
+            p.buy_price_high,
+            p.init_base_volume,
+            p.add_pos_drawdown_pct,
+            p.init_stop_profit,
+            p.liquidation_price
+        )
+    }
+}
+
+/// 运行历史和对比视图
+#[derive(Default)]
+pub struct RunHistory {
+    runs: Vec<RunRecord>,
+    next_id: usize,
+}
+
+impl RunHistory {
+    /// 记录一次完成的回测
+    pub fn push(&mut self, job: &RunJob, result: &BacktestResult) {
+        self.next_id += 1;
+        self.runs.push(RunRecord {
+            id: self.next_id,
+            code: job.code.clone(),
+            start: job.start,
+            end: job.end,
+            params: job.params.clone(),
+            metrics: Metrics::from_result(result),
+            nav: result.equity.iter().map(|&(t, v)| (t, v / result.init_cash)).collect(),
+            visible: true,
+        });
+    }
+
+    pub fn get(&self, id: usize) -> Option<&RunRecord> {
+        self.runs.iter().find(|r| r.id == id)
+    }
+
+    /// 显示对比图和指标表，返回点击了“恢复参数”的运行编号
+    pub fn show(&mut self, ui: &mut egui::Ui) -> Option<usize> {
+        if self.runs.is_empty() {
+            ui.label("完成回测后在这里对比多次运行");
+            return None;
+        }
+
+        // 横轴为时间戳（秒），区间不同的运行也能对齐
+        Plot::new("compare_nav")
+            .height(ui.available_height() * 0.55)
+            .legend(Legend::default())
+            .x_axis_formatter(|mark, _range| bar_date(mark.value as i64).format("%Y-%m-%d").to_string())
+            .y_axis_formatter(|mark, _range| format!("{:.2}", mark.value))
+            .show(ui, |plot_ui| {
+                for run in self.runs.iter().filter(|r| r.visible) {
+                    let points: Vec<[f64; 2]> = run.nav.iter().map(|&(t, v)| [t as f64, v]).collect();
+                    plot_ui.line(Line::new(run.label(), PlotPoints::new(points)));
+                }
+            });
+
+        let mut restore = None;
+        let mut remove = None;
+        ui.horizontal(|ui| {
+            if ui.button("全部显示").clicked() {
+                self.runs.iter_mut().for_each(|r| r.visible = true);
+            }
+            if ui.button("全部隐藏").clicked() {
+                self.runs.iter_mut().for_each(|r| r.visible = false);
+            }
+            if ui.button("清空历史").clicked() {
+                self.runs.clear();
+            }
+        });
+        egui::ScrollArea::both().show(ui, |ui| {
+            egui::Grid::new("compare_metrics").striped(true).show(ui, |ui| {
+                for title in ["显示", "运行", "区间", "总收益", "年化", "最大回撤", "夏普", "笔数", "期末资产", "参数", ""] {
+                    ui.strong(title);
+                }
+                ui.end_row();
+                for run in self.runs.iter_mut() {
+                    ui.checkbox(&mut run.visible, "");
+                    ui.label(run.label());
+                    ui.label(run.range_text());
+                    ui.label(format!("{:.2}%", run.metrics.total_return * 100.0));
+                    ui.label(format!("{:.2}%", run.metrics.annual_return * 100.0));
+                    ui.label(format!("{:.2}%", run.metrics.max_drawdown * 100.0));
+                    ui.label(format!("{:.3}", run.metrics.sharpe));
+                    ui.label(run.metrics.trade_count.to_string());
+                    ui.label(format!("{:.2}", run.metrics.final_balance));
+                    ui.label(run.params_text());
+                    ui.horizontal(|ui| {
+                        if ui.button("恢复参数").clicked() {
+                            restore = Some(run.id);
+                        }
+                        if ui.button("删除").clicked() {
+                            remove = Some(run.id);
+                        }
+                    });
+                    ui.end_row();
+                }
+            });
+        });
+        if let Some(id) = remove {
+            self.runs.retain(|r| r.id != id);
+        }
+        restore
+    }
+}
@@ -1,16 +1,85 @@
-use crate::account::Account;
-use crate::model::KLine;
-use crate::strategy::k_strategy::KStrategy;
-use csv::Reader;
+mod chart;
+mod compare;
+mod replay;
+mod results;
+mod state;
+mod sweep;
+mod worker;
+
+use backtest::account::FeeModel;
+use backtest::config::{BacktestConfig, DataConfig, DateRange, OutputConfig, StrategyConfig};
+use backtest::data::{find_symbol_file, list_symbols, SymbolFile};
+use backtest::engine::BacktestResult;
+use backtest::model::KLine;
+use backtest::strategy::k_strategy::KStrategyParams;
+use chart::{kline_chart, KLineOverlay};
+use compare::RunHistory;
+use replay::ReplayView;
+use results::{drawdown_plot, ResultsView};
 use eframe::egui;
 use egui_plot::{Line, Plot, PlotPoints};
+use chrono::NaiveDate;
+use state::SavedState;
+use sweep::{SweepAction, SweepView};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
 use std::sync::Arc;
-use crate::account::StockCode;
+use worker::{RunJob, Worker, WorkerMsg};
+
+/// 主区域的页签
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tab {
+    KLine,
+    Equity,
+    Results,
+    Compare,
+    Sweep,
+    Replay,
+}
 
 pub struct StrategyApp {
     strategy_params: StrategyParams,
-    running: bool,
+    /// 当前页签
+    tab: Tab,
+    results_view: ResultsView,
+    /// 已完成的回测，用于对比
+    history: RunHistory,
+    /// 两参数扫描热力图
+    sweep: SweepView,
+    /// Tick 回放
+    replay: ReplayView,
+    /// 正在运行的后台回测
+    worker: Option<Worker>,
+    /// 正在运行的任务，结束后记入历史
+    running: Option<RunJob>,
+    /// 进度 (已处理, 总数)
+    progress: (usize, usize),
     balance_points: Vec<[f64; 2]>,
+    /// 最近一次运行的 K 线
+    bars: Vec<KLine>,
+    /// 最近一次运行的结果
+    result: Option<BacktestResult>,
+    /// 数据目录
+    data_root: String,
+    /// 数据目录中扫描到的标的
+    symbols: Vec<SymbolFile>,
+    /// 选中的股票代码
+    symbol: String,
+    /// 日期区间 YYYY-MM-DD，留空表示不限
+    start_date: String,
+    end_date: String,
+    /// 配置文件路径
+    config_path: String,
+    /// 已加载的配置，运行时使用其中的数据、资金和手续费
+    config: Option<BacktestConfig>,
+    /// 命名预设
+    presets: BTreeMap<String, BacktestConfig>,
+    /// 预设名输入框
+    preset_name: String,
+    /// 加载配置或运行出错时的提示
+    error: Option<String>,
+    /// 操作成功的提示
+    notice: Option<String>,
 }
 
 struct StrategyParams {
@@ -31,15 +100,136 @@ impl Default for StrategyApp {
                 t_stop_profit: 0.1,
                 liquidation_price: 8.5,
             },
-            running: false,
+            tab: Tab::KLine,
+            results_view: ResultsView::default(),
+            history: RunHistory::default(),
+            sweep: SweepView::default(),
+            replay: ReplayView::default(),
+            worker: None,
+            running: None,
+            progress: (0, 0),
             balance_points: Vec::new(),
+            bars: Vec::new(),
+            result: None,
+            data_root: r"A:\data\day".to_string(),
+            symbols: Vec::new(),
+            symbol: "601111".to_string(),
+            start_date: String::new(),
+            end_date: String::new(),
+            config_path: String::new(),
+            config: None,
+            presets: BTreeMap::new(),
+            preset_name: String::new(),
+            error: None,
+            notice: None,
+        }
+    }
+}
+
+impl StrategyParams {
+    fn to_k_params(&self) -> KStrategyParams {
+        KStrategyParams {
+            buy_price_low: self.entry_range[0],
+            buy_price_high: self.entry_range[1],
+            init_base_volume: self.base_volume,
+            add_pos_drawdown_pct: self.t_stop_loss_pct,
+            init_stop_profit: self.t_stop_profit,
+            liquidation_price: self.liquidation_price,
+        }
+    }
+
+    fn from_k_params(p: &KStrategyParams) -> Self {
+        Self {
+            entry_range: [p.buy_price_low, p.buy_price_high],
+            base_volume: p.init_base_volume,
+            t_stop_loss_pct: p.add_pos_drawdown_pct,
+            t_stop_profit: p.init_stop_profit,
+            liquidation_price: p.liquidation_price,
         }
     }
 }
 
 impl eframe::App for StrategyApp {
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, eframe::APP_KEY, &self.saved_state());
+    }
+
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        egui::CentralPanel::default().show(ctx, |ui| {
+        self.poll_worker();
+
+        egui::SidePanel::left("params").resizable(true).show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("配置文件：");
+                ui.text_edit_singleline(&mut self.config_path);
+                if ui.button("加载").clicked() {
+                    self.load_config();
+                }
+            });
+            if let Some(config) = &self.config {
+                ui.label(format!("初始资金：{:.0}  佣金费率：{}", config.initial_cash, config.fees.commission_ratio));
+            }
+            ui.horizontal(|ui| {
+                ui.label("预设：");
+                egui::ComboBox::from_id_salt("preset")
+                    .selected_text(self.preset_name.as_str())
+                    .show_ui(ui, |ui| {
+                        for name in self.presets.keys() {
+                            ui.selectable_value(&mut self.preset_name, name.clone(), name.as_str());
+                        }
+                    });
+                ui.add(egui::TextEdit::singleline(&mut self.preset_name).hint_text("名称").desired_width(80.0));
+            });
+            ui.horizontal(|ui| {
+                if ui.button("保存预设").clicked() {
+                    self.save_preset();
+                }
+                let exists = self.presets.contains_key(self.preset_name.trim());
+                if ui.add_enabled(exists, egui::Button::new("载入")).clicked() {
+                    self.load_preset();
+                }
+                if ui.add_enabled(exists, egui::Button::new("删除")).clicked() {
+                    self.presets.remove(self.preset_name.trim());
+                }
+                if ui
+                    .add_enabled(exists, egui::Button::new("导出"))
+                    .on_hover_text("导出到配置文件路径，留空时为 <预设名>.toml")
+                    .clicked()
+                {
+                    self.export_preset();
+                }
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("数据目录：");
+                ui.text_edit_singleline(&mut self.data_root);
+                if ui.button("扫描").clicked() {
+                    self.scan_symbols();
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label("股票：");
+                egui::ComboBox::from_id_salt("symbol")
+                    .selected_text(self.symbol.as_str())
+                    .show_ui(ui, |ui| {
+                        for s in &self.symbols {
+                            ui.selectable_value(&mut self.symbol, s.code.clone(), s.code.as_str());
+                        }
+                    });
+                ui.label("代码：");
+                ui.add(egui::TextEdit::singleline(&mut self.symbol).desired_width(70.0));
+            });
+            ui.horizontal(|ui| {
+                ui.label("日期：");
+                ui.add(egui::TextEdit::singleline(&mut self.start_date).hint_text("开始").desired_width(80.0));
+                ui.label("到");
+                ui.add(egui::TextEdit::singleline(&mut self.end_date).hint_text("结束").desired_width(80.0));
+            });
+            if let Some(error) = &self.error {
+                ui.colored_label(egui::Color32::RED, error);
+            } else if let Some(notice) = &self.notice {
+                ui.label(notice);
+            }
+
             ui.heading("K线策略参数设置");
             
             ui.horizontal(|ui| {
@@ -71,67 +261,355 @@ impl eframe::App for StrategyApp {
 
             ui.add_space(10.0);
 
-            if ui.button(if self.running { "停止策略" } else { "运行策略" }).clicked() {
-                if !self.running {
-                    self.running = true;  // Set running state before execution
-                    self.run_strategy();
-                    self.running = false;  // Reset state after completion
-                } 
+            let running = self.worker.is_some();
+            if ui.button(if running { "停止策略" } else { "运行策略" }).clicked() {
+                match &self.worker {
+                    Some(worker) => worker.cancel(),
+                    None => self.start_run(ctx),
+                }
+            }
+            if let Some(worker) = &self.worker {
+                let (done, total) = self.progress;
+                let fraction = if total > 0 { done as f32 / total as f32 } else { 0.0 };
+                let text = if worker.is_cancelling() { "正在停止…".to_string() } else { format!("{}/{}", done, total) };
+                ui.add(egui::ProgressBar::new(fraction).text(text));
             }
 
-            // 显示资金曲线
-            if !self.balance_points.is_empty() {
-                ui.add_space(20.0);
-                Plot::new("资金变化").show(ui, |plot_ui| {
-                    let plot_points = PlotPoints::new(self.balance_points.clone());
-                    plot_ui.line(Line::new("资金", plot_points));
-                });
+        });
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.selectable_value(&mut self.tab, Tab::KLine, "K线");
+                ui.selectable_value(&mut self.tab, Tab::Equity, "资金曲线");
+                ui.selectable_value(&mut self.tab, Tab::Results, "回测结果");
+                ui.selectable_value(&mut self.tab, Tab::Compare, "运行对比");
+                ui.selectable_value(&mut self.tab, Tab::Sweep, "参数热力图");
+                ui.selectable_value(&mut self.tab, Tab::Replay, "Tick回放");
+            });
+            ui.separator();
+
+            match self.tab {
+                Tab::KLine => {
+                    if self.bars.is_empty() {
+                        ui.label("运行策略后显示K线");
+                    } else {
+                        let overlay = KLineOverlay {
+                            entry_range: self.strategy_params.entry_range,
+                            liquidation_price: self.strategy_params.liquidation_price,
+                        };
+                        let height = ui.available_height();
+                        kline_chart(ui, &self.bars, self.result.as_ref(), &overlay, height);
+                    }
+                }
+                Tab::Equity => {
+                    // 运行中也显示已推送的资金曲线
+                    let height = ui.available_height();
+                    Plot::new("资金变化")
+                        .height(height * 0.65)
+                        .link_axis("equity_axis", [true, false])
+                        .show(ui, |plot_ui| {
+                            let plot_points = PlotPoints::new(self.balance_points.clone());
+                            plot_ui.line(Line::new("资金", plot_points));
+                        });
+                    if let Some(result) = &self.result {
+                        drawdown_plot(ui, result, height * 0.3);
+                    }
+                }
+                Tab::Results => match &self.result {
+                    Some(result) => self.results_view.show(ui, result),
+                    None => {
+                        ui.label("运行策略后显示结果");
+                    }
+                },
+                Tab::Compare => {
+                    if let Some(id) = self.history.show(ui) {
+                        self.restore_run(id);
+                    }
+                }
+                Tab::Sweep => match self.sweep.show(ui) {
+                    Some(SweepAction::Start) => self.sweep.start(ctx, self.build_job()),
+                    // 用点中的参数回测并切到结果页
+                    Some(SweepAction::Pick(set)) if self.worker.is_none() => {
+                        let params = self.strategy_params.to_k_params().with(&set);
+                        self.strategy_params = StrategyParams::from_k_params(&params);
+                        self.start_run(ctx);
+                        self.tab = Tab::Results;
+                    }
+                    _ => {}
+                },
+                Tab::Replay => self.replay.show(ui),
             }
         });
     }
 }
 
 impl StrategyApp {
-    fn run_strategy(&mut self) {
-        let mut strategy = KStrategy::new(
-            self.strategy_params.entry_range[0],
-            self.strategy_params.entry_range[1],
-            self.strategy_params.base_volume,
-            self.strategy_params.t_stop_loss_pct,
-            self.strategy_params.t_stop_profit,
-            self.strategy_params.liquidation_price,
-        );
-        
-        // TODO: 实现实际的策略运行逻辑
-        let mut account = Account {
-            balance: 1_000_000.0,
-            available_balance: 1_000_000.0,
-            ..Default::default()
-        }; // 初始资金100万
-        let mut bars = Reader::from_path(r"A:\data\day\USHA601111.csv").unwrap();
-        let code = "600795";
-        // let mut strategy = KStrategy::new([5.9, 7.9],10000,0.02, 0.1, 9.0);
-        self.balance_points.clear();
-        let mut time_index = 0.0_f64;
+    /// 从 eframe 存储恢复上次关闭时的状态
+    fn new(cc: &eframe::CreationContext<'_>) -> Self {
+        let mut app = Self::default();
+        if let Some(state) = cc.storage.and_then(|s| eframe::get_value::<SavedState>(s, eframe::APP_KEY)) {
+            app.restore(state);
+        }
+        app
+    }
+
+    fn saved_state(&self) -> SavedState {
+        SavedState {
+            params: self.strategy_params.to_k_params().to_param_set(),
+            data_root: self.data_root.clone(),
+            symbol: self.symbol.clone(),
+            start_date: self.start_date.clone(),
+            end_date: self.end_date.clone(),
+            config_path: self.config_path.clone(),
+            config: self.config.clone(),
+            presets: self.presets.clone(),
+            preset_name: self.preset_name.clone(),
+        }
+    }
+
+    fn restore(&mut self, state: SavedState) {
+        self.strategy_params = StrategyParams::from_k_params(&KStrategyParams::default().with(&state.params));
+        self.data_root = state.data_root;
+        self.symbol = state.symbol;
+        self.start_date = state.start_date;
+        self.end_date = state.end_date;
+        self.config_path = state.config_path;
+        self.config = state.config;
+        self.presets = state.presets;
+        self.preset_name = state.preset_name;
+        self.symbols = list_symbols(self.data_root.trim());
+    }
+
+    /// 读取配置文件，用其中的策略参数覆盖界面参数
+    fn load_config(&mut self) {
+        match BacktestConfig::load(self.config_path.trim()) {
+            Ok(config) => self.apply_config(config),
+            Err(e) => self.error = Some(format!("配置错误 {}", e)),
+        }
+    }
+
+    fn apply_config(&mut self, config: BacktestConfig) {
+        if config.strategy.name != "k" {
+            self.error = Some(format!("界面只支持 k 策略，配置中为 {}", config.strategy.name));
+            return;
+        }
+        let params = KStrategyParams::default().with(&config.params());
+        self.strategy_params = StrategyParams::from_k_params(&params);
+        let root = config.data.dir.clone().or_else(|| config.data.path.as_ref()?.parent().map(PathBuf::from));
+        if let Some(root) = root {
+            self.data_root = root.display().to_string();
+        }
+        self.symbol = config.data.code.clone();
+        self.start_date = config.range.start.clone().unwrap_or_default();
+        self.end_date = config.range.end.clone().unwrap_or_default();
+        self.config = Some(config);
+        self.error = None;
+        self.scan_symbols();
+    }
+
+    /// 把界面当前的数据、区间和参数组成一份配置，资金、手续费和输出沿用已加载的配置
+    fn current_config(&self) -> Result<BacktestConfig, String> {
+        let code = self.symbol.trim().to_string();
+        let data = match &self.config {
+            Some(config) if config.data.code == code && config.data.path.is_some() => config.data.clone(),
+            _ => DataConfig {
+                path: None,
+                dir: Some(PathBuf::from(self.data_root.trim())),
+                code,
+            },
+        };
+        let text = |s: &str| Some(s.trim().to_string()).filter(|s| !s.is_empty());
+        let (initial_cash, fees, output) = match &self.config {
+            Some(c) => (c.initial_cash, c.fees.clone(), c.output.clone()),
+            None => (1_000_000.0, FeeModel::default(), OutputConfig::default()),
+        };
+        let config = BacktestConfig {
+            data,
+            range: DateRange {
+                start: text(&self.start_date),
+                end: text(&self.end_date),
+            },
+            initial_cash,
+            fees,
+            strategy: StrategyConfig {
+                name: "k".to_string(),
+                params: self.strategy_params.to_k_params().to_param_set(),
+            },
+            output,
+            risk: self.config.as_ref().and_then(|c| c.risk.clone()),
+            margin: self.config.as_ref().and_then(|c| c.margin.clone()),
+            futures: self.config.as_ref().map(|c| c.futures.clone()).unwrap_or_default(),
+        };
+        config.validate().map_err(|e| format!("配置错误 {}", e))?;
+        Ok(config)
+    }
+
+    /// 以当前界面设置保存预设，同名覆盖
+    fn save_preset(&mut self) {
+        let name = self.preset_name.trim().to_string();
+        if name.is_empty() {
+            self.error = Some("请输入预设名称".to_string());
+            return;
+        }
+        match self.current_config() {
+            Ok(config) => {
+                self.presets.insert(name.clone(), config);
+                self.preset_name = name.clone();
+                self.error = None;
+                self.notice = Some(format!("已保存预设 {}", name));
+            }
+            Err(e) => self.error = Some(e),
+        }
+    }
+
+    fn load_preset(&mut self) {
+        let Some(config) = self.presets.get(self.preset_name.trim()).cloned() else { return };
+        self.apply_config(config);
+        if self.error.is_none() {
+            self.notice = Some(format!("已载入预设 {}", self.preset_name.trim()));
+        }
+    }
+
+    /// 把预设写成配置文件，格式由扩展名决定
+    fn export_preset(&mut self) {
+        let name = self.preset_name.trim();
+        let Some(config) = self.presets.get(name) else { return };
+        let path = match self.config_path.trim() {
+            "" => PathBuf::from(format!("{}.toml", name)),
+            path => PathBuf::from(path),
+        };
+        match config.save(&path) {
+            Ok(()) => {
+                self.error = None;
+                self.notice = Some(format!("已导出到 {}", path.display()));
+            }
+            Err(e) => self.error = Some(e.to_string()),
+        }
+    }
+
+    /// 把历史运行的参数、标的和区间恢复到界面
+    fn restore_run(&mut self, id: usize) {
+        let Some(run) = self.history.get(id) else { return };
+        self.strategy_params = StrategyParams::from_k_params(&run.params);
+        self.symbol = run.code.clone();
+        self.start_date = run.start.map(|d| d.to_string()).unwrap_or_default();
+        self.end_date = run.end.map(|d| d.to_string()).unwrap_or_default();
+        self.error = None;
+    }
+
+    /// 扫描数据目录中的标的
+    fn scan_symbols(&mut self) {
+        self.symbols = list_symbols(self.data_root.trim());
+        if self.symbols.is_empty() {
+            self.error = Some(format!("{} 中没有找到 CSV 数据", self.data_root));
+        } else if !self.symbols.iter().any(|s| s.code == self.symbol) {
+            self.symbol = self.symbols[0].code.clone();
+        }
+    }
+
+    /// 选中股票的数据文件，配置中直接指定的文件优先
+    fn data_path(&self) -> Option<PathBuf> {
+        let code = self.symbol.trim();
+        if let Some(config) = &self.config
+            && config.data.code == code
+            && let Some(path) = &config.data.path
+        {
+            return Some(path.clone());
+        }
+        self.symbols
+            .iter()
+            .find(|s| s.code == code)
+            .map(|s| s.path.clone())
+            .or_else(|| find_symbol_file(self.data_root.trim(), code))
+    }
 
-        let iter = bars.deserialize();
-        for ite in iter {
-            let bar: KLine = ite.unwrap();
-            strategy.process_bar(&bar, &code, &mut account);
-            // todo 计算的金额不对 
-            self.balance_points.push([time_index, account.balance]);
-            time_index += 1.0;
+    fn build_job(&self) -> Result<RunJob, String> {
+        let code = self.symbol.trim();
+        if code.is_empty() || code.len() > 8 || !code.is_ascii() {
+            return Err(format!("无效的股票代码 {}", code));
+        }
+        let data_path = self
+            .data_path()
+            .ok_or_else(|| format!("{} 中没有 {} 的数据文件", self.data_root, code))?;
+        let parse = |name: &str, text: &str| -> Result<Option<NaiveDate>, String> {
+            let text = text.trim();
+            if text.is_empty() {
+                return Ok(None);
+            }
+            NaiveDate::parse_from_str(text, "%Y-%m-%d")
+                .map(Some)
+                .map_err(|_| format!("{}格式应为 YYYY-MM-DD：{}", name, text))
+        };
+        let start = parse("开始日期", &self.start_date)?;
+        let end = parse("结束日期", &self.end_date)?;
+        if let (Some(s), Some(e)) = (start, end)
+            && s > e
+        {
+            return Err(format!("开始日期 {} 晚于结束日期 {}", s, e));
         }
-        // 7. 打印结果
-        let position = account.hold.get(&StockCode::from(code)).unwrap();
+        Ok(RunJob {
+            params: self.strategy_params.to_k_params(),
+            data_path,
+            code: code.to_string(),
+            start,
+            end,
+            config: self.current_config()?,
+        })
+    }
 
-        strategy.print_results(&account.transactions, position, &account);
+    /// 在后台线程启动回测
+    fn start_run(&mut self, ctx: &egui::Context) {
+        let job = match self.build_job() {
+            Ok(job) => job,
+            Err(e) => {
+                self.error = Some(e);
+                return;
+            }
+        };
+        self.error = None;
+        self.balance_points.clear();
+        self.result = None;
+        self.progress = (0, 0);
+        self.running = Some(job.clone());
+        self.worker = Some(Worker::spawn(job, ctx.clone()));
+    }
+
+    /// 处理后台线程的消息：追加资金曲线，结束时保存结果
+    fn poll_worker(&mut self) {
+        let Some(worker) = &self.worker else { return };
+        for msg in worker.poll() {
+            match msg {
+                WorkerMsg::Progress { done, total, equity } => {
+                    self.progress = (done, total);
+                    let start = self.balance_points.len();
+                    self.balance_points
+                        .extend(equity.iter().enumerate().map(|(i, e)| [(start + i) as f64, e.1]));
+                }
+                WorkerMsg::Finished { bars, result, cancelled } => {
+                    self.balance_points = result.equity.iter().enumerate().map(|(i, e)| [i as f64, e.1]).collect();
+                    if cancelled {
+                        self.error = Some(format!("已停止，处理了 {}/{} 根K线", result.equity.len(), bars.len()));
+                    } else if let Some(job) = &self.running {
+                        self.history.push(job, &result);
+                    }
+                    self.running = None;
+                    self.bars = bars;
+                    self.result = Some(*result);
+                    self.worker = None;
+                }
+                WorkerMsg::Failed(e) => {
+                    self.error = Some(e);
+                    self.worker = None;
+                    self.running = None;
+                }
+            }
+        }
     }
 }
 
 pub fn run_app() -> Result<(), eframe::Error> {
     let options = eframe::NativeOptions {
-        viewport: egui::ViewportBuilder::default().with_inner_size([400.0, 500.0]),
+        viewport: egui::ViewportBuilder::default().with_inner_size([1200.0, 800.0]),
         ..Default::default()
     };
     
@@ -152,7 +630,7 @@ pub fn run_app() -> Result<(), eframe::Error> {
                 
             cc.egui_ctx.set_fonts(fonts);
             
-            Ok(Box::new(StrategyApp::default()))
+            Ok(Box::new(StrategyApp::new(cc)))
         }),
     )
 } 
\ No newline at end of file
@@ -0,0 +1,275 @@
+use backtest::data::load_ticks;
+use backtest::engine::new_account;
+use backtest::replay::TickReplay;
+use backtest::strategy::tick_t::TickTStrategy;
+use chrono::{Duration, TimeZone, Utc};
+use eframe::egui;
+use egui::Color32;
+use egui_plot::{Line, MarkerShape, Plot, PlotPoints, Points};
+
+use crate::ui::chart::{DOWN_COLOR, UP_COLOR};
+
+/// 北京时间 时:分:秒
+fn tick_time(time: i64) -> String {
+    let utc = Utc.timestamp_opt(time, 0).single().unwrap_or_default();
+    (utc + Duration::hours(8)).format("%m-%d %H:%M:%S").to_string()
+}
+
+/// Tick 回放：单步或按速度播放，显示五档盘口、策略挂单和成交
+pub struct ReplayView {
+    tick_path: String,
+    code: String,
+    init_cash: f64,
+    strategy: TickTStrategy,
+    replay: Option<TickReplay>,
+    playing: bool,
+    /// 每秒播放的 tick 数
+    speed: f64,
+    /// 未满一笔的播放进度
+    pending: f64,
+    error: Option<String>,
+}
+
+impl Default for ReplayView {
+    fn default() -> Self {
+        Self {
+            tick_path: String::new(),
+            code: "601111".to_string(),
+            init_cash: 1_000_000.0,
+            strategy: TickTStrategy::default(),
+            replay: None,
+            playing: false,
+            speed: 10.0,
+            pending: 0.0,
+            error: None,
+        }
+    }
+}
+
+impl ReplayView {
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        self.settings(ui);
+        if let Some(error) = &self.error {
+            ui.colored_label(Color32::RED, error);
+        }
+        self.controls(ui);
+        ui.separator();
+
+        let Some(replay) = &self.replay else {
+            ui.label("加载 Tick 文件后开始回放");
+            return;
+        };
+        ui.horizontal_top(|ui| {
+            ui.vertical(|ui| {
+                ui.set_width(260.0);
+                ladder(ui, replay);
+                ui.add_space(10.0);
+                resting_orders(ui, replay);
+            });
+            ui.vertical(|ui| {
+                price_plot(ui, replay);
+                fills(ui, replay);
+            });
+        });
+    }
+
+    fn settings(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Tick 文件：");
+            ui.text_edit_singleline(&mut self.tick_path);
+            ui.label("代码：");
+            ui.add(egui::TextEdit::singleline(&mut self.code).desired_width(70.0));
+            if ui.button("加载").clicked() {
+                self.load();
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("初始资金：");
+            ui.add(egui::DragValue::new(&mut self.init_cash).speed(1000.0).range(0.0..=f64::MAX));
+            ui.label("买单回撤：");
+            ui.add(egui::DragValue::new(&mut self.strategy.buy_drawdown_pct).speed(0.001).range(0.0..=1.0));
+            ui.label("止盈：");
+            ui.add(egui::DragValue::new(&mut self.strategy.stop_profit_pct).speed(0.001).range(0.0..=1.0));
+            ui.label("每笔数量：");
+            ui.add(egui::DragValue::new(&mut self.strategy.volume).speed(100).range(100..=i32::MAX));
+        });
+    }
+
+    fn load(&mut self) {
+        self.playing = false;
+        let code = self.code.trim();
+        if code.is_empty() || code.len() > 8 || !code.is_ascii() {
+            self.error = Some(format!("无效的股票代码 {}", code));
+            return;
+        }
+        match load_ticks(self.tick_path.trim()) {
+            Ok(ticks) if ticks.is_empty() => self.error = Some("文件中没有 tick".to_string()),
+            Ok(ticks) => {
+                let strategy = Box::new(self.strategy.clone());
+                self.replay = Some(TickReplay::new(ticks, code, strategy, new_account(self.init_cash)));
+                self.error = None;
+            }
+            Err(e) => self.error = Some(format!("读取 {} 失败：{}", self.tick_path, e)),
+        }
+    }
+
+    fn controls(&mut self, ui: &mut egui::Ui) {
+        let Some(replay) = &mut self.replay else { return };
+        ui.horizontal(|ui| {
+            if ui.button("重置").clicked() {
+                let ticks = replay.ticks().to_vec();
+                let code = replay.code().to_string();
+                *replay = TickReplay::new(ticks, &code, Box::new(self.strategy.clone()), new_account(self.init_cash));
+                self.playing = false;
+            }
+            if ui.add_enabled(!replay.is_finished(), egui::Button::new("单步")).clicked() {
+                replay.step();
+            }
+            let label = if self.playing { "暂停" } else { "播放" };
+            if ui.add_enabled(!replay.is_finished(), egui::Button::new(label)).clicked() {
+                self.playing = !self.playing;
+                self.pending = 0.0;
+            }
+            ui.add(egui::Slider::new(&mut self.speed, 1.0..=500.0).logarithmic(true).text("笔/秒"));
+            let total = replay.ticks().len();
+            ui.add(
+                egui::ProgressBar::new(replay.position() as f32 / total as f32)
+                    .text(format!("{}/{}", replay.position(), total)),
+            );
+        });
+
+        if self.playing {
+            self.pending += ui.input(|i| i.stable_dt) as f64 * self.speed;
+            let n = self.pending.floor();
+            self.pending -= n;
+            replay.advance(n as usize);
+            if replay.is_finished() {
+                self.playing = false;
+            } else {
+                ui.ctx().request_repaint();
+            }
+        }
+    }
+}
+
+/// 五档盘口，卖盘在上、买盘在下，最左列为策略在该价位的挂单
+fn ladder(ui: &mut egui::Ui, replay: &TickReplay) {
+    let Some(tick) = replay.current() else {
+        ui.label("尚未开始");
+        return;
+    };
+    let mine = |price: f64, order_type: char| -> String {
+        let volume: i32 = replay
+            .book
+            .orders()
+            .iter()
+            .filter(|o| o.order_type == order_type && (o.price - price).abs() < 1e-6)
+            .map(|o| o.volume)
+            .sum();
+        if volume > 0 { volume.to_string() } else { String::new() }
+    };
+
+    ui.label(tick_time(tick.time));
+    egui::Grid::new("ladder").striped(true).num_columns(4).show(ui, |ui| {
+        for title in ["档位", "挂单", "价格", "数量"] {
+            ui.strong(title);
+        }
+        ui.end_row();
+        for (i, &(price, volume)) in tick.asks().iter().enumerate().rev() {
+            ui.label(format!("卖{}", i + 1));
+            ui.colored_label(DOWN_COLOR, mine(price, 'S'));
+            ui.colored_label(DOWN_COLOR, format!("{:.2}", price));
+            ui.label(volume.to_string());
+            ui.end_row();
+        }
+        ui.strong("最新");
+        ui.label("");
+        ui.strong(format!("{:.2}", tick.last_price));
+        ui.label(tick.volume.to_string());
+        ui.end_row();
+        for (i, &(price, volume)) in tick.bids().iter().enumerate() {
+            ui.label(format!("买{}", i + 1));
+            ui.colored_label(UP_COLOR, mine(price, 'B'));
+            ui.colored_label(UP_COLOR, format!("{:.2}", price));
+            ui.label(volume.to_string());
+            ui.end_row();
+        }
+    });
+
+    let account = &replay.account;
+    ui.label(format!("可用资金 {:.2}  总资产 {:.2}", account.available_balance, account.balance));
+    if let Some(p) = account.hold.values().find(|p| p.volume != 0) {
+        ui.label(format!("持仓 {}（可卖 {}） 成本 {:.3}", p.volume, p.available_vol, p.cost_price));
+    }
+}
+
+/// 全部挂单，包括不在五档内的
+fn resting_orders(ui: &mut egui::Ui, replay: &TickReplay) {
+    ui.strong("策略挂单");
+    if replay.book.orders().is_empty() {
+        ui.label("无");
+        return;
+    }
+    egui::Grid::new("resting").striped(true).show(ui, |ui| {
+        for o in replay.book.orders() {
+            let buy = o.order_type == 'B';
+            ui.label(format!("#{}", o.id));
+            ui.colored_label(if buy { UP_COLOR } else { DOWN_COLOR }, if buy { "买" } else { "卖" });
+            ui.label(format!("{:.2}", o.price));
+            ui.label(o.volume.to_string());
+            ui.end_row();
+        }
+    });
+}
+
+/// 已回放部分的最新价和成交点，横轴为 tick 序号
+fn price_plot(ui: &mut egui::Ui, replay: &TickReplay) {
+    let ticks = &replay.ticks()[..replay.position()];
+    let prices: Vec<[f64; 2]> = ticks.iter().enumerate().map(|(i, t)| [i as f64, t.last_price]).collect();
+    // 成交时间对应的 tick 序号
+    let index_of = |time: i64| ticks.partition_point(|t| t.time < time) as f64;
+    let marks = |order_type: char| -> Vec<[f64; 2]> {
+        replay
+            .fills
+            .iter()
+            .filter(|f| f.order_type == order_type)
+            .map(|f| [index_of(f.time), f.price])
+            .collect()
+    };
+    let (buys, sells) = (marks('B'), marks('S'));
+    Plot::new("tick_price")
+        .height(ui.available_height() * 0.6)
+        .show(ui, |plot_ui| {
+            plot_ui.line(Line::new("最新价", PlotPoints::new(prices)));
+            plot_ui.points(
+                Points::new("买入", buys)
+                    .shape(MarkerShape::Up)
+                    .radius(6.0)
+                    .color(UP_COLOR),
+            );
+            plot_ui.points(
+                Points::new("卖出", sells)
+                    .shape(MarkerShape::Down)
+                    .radius(6.0)
+                    .color(DOWN_COLOR),
+            );
+        });
+}
+
+/// 成交记录，最新的在最上面
+fn fills(ui: &mut egui::Ui, replay: &TickReplay) {
+    ui.strong(format!("成交 {} 笔", replay.fills.len()));
+    egui::ScrollArea::vertical().id_salt("fills").show(ui, |ui| {
+        egui::Grid::new("fills").striped(true).show(ui, |ui| {
+            for f in replay.fills.iter().rev() {
+                let buy = f.order_type == 'B';
+                ui.label(tick_time(f.time));
+                ui.colored_label(if buy { UP_COLOR } else { DOWN_COLOR }, if buy { "买入" } else { "卖出" });
+                ui.label(format!("{:.2}", f.price));
+                ui.label(f.volume.to_string());
+                ui.label(format!("#{}", f.order_id));
+                ui.end_row();
+            }
+        });
+    });
+}
@@ -0,0 +1,204 @@
+use std::cmp::Ordering;
+
+use backtest::account::Transaction;
+use backtest::data::bar_date;
+use backtest::engine::BacktestResult;
+use backtest::metrics::{drawdown_series, Metrics};
+use eframe::egui;
+use egui_plot::{Line, Plot, PlotPoints};
+
+use crate::ui::chart::{DOWN_COLOR, UP_COLOR};
+
+/// 交易记录表的列
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Column {
+    Date,
+    Side,
+    Price,
+    Volume,
+    Amount,
+    Fee,
+    RemainVol,
+    RemainCost,
+}
+
+impl Column {
+    const ALL: [Column; 8] = [
+        Column::Date,
+        Column::Side,
+        Column::Price,
+        Column::Volume,
+        Column::Amount,
+        Column::Fee,
+        Column::RemainVol,
+        Column::RemainCost,
+    ];
+
+    fn title(&self) -> &'static str {
+        match self {
+            Column::Date => "日期",
+            Column::Side => "方向",
+            Column::Price => "价格",
+            Column::Volume => "数量",
+            Column::Amount => "金额",
+            Column::Fee => "手续费",
+            Column::RemainVol => "成交后数量",
+            Column::RemainCost => "成交后成本",
+        }
+    }
+
+    fn compare(&self, a: &Transaction, b: &Transaction) -> Ordering {
+        let amount = |t: &Transaction| t.price * t.volume.abs() as f64;
+        match self {
+            Column::Date => a.time.cmp(&b.time),
+            Column::Side => a.volume.signum().cmp(&b.volume.signum()),
+            Column::Price => a.price.total_cmp(&b.price),
+            Column::Volume => a.volume.abs().cmp(&b.volume.abs()),
+            Column::Amount => amount(a).total_cmp(&amount(b)),
+            Column::Fee => a.fee.total_cmp(&b.fee),
+            Column::RemainVol => a.remain_vol.cmp(&b.remain_vol),
+            Column::RemainCost => a.remain_cost.total_cmp(&b.remain_cost),
+        }
+    }
+}
+
+/// 结果面板：指标、持仓和可排序的交易记录
+pub struct ResultsView {
+    sort: Column,
+    ascending: bool,
+}
+
+impl Default for ResultsView {
+    fn default() -> Self {
+        Self {
+            sort: Column::Date,
+            ascending: true,
+        }
+    }
+}
+
+fn pct(v: f64) -> String {
+    format!("{:.2}%", v * 100.0)
+}
+
+impl ResultsView {
+    pub fn show(&mut self, ui: &mut egui::Ui, result: &BacktestResult) {
+        let metrics = Metrics::from_result(result);
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            ui.heading("绩效指标");
+            egui::Grid::new("metrics").striped(true).num_columns(4).show(ui, |ui| {
+                ui.label("初始资金");
+                ui.label(format!("{:.2}", result.init_cash));
+                ui.label("期末总资产");
+                ui.label(format!("{:.2}", metrics.final_balance));
+                ui.end_row();
+                ui.label("总收益率");
+                ui.label(pct(metrics.total_return));
+                ui.label("年化收益率");
+                ui.label(pct(metrics.annual_return));
+                ui.end_row();
+                ui.label("最大回撤");
+                ui.label(pct(metrics.max_drawdown));
+                ui.label("年化波动率");
+                ui.label(pct(metrics.volatility));
+                ui.end_row();
+                ui.label("夏普比率");
+                ui.label(format!("{:.3}", metrics.sharpe));
+                ui.label("成交笔数");
+                ui.label(metrics.trade_count.to_string());
+                ui.end_row();
+                ui.label("剩余现金");
+                ui.label(format!("{:.2}", result.account.available_balance));
+                ui.label("持仓市值");
+                ui.label(format!("{:.2}", result.account.portfolio_value));
+                ui.end_row();
+            });
+
+            ui.add_space(10.0);
+            ui.heading("最终持仓");
+            let mut held: Vec<_> = result.account.hold.values().filter(|p| p.volume != 0).collect();
+            held.sort_by(|a, b| a.code.cmp(&b.code));
+            if held.is_empty() {
+                ui.label("空仓");
+            } else {
+                egui::Grid::new("positions").striped(true).show(ui, |ui| {
+                    for title in ["代码", "持仓数量", "可用数量", "成本价", "现价", "市值", "浮动盈亏"] {
+                        ui.strong(title);
+                    }
+                    ui.end_row();
+                    for p in held {
+                        ui.label(p.code.to_string());
+                        ui.label(p.volume.to_string());
+                        ui.label(p.available_vol.to_string());
+                        ui.label(format!("{:.3}", p.cost_price));
+                        ui.label(format!("{:.2}", p.current_price));
+                        ui.label(format!("{:.2}", p.market_value));
+                        ui.label(format!("{:.2}", (p.current_price - p.cost_price) * p.volume as f64));
+                        ui.end_row();
+                    }
+                });
+            }
+
+            ui.add_space(10.0);
+            ui.heading("交易记录");
+            self.transaction_table(ui, &result.account.transactions);
+        });
+    }
+
+    fn transaction_table(&mut self, ui: &mut egui::Ui, transactions: &[Transaction]) {
+        let mut rows: Vec<&Transaction> = transactions.iter().collect();
+        rows.sort_by(|a, b| {
+            let ord = self.sort.compare(a, b);
+            if self.ascending { ord } else { ord.reverse() }
+        });
+
+        egui::Grid::new("transactions").striped(true).show(ui, |ui| {
+            for column in Column::ALL {
+                let arrow = match (self.sort == column, self.ascending) {
+                    (true, true) => " ▲",
+                    (true, false) => " ▼",
+                    _ => "",
+                };
+                if ui.button(format!("{}{}", column.title(), arrow)).clicked() {
+                    if self.sort == column {
+                        self.ascending = !self.ascending;
+                    } else {
+                        self.sort = column;
+                        self.ascending = true;
+                    }
+                }
+            }
+            ui.end_row();
+
+            for t in rows {
+                let buy = t.volume > 0;
+                ui.label(bar_date(t.time).format("%Y-%m-%d").to_string());
+                ui.colored_label(if buy { UP_COLOR } else { DOWN_COLOR }, if buy { "买入" } else { "卖出" });
+                ui.label(format!("{:.2}", t.price));
+                ui.label(t.volume.abs().to_string());
+                ui.label(format!("{:.2}", t.price * t.volume.abs() as f64));
+                ui.label(format!("{:.2}", t.fee));
+                ui.label(t.remain_vol.to_string());
+                ui.label(format!("{:.3}", t.remain_cost));
+                ui.end_row();
+            }
+        });
+    }
+}
+
+/// 回撤曲线，横轴为 K 线序号
+pub fn drawdown_plot(ui: &mut egui::Ui, result: &BacktestResult, height: f32) {
+    let balances: Vec<f64> = result.equity.iter().map(|e| e.1).collect();
+    let points: Vec<[f64; 2]> = drawdown_series(&balances)
+        .into_iter()
+        .enumerate()
+        .map(|(i, d)| [i as f64, d * 100.0])
+        .collect();
+    Plot::new("drawdown")
+        .height(height)
+        .link_axis("equity_axis", [true, false])
+        .y_axis_formatter(|mark, _range| format!("{:.1}%", mark.value))
+        .show(ui, |plot_ui| {
+            plot_ui.line(Line::new("回撤", PlotPoints::new(points)).color(DOWN_COLOR).fill(0.0));
+        });
+}
@@ -0,0 +1,40 @@
+use std::collections::BTreeMap;
+
+use backtest::config::BacktestConfig;
+use backtest::optimize::ParamSet;
+use serde::{Deserialize, Serialize};
+
+/// 关闭时写入 eframe 存储、启动时恢复的界面状态
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct SavedState {
+    /// k 策略参数，键与 `KStrategyParams::NAMES` 一致
+    pub params: ParamSet,
+    pub data_root: String,
+    pub symbol: String,
+    pub start_date: String,
+    pub end_date: String,
+    pub config_path: String,
+    /// 已加载的配置
+    pub config: Option<BacktestConfig>,
+    /// 命名预设，每个预设是一份完整的回测配置
+    pub presets: BTreeMap<String, BacktestConfig>,
+    /// 当前选中的预设名
+    pub preset_name: String,
+}
+
+impl Default for SavedState {
+    fn default() -> Self {
+        Self {
+            params: ParamSet::new(),
+            data_root: r"A:\data\day".to_string(),
+            symbol: "601111".to_string(),
+            start_date: String::new(),
+            end_date: String::new(),
+            config_path: String::new(),
+            config: None,
+            presets: BTreeMap::new(),
+            preset_name: String::new(),
+        }
+    }
+}
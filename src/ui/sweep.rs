@@ -0,0 +1,315 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::thread;
+
+use backtest::engine;
+use backtest::metrics::{Metric, Metrics};
+use backtest::optimize::{ParamRange, ParamSet};
+use backtest::strategy::k_strategy::{KStrategy, KStrategyParams};
+use eframe::egui;
+use egui::{Color32, Pos2, Rect, Sense, Stroke, Vec2};
+
+use crate::ui::chart::{DOWN_COLOR, UP_COLOR};
+use crate::ui::worker::RunJob;
+
+/// 单个参数轴的扫描设置
+struct Axis {
+    name: String,
+    start: f64,
+    end: f64,
+    step: f64,
+}
+
+impl Axis {
+    fn range(&self) -> ParamRange {
+        ParamRange::new(&self.name, self.start, self.end, self.step)
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, label: &str) {
+        ui.horizontal(|ui| {
+            ui.label(label);
+            egui::ComboBox::from_id_salt(label)
+                .selected_text(self.name.as_str())
+                .show_ui(ui, |ui| {
+                    for name in KStrategyParams::NAMES {
+                        ui.selectable_value(&mut self.name, name.to_string(), name);
+                    }
+                });
+            ui.add(egui::DragValue::new(&mut self.start).speed(0.01).prefix("从 "));
+            ui.add(egui::DragValue::new(&mut self.end).speed(0.01).prefix("到 "));
+            ui.add(egui::DragValue::new(&mut self.step).speed(0.001).range(0.0001..=f64::MAX).prefix("步长 "));
+        });
+    }
+}
+
+/// 扫描线程发给界面的消息
+enum SweepMsg {
+    /// 第 index 个格子（行优先，y 为行）的结果
+    Cell { index: usize, metrics: Metrics },
+    Done,
+    Failed(String),
+}
+
+/// 两参数扫描的后台线程
+struct SweepWorker {
+    rx: Receiver<SweepMsg>,
+    cancel: Arc<AtomicBool>,
+}
+
+impl SweepWorker {
+    fn spawn(job: RunJob, x: ParamRange, y: ParamRange, ctx: egui::Context) -> Self {
+        let (tx, rx) = mpsc::channel();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let flag = cancel.clone();
+        thread::spawn(move || {
+            let msg = match sweep(&job, &x, &y, &tx, &flag, &ctx) {
+                Ok(()) => SweepMsg::Done,
+                Err(e) => SweepMsg::Failed(e),
+            };
+            let _ = tx.send(msg);
+            ctx.request_repaint();
+        });
+        Self { rx, cancel }
+    }
+}
+
+fn sweep(
+    job: &RunJob,
+    x: &ParamRange,
+    y: &ParamRange,
+    tx: &Sender<SweepMsg>,
+    cancel: &AtomicBool,
+    ctx: &egui::Context,
+) -> Result<(), String> {
+    let bars = job.load_bars()?;
+    for iy in 0..y.count() {
+        for ix in 0..x.count() {
+            if cancel.load(Ordering::Relaxed) {
+                return Ok(());
+            }
+            let set = ParamSet::from([(x.name.clone(), x.value(ix)), (y.name.clone(), y.value(iy))]);
+            let mut strategy = KStrategy::from_params(&job.params.with(&set));
+            let result = engine::run_with(&mut strategy, &bars, &job.code, job.new_account());
+            let _ = tx.send(SweepMsg::Cell {
+                index: iy * x.count() + ix,
+                metrics: Metrics::from_result(&result),
+            });
+            ctx.request_repaint();
+        }
+    }
+    Ok(())
+}
+
+/// 热力图上的操作，由界面处理
+pub enum SweepAction {
+    /// 按当前界面设置开始扫描
+    Start,
+    /// 点中了一个格子，回测该组参数
+    Pick(ParamSet),
+}
+
+/// 参数热力图：两个参数做网格扫描，按所选指标着色，点击格子回测该组参数
+pub struct SweepView {
+    x: Axis,
+    y: Axis,
+    metric: Metric,
+    /// 本次扫描实际使用的范围
+    ranges: Option<(ParamRange, ParamRange)>,
+    cells: Vec<Option<Metrics>>,
+    worker: Option<SweepWorker>,
+    error: Option<String>,
+}
+
+impl Default for SweepView {
+    fn default() -> Self {
+        Self {
+            x: Axis {
+                name: "add_pos_drawdown_pct".to_string(),
+                start: 0.01,
+                end: 0.05,
+                step: 0.01,
+            },
+            y: Axis {
+                name: "init_stop_profit".to_string(),
+                start: 0.05,
+                end: 0.2,
+                step: 0.05,
+            },
+            metric: Metric::TotalReturn,
+            ranges: None,
+            cells: Vec::new(),
+            worker: None,
+            error: None,
+        }
+    }
+}
+
+/// 在两种颜色间线性插值
+fn lerp_color(a: Color32, b: Color32, t: f32) -> Color32 {
+    let mix = |x: u8, y: u8| (x as f32 + (y as f32 - x as f32) * t).round() as u8;
+    Color32::from_rgb(mix(a.r(), b.r()), mix(a.g(), b.g()), mix(a.b(), b.b()))
+}
+
+impl SweepView {
+    /// 显示扫描设置和热力图
+    pub fn show(&mut self, ui: &mut egui::Ui) -> Option<SweepAction> {
+        self.poll();
+
+        let mut action = None;
+        self.x.show(ui, "横轴：");
+        self.y.show(ui, "纵轴：");
+        ui.horizontal(|ui| {
+            ui.label("指标：");
+            egui::ComboBox::from_id_salt("sweep_metric")
+                .selected_text(self.metric.name())
+                .show_ui(ui, |ui| {
+                    for m in Metric::ALL {
+                        ui.selectable_value(&mut self.metric, m, m.name());
+                    }
+                });
+            match &self.worker {
+                Some(worker) => {
+                    if ui.button("停止扫描").clicked() {
+                        worker.cancel.store(true, Ordering::Relaxed);
+                    }
+                }
+                None => {
+                    if ui.button("开始扫描").clicked() {
+                        action = Some(SweepAction::Start);
+                    }
+                }
+            }
+            if self.worker.is_some() {
+                let done = self.cells.iter().filter(|c| c.is_some()).count();
+                let total = self.cells.len().max(1);
+                ui.add(egui::ProgressBar::new(done as f32 / total as f32).text(format!("{}/{}", done, total)));
+            }
+        });
+        if let Some(error) = &self.error {
+            ui.colored_label(Color32::RED, error);
+        }
+        ui.separator();
+        action.or(self.heatmap(ui).map(SweepAction::Pick))
+    }
+
+    /// 以 job 中的数据和其余参数开始扫描，job 出错时显示错误
+    pub fn start(&mut self, ctx: &egui::Context, job: Result<RunJob, String>) {
+        if self.x.name == self.y.name {
+            self.error = Some("横轴和纵轴需要选择不同的参数".to_string());
+            return;
+        }
+        let job = match job {
+            Ok(job) => job,
+            Err(e) => {
+                self.error = Some(e);
+                return;
+            }
+        };
+        let (x, y) = (self.x.range(), self.y.range());
+        self.error = None;
+        self.cells = vec![None; x.count() * y.count()];
+        self.worker = Some(SweepWorker::spawn(job, x.clone(), y.clone(), ctx.clone()));
+        self.ranges = Some((x, y));
+    }
+
+    fn poll(&mut self) {
+        let Some(worker) = &self.worker else { return };
+        let msgs: Vec<SweepMsg> = worker.rx.try_iter().collect();
+        for msg in msgs {
+            match msg {
+                SweepMsg::Cell { index, metrics } => self.cells[index] = Some(metrics),
+                SweepMsg::Done => self.worker = None,
+                SweepMsg::Failed(e) => {
+                    self.error = Some(e);
+                    self.worker = None;
+                }
+            }
+        }
+    }
+
+    fn heatmap(&self, ui: &mut egui::Ui) -> Option<ParamSet> {
+        let Some((x, y)) = &self.ranges else {
+            ui.label("选择两个参数及范围后开始扫描");
+            return None;
+        };
+        let (nx, ny) = (x.count(), y.count());
+        let scores: Vec<f64> = self.cells.iter().flatten().map(|m| self.metric.score(m)).collect();
+        let lo = scores.iter().copied().fold(f64::INFINITY, f64::min);
+        let hi = scores.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+
+        // 左侧和底部留出坐标标签的位置
+        let margin = Vec2::new(60.0, 24.0);
+        let size = ui.available_size();
+        let (response, painter) = ui.allocate_painter(size, Sense::click());
+        let area = Rect::from_min_max(
+            response.rect.min + Vec2::new(margin.x, 0.0),
+            response.rect.max - Vec2::new(0.0, margin.y),
+        );
+        let cell = Vec2::new(area.width() / nx as f32, area.height() / ny as f32);
+        // 纵轴第 0 个值在最下面
+        let cell_rect = |ix: usize, iy: usize| {
+            let min = Pos2::new(area.min.x + ix as f32 * cell.x, area.max.y - (iy + 1) as f32 * cell.y);
+            Rect::from_min_size(min, cell)
+        };
+        let text_color = ui.visuals().text_color();
+        let font = egui::FontId::proportional(12.0);
+
+        for iy in 0..ny {
+            for ix in 0..nx {
+                let rect = cell_rect(ix, iy);
+                let (fill, text) = match &self.cells[iy * nx + ix] {
+                    Some(m) => {
+                        let t = if hi > lo { ((self.metric.score(m) - lo) / (hi - lo)) as f32 } else { 0.5 };
+                        let value = self.metric.value(m);
+                        let text = match self.metric {
+                            Metric::Sharpe => format!("{:.2}", value),
+                            _ => format!("{:.1}%", value * 100.0),
+                        };
+                        (lerp_color(DOWN_COLOR, UP_COLOR, t), text)
+                    }
+                    None => (ui.visuals().faint_bg_color, String::new()),
+                };
+                painter.rect_filled(rect.shrink(1.0), 0.0, fill);
+                if cell.x > 40.0 && cell.y > 16.0 {
+                    painter.text(rect.center(), egui::Align2::CENTER_CENTER, text, font.clone(), Color32::WHITE);
+                }
+            }
+        }
+        for ix in 0..nx {
+            let pos = Pos2::new(cell_rect(ix, 0).center().x, area.max.y + 4.0);
+            painter.text(pos, egui::Align2::CENTER_TOP, format!("{:.4}", x.value(ix)), font.clone(), text_color);
+        }
+        for iy in 0..ny {
+            let pos = Pos2::new(area.min.x - 4.0, cell_rect(0, iy).center().y);
+            painter.text(pos, egui::Align2::RIGHT_CENTER, format!("{:.4}", y.value(iy)), font.clone(), text_color);
+        }
+
+        let hit = |pos: Pos2| {
+            if !area.contains(pos) {
+                return None;
+            }
+            let ix = (((pos.x - area.min.x) / cell.x) as usize).min(nx - 1);
+            let iy = (((area.max.y - pos.y) / cell.y) as usize).min(ny - 1);
+            Some((ix, iy))
+        };
+        if let Some((ix, iy)) = response.hover_pos().and_then(hit) {
+            painter.rect_stroke(cell_rect(ix, iy), 0.0, Stroke::new(2.0, text_color), egui::StrokeKind::Inside);
+            if let Some(m) = &self.cells[iy * nx + ix] {
+                response.clone().on_hover_text(format!(
+                    "{} = {:.4}\n{} = {:.4}\n总收益率 {:.2}%\n最大回撤 {:.2}%\n夏普 {:.3}\n成交 {} 笔\n点击查看详情",
+                    x.name,
+                    x.value(ix),
+                    y.name,
+                    y.value(iy),
+                    m.total_return * 100.0,
+                    m.max_drawdown * 100.0,
+                    m.sharpe,
+                    m.trade_count
+                ));
+            }
+        }
+        let (ix, iy) = response.interact_pointer_pos().filter(|_| response.clicked()).and_then(hit)?;
+        Some(ParamSet::from([(x.name.clone(), x.value(ix)), (y.name.clone(), y.value(iy))]))
+    }
+}
@@ -0,0 +1,136 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::thread;
+
+use std::path::PathBuf;
+
+use backtest::account::Account;
+use backtest::config::BacktestConfig;
+use backtest::data::{filter_dates, load_klines};
+use backtest::engine::{self, BacktestResult};
+use backtest::model::KLine;
+use backtest::strategy::k_strategy::{KStrategy, KStrategyParams};
+use chrono::NaiveDate;
+use eframe::egui;
+
+/// 每处理多少根 K 线向界面推送一次进度
+const PROGRESS_EVERY: usize = 20;
+
+/// 一次回测任务的输入
+#[derive(Clone)]
+pub struct RunJob {
+    pub params: KStrategyParams,
+    pub data_path: PathBuf,
+    /// 股票代码，加载和交易使用同一个代码
+    pub code: String,
+    /// 日期区间，None 表示不限
+    pub start: Option<NaiveDate>,
+    pub end: Option<NaiveDate>,
+    /// 界面当前设置组成的配置，提供初始资金、手续费、风控、融资融券和期货设置
+    pub config: BacktestConfig,
+}
+
+impl RunJob {
+    /// 读取数据并按日期区间过滤
+    pub fn load_bars(&self) -> Result<Vec<KLine>, String> {
+        let bars = load_klines(&self.data_path)
+            .map_err(|e| format!("读取 {} 失败：{}", self.data_path.display(), e))?;
+        let bars = filter_dates(bars, self.start, self.end);
+        if bars.is_empty() {
+            return Err("所选日期区间内没有数据".to_string());
+        }
+        Ok(bars)
+    }
+
+    /// 按任务的配置创建账户
+    pub fn new_account(&self) -> Account {
+        self.config.new_account()
+    }
+}
+
+/// 后台线程发给界面的消息
+pub enum WorkerMsg {
+    /// 已处理 done / total 根，附带新增的资金点
+    Progress {
+        done: usize,
+        total: usize,
+        equity: Vec<(i64, f64)>,
+    },
+    /// 运行结束，cancelled 表示被停止按钮中断
+    Finished {
+        bars: Vec<KLine>,
+        result: Box<BacktestResult>,
+        cancelled: bool,
+    },
+    Failed(String),
+}
+
+/// 后台回测线程
+pub struct Worker {
+    rx: Receiver<WorkerMsg>,
+    cancel: Arc<AtomicBool>,
+}
+
+impl Worker {
+    pub fn spawn(job: RunJob, ctx: egui::Context) -> Self {
+        let (tx, rx) = mpsc::channel();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let flag = cancel.clone();
+        thread::spawn(move || {
+            let msg = match run(&job, &tx, &flag, &ctx) {
+                Ok((bars, result)) => WorkerMsg::Finished {
+                    bars,
+                    result: Box::new(result),
+                    cancelled: flag.load(Ordering::Relaxed),
+                },
+                Err(e) => WorkerMsg::Failed(e),
+            };
+            let _ = tx.send(msg);
+            ctx.request_repaint();
+        });
+        Self { rx, cancel }
+    }
+
+    /// 请求停止，线程在处理完当前 K 线后退出
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelling(&self) -> bool {
+        self.cancel.load(Ordering::Relaxed)
+    }
+
+    /// 取出已收到的消息，不阻塞
+    pub fn poll(&self) -> Vec<WorkerMsg> {
+        self.rx.try_iter().collect()
+    }
+}
+
+fn run(
+    job: &RunJob,
+    tx: &Sender<WorkerMsg>,
+    cancel: &AtomicBool,
+    ctx: &egui::Context,
+) -> Result<(Vec<KLine>, BacktestResult), String> {
+    let bars = job.load_bars()?;
+    let code = &job.code;
+    let account = job.new_account();
+
+    let mut strategy = KStrategy::from_params(&job.params);
+    let total = bars.len();
+    let mut pending = Vec::with_capacity(PROGRESS_EVERY);
+    let result = engine::run_until(&mut strategy, &bars, code, account, |i, point| {
+        pending.push(point);
+        if pending.len() >= PROGRESS_EVERY || i + 1 == total {
+            let _ = tx.send(WorkerMsg::Progress {
+                done: i + 1,
+                total,
+                equity: std::mem::take(&mut pending),
+            });
+            ctx.request_repaint();
+        }
+        !cancel.load(Ordering::Relaxed)
+    });
+    Ok((bars, result))
+}
@@ -64,3 +64,39 @@ fn test_account() {
     assert_eq!(pos2.market_value, 200.0);  // 市值
     assert_eq!(pos2.cost_price, 1.0);      // 成本价
 }
+
+#[test]
+fn test_fee() {
+    let mut account = Account {
+        balance: 100_000.0,
+        available_balance: 100_000.0,
+        fee: backtest::account::FeeModel {
+            commission_ratio: 0.0003,
+            min_commission: 5.0,
+            tax_ratio: 0.001,
+        },
+        ..Default::default()
+    };
+    let code = "601111";
+    let mut order = Order {
+        market_type: '0',
+        code: StockCode::from(code),
+        time: 1,
+        order_type: 'B',
+        price: 10.0,
+        volume: 1000,
+    };
+    // 佣金 3 元，按最低 5 元收取
+    assert!(account.buy(&order));
+    assert_eq!(account.available_balance, 100_000.0 - 10_005.0);
+    assert_eq!(account.transactions[0].fee, 5.0);
+    assert_eq!(account.get_position(StockCode::from(code)).cost_price, 10.005);
+
+    // 卖出：佣金 6 元 + 印花税 20 元
+    account.get_position(StockCode::from(code)).available_vol = 1000;
+    order.order_type = 'S';
+    order.price = 20.0;
+    assert!(account.sell(&order));
+    assert_eq!(account.transactions[1].fee, 26.0);
+    assert_eq!(account.available_balance, 100_000.0 - 10_005.0 + 20_000.0 - 26.0);
+}
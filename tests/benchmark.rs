@@ -0,0 +1,55 @@
+use backtest::analysis::benchmark::{compare, Benchmark};
+use backtest::engine::{self, BacktestResult};
+
+fn result(balances: &[f64]) -> BacktestResult {
+    BacktestResult {
+        init_cash: 100.0,
+        equity: balances.iter().enumerate().map(|(i, &b)| (i as i64 * 10, b)).collect(),
+        positions: Vec::new(),
+        account: engine::new_account(100.0),
+    }
+}
+
+#[test]
+fn beta_of_leveraged_strategy() {
+    let bench = Benchmark {
+        name: "index".to_string(),
+        prices: vec![(0, 10.0), (10, 11.0), (20, 10.45), (30, 11.495)],
+    };
+    // 基准逐期收益 10%, -5%, 10%，策略是 2 倍
+    let r = result(&[100.0, 120.0, 108.0, 129.6]);
+    let m = compare(&r, &bench);
+    assert!((m.beta - 2.0).abs() < 1e-9, "beta {}", m.beta);
+    assert!(m.alpha.abs() < 1e-9);
+    assert!((m.benchmark_return - 0.1495).abs() < 1e-9);
+    assert_eq!(m.excess_curve.len(), 4);
+    assert!((m.benchmark_equity[3].1 - 114.95).abs() < 1e-9);
+}
+
+#[test]
+fn align_forward_fills() {
+    let bench = Benchmark {
+        name: "index".to_string(),
+        prices: vec![(5, 1.0), (25, 2.0)],
+    };
+    assert_eq!(bench.align(&[0, 10, 20, 30]), vec![None, Some(1.0), Some(1.0), Some(2.0)]);
+    let m = compare(&result(&[100.0, 100.0, 100.0, 100.0]), &bench);
+    assert_eq!(m.excess_curve.len(), 3);
+    assert!((m.excess_return + 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn later_benchmark_starts_from_common_date() {
+    let bench = Benchmark {
+        name: "index".to_string(),
+        prices: vec![(10, 1.0), (30, 1.1)],
+    };
+    // 基准从第二个时间点开始，策略从当时的 120 起算，此后同样上涨 10%
+    let m = compare(&result(&[100.0, 120.0, 120.0, 132.0]), &bench);
+    assert_eq!(m.excess_curve.len(), 3);
+    assert_eq!(m.excess_curve[0], (10, 0.0));
+    assert!(m.excess_curve.iter().all(|e| e.1.abs() < 1e-9));
+    assert!(m.excess_return.abs() < 1e-9);
+    assert_eq!(m.benchmark_equity[0], (10, 120.0));
+    assert!((m.benchmark_equity[2].1 - 132.0).abs() < 1e-9);
+}
@@ -0,0 +1,108 @@
+use backtest::engine::{new_account, run, run_with, BacktestResult};
+use backtest::model::KLine;
+use backtest::strategy::bollinger::{BollingerParams, BollingerStrategy};
+use backtest::strategy::donchian::{DonchianParams, DonchianStrategy};
+use backtest::strategy::dual_ma::{DualMaParams, DualMaStrategy};
+use backtest::strategy::rsi::{RsiParams, RsiStrategy};
+use backtest::strategy::{build, Strategy};
+use backtest::optimize::ParamSet;
+
+/// 2024-01-02 15:00 北京时间
+const DAY1: i64 = 1704178800;
+
+/// 每天一根的日线，最高最低为收盘价 ±1%
+fn daily(closes: &[f64]) -> Vec<KLine> {
+    closes
+        .iter()
+        .enumerate()
+        .map(|(i, &c)| KLine {
+            time: DAY1 + i as i64 * 86400,
+            open: c,
+            high: c * 1.01,
+            low: c * 0.99,
+            close: c,
+            volume: 1000,
+        })
+        .collect()
+}
+
+/// 先跌后涨再跌的 V 形走势，跌幅、涨幅各 30%
+fn v_shape() -> Vec<f64> {
+    let down = (0..30).map(|i| 10.0 - 0.1 * i as f64);
+    let up = (0..60).map(|i| 7.0 + 0.1 * i as f64);
+    let fall = (0..30).map(|i| 13.0 - 0.2 * i as f64);
+    down.chain(up).chain(fall).collect()
+}
+
+/// 在 base 附近振荡的正弦波
+fn sine(base: f64, amplitude: f64, period: f64, n: usize) -> Vec<f64> {
+    (0..n)
+        .map(|i| base + amplitude * (i as f64 * std::f64::consts::TAU / period).sin())
+        .collect()
+}
+
+fn sides(result: &BacktestResult) -> Vec<char> {
+    result.account.transactions.iter().map(|t| t.order_type).collect()
+}
+
+fn backtest(strategy: &mut dyn Strategy, closes: &[f64]) -> BacktestResult {
+    run(strategy, &daily(closes), "601111", 100_000.0)
+}
+
+#[test]
+fn dual_ma_follows_trend() {
+    let mut strategy = DualMaStrategy::new(&DualMaParams::default());
+    let result = backtest(&mut strategy, &v_shape());
+    // 上涨途中金叉买入，下跌途中死叉卖出，赚到中段涨幅
+    assert_eq!(sides(&result), ['B', 'S']);
+    let t = &result.account.transactions;
+    assert!(t[0].price < 8.5 && t[1].price > 11.0, "{:?}", t);
+    assert!(result.final_balance() > 120_000.0);
+
+    let mut ema = DualMaStrategy::new(&DualMaParams { exponential: true, ..Default::default() });
+    let ema_result = backtest(&mut ema, &v_shape());
+    assert_eq!(sides(&ema_result), ['B', 'S']);
+}
+
+#[test]
+fn donchian_breakout() {
+    let mut strategy = DonchianStrategy::new(&DonchianParams::default());
+    let result = backtest(&mut strategy, &v_shape());
+    assert_eq!(sides(&result), ['B', 'S']);
+    assert!(result.final_balance() > 100_000.0);
+}
+
+#[test]
+fn bollinger_and_rsi_trade_oscillation() {
+    let prices = sine(10.0, 1.0, 30.0, 200);
+    let mut bollinger = BollingerStrategy::new(&BollingerParams::default());
+    let result = backtest(&mut bollinger, &prices);
+    assert!(result.account.transactions.len() >= 6, "{:?}", sides(&result));
+    assert!(result.final_balance() > 100_000.0);
+    // 低买高卖，每笔卖出价高于前一笔买入价
+    for pair in result.account.transactions.chunks(2).filter(|p| p.len() == 2) {
+        assert!(pair[1].price > pair[0].price);
+    }
+
+    let mut rsi = RsiStrategy::new(&RsiParams::default());
+    let result = backtest(&mut rsi, &prices);
+    assert!(result.account.transactions.len() >= 6);
+    assert!(result.final_balance() > 100_000.0);
+}
+
+#[test]
+fn no_trades_without_enough_history() {
+    let mut strategy = DualMaStrategy::new(&DualMaParams::default());
+    let result = run_with(&mut strategy, &daily(&[10.0; 10]), "601111", new_account(100_000.0));
+    assert!(result.account.transactions.is_empty());
+}
+
+#[test]
+fn build_classic_by_name() {
+    let params = |pairs: &[(&str, f64)]| -> ParamSet { pairs.iter().map(|&(k, v)| (k.to_string(), v)).collect() };
+    assert!(build("dual_ma", &params(&[("fast", 10.0), ("slow", 30.0)])).is_ok());
+    assert!(build("dual_ma", &params(&[("fast", 30.0), ("slow", 10.0)])).is_err());
+    assert!(build("rsi", &params(&[("oversold", 80.0)])).is_err());
+    assert!(build("bollinger", &params(&[("width", 1.5)])).is_ok());
+    assert!(build("donchian", &params(&[("window", 1.5)])).is_err());
+}
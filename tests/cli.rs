@@ -0,0 +1,162 @@
+use std::path::PathBuf;
+
+use backtest::cli::{parse_range, Args, Job};
+
+fn args(list: &[&str]) -> Result<Args, String> {
+    let list: Vec<String> = list.iter().map(|s| s.to_string()).collect();
+    Args::parse(&list)
+}
+
+/// 在临时目录写一份日线和引用它的配置文件，返回 (日线路径, 配置路径)
+fn fixture(name: &str) -> (String, String) {
+    let dir = std::env::temp_dir().join(name);
+    std::fs::create_dir_all(&dir).unwrap();
+    let mut csv = String::from("time,open,high,low,close,volume\n");
+    for i in 0..10 {
+        let close = 7.0 + i as f64 * 0.1;
+        csv += &format!("{},{},{},{},{},1000\n", 1704240000 + i * 86400, close, close + 0.1, close - 0.1, close);
+    }
+    let data = dir.join("USHA601111.csv");
+    std::fs::write(&data, csv).unwrap();
+
+    let config = dir.join("backtest.toml");
+    let text = format!(
+        r#"
+initial_cash = 500000
+
+[data]
+dir = "{}"
+code = "601111"
+
+[fees]
+commission_ratio = 0.0002
+
+[strategy]
+name = "k"
+params = {{ buy_price_low = 5.9, init_base_volume = 20000 }}
+
+[output]
+formats = ["json", "html"]
+
+[risk]
+max_adds = 2
+"#,
+        dir.display().to_string().replace('\\', "/")
+    );
+    std::fs::write(&config, text).unwrap();
+    (data.display().to_string(), config.display().to_string())
+}
+
+#[test]
+fn parse_range_spec() {
+    let r = parse_range("init_stop_profit=0.1:0.3:0.05").unwrap();
+    assert_eq!(r.name, "init_stop_profit");
+    assert_eq!((r.start, r.end, r.step), (0.1, 0.3, 0.05));
+
+    // 只给一个值时固定不变
+    let r = parse_range("init_base_volume=1000").unwrap();
+    assert_eq!((r.start, r.end, r.step), (1000.0, 1000.0, 0.0));
+
+    for bad in ["init_stop_profit", "a=1:2", "a=1:2:3:4", "a=x:1:0.1", "a="] {
+        assert!(parse_range(bad).is_err(), "{}", bad);
+    }
+}
+
+#[test]
+fn parse_args() {
+    let a = args(&["run", "--code", "601111", "--param", "a=1", "--param", "b=2", "--code", "000001"]).unwrap();
+    assert_eq!(a.command, "run");
+    // 重复的选项取最后一个，all 按顺序返回全部
+    assert_eq!(a.get("code"), Some("000001"));
+    assert_eq!(a.all("param"), ["a=1", "b=2"]);
+    assert_eq!(a.get("out"), None);
+
+    let a = args(&["run", "--cash", "2e5", "--seed", "x"]).unwrap();
+    assert_eq!(a.number("cash", 0.0), Ok(200_000.0));
+    assert_eq!(a.number("evals", 200usize), Ok(200));
+    assert!(a.number("seed", 42u64).is_err());
+
+    assert!(args(&[]).is_err());
+    assert!(args(&["run", "601111"]).is_err());
+    assert!(args(&["run", "--code"]).unwrap_err().contains("--code"));
+}
+
+#[test]
+fn job_merges_config_and_args() {
+    let (data, config) = fixture("backtest_cli_merge_test");
+
+    // 只用配置文件
+    let job = Job::from_args(&args(&["run", "--config", &config]).unwrap()).unwrap();
+    assert_eq!(job.bars.len(), 10);
+    assert_eq!(job.code, "601111");
+    assert_eq!(job.strategy, "k");
+    assert_eq!(job.cash, 500_000.0);
+    let loaded = job.config.as_ref().unwrap();
+    assert_eq!(loaded.fees.commission_ratio, 0.0002);
+    assert_eq!(loaded.risk.as_ref().and_then(|r| r.max_adds), Some(2));
+    assert_eq!(job.params["buy_price_low"], 5.9);
+    assert_eq!(job.formats, ["json", "html"]);
+    assert_eq!(job.out, None);
+
+    // 命令行覆盖配置中的同名项
+    let out = std::env::temp_dir().join("backtest_cli_merge_test").join("out");
+    let list = [
+        "run", "--config", &config, "--data", &data, "--code", "600000", "--cash", "1000",
+        "--param", "buy_price_low=6.5", "--format", "csv, parquet", "--out", out.to_str().unwrap(),
+    ];
+    let job = Job::from_args(&args(&list).unwrap()).unwrap();
+    assert_eq!(job.code, "600000");
+    assert_eq!(job.cash, 1000.0);
+    assert_eq!(job.params["buy_price_low"], 6.5);
+    assert_eq!(job.params["init_base_volume"], 20000.0);
+    assert_eq!(job.formats, ["csv", "parquet"]);
+    assert_eq!(job.out, Some(PathBuf::from(&out)));
+    assert!(out.is_dir());
+
+    // 没有配置文件时使用默认值
+    let job = Job::from_args(&args(&["run", "--data", &data, "--code", "601111"]).unwrap()).unwrap();
+    assert_eq!((job.strategy.as_str(), job.cash), ("k", 1_000_000.0));
+    assert_eq!(job.formats, ["csv"]);
+    assert!(job.config.is_none());
+}
+
+#[test]
+fn job_rejects_bad_input() {
+    let (data, config) = fixture("backtest_cli_error_test");
+    let err = |list: &[&str]| Job::from_args(&args(list).unwrap()).err().unwrap();
+
+    assert!(err(&["run", "--code", "601111"]).contains("--data"));
+    assert!(err(&["run", "--data", &data]).contains("--code"));
+    assert!(err(&["run", "--data", "no_such.csv", "--code", "601111"]).contains("no_such.csv"));
+    assert!(err(&["run", "--config", "no_such.toml"]).contains("no_such.toml"));
+
+    assert!(err(&["run", "--config", &config, "--format", "csv,xls"]).contains("xls"));
+    assert!(err(&["run", "--config", &config, "--strategy", "nope"]).contains("nope"));
+    assert!(err(&["run", "--config", &config, "--param", "no_such_param=1"]).contains("no_such_param"));
+    assert!(err(&["run", "--config", &config, "--param", "buy_price_low"]).contains("名称=值"));
+    assert!(err(&["run", "--config", &config, "--param", "buy_price_low=x"]).contains("buy_price_low"));
+    assert!(err(&["run", "--config", &config, "--cash", "lots"]).contains("--cash"));
+}
+
+#[test]
+fn optimize_skips_invalid_points() {
+    let (data, _) = fixture("backtest_cli_optimize_test");
+    let run = |extra: &[&str]| {
+        let mut list = vec!["optimize", "--data", &data, "--code", "601111", "--strategy", "dual_ma"];
+        list.extend_from_slice(extra);
+        backtest::cli::run(&list.iter().map(|s| s.to_string()).collect::<Vec<_>>())
+    };
+
+    // fast >= slow 的点无法构建策略，得分记为 NaN 而不是中断寻优
+    let job = Job::from_args(&args(&["run", "--data", &data, "--code", "601111", "--strategy", "dual_ma"]).unwrap()).unwrap();
+    let invalid = [("fast".to_string(), 20.0), ("slow".to_string(), 20.0)].into_iter().collect();
+    assert!(job.run(&invalid, &job.bars, job.cash).unwrap_err().contains("20"));
+    assert!(run(&["--range", "fast=5:30:5", "--range", "slow=20:20:1"]).is_ok());
+    assert!(run(&["--range", "fast=5:30:5", "--range", "slow=20:20:1", "--walk-forward", "5:3"]).is_ok());
+
+    // 随机搜索不展开整个网格
+    let huge = ["--method", "random", "--evals", "3", "--range", "fast=1:4:1", "--range", "slow=10:1000000:1", "--range", "exponential=0:1000000:1"];
+    assert!(run(&huge).is_ok());
+    // 起点本身无效时直接报错
+    assert!(run(&["--range", "fast=20:30:5", "--range", "slow=20:20:1"]).unwrap_err().contains("短均线"));
+}
@@ -0,0 +1,108 @@
+use backtest::config::BacktestConfig;
+
+const TOML: &str = r#"
+initial_cash = 500000
+
+[data]
+dir = "A:/data/day"
+code = "601111"
+
+[range]
+start = "2020-01-01"
+end = "2024-12-31"
+
+[fees]
+commission_ratio = 0.00025
+min_commission = 5
+
+[strategy]
+name = "k"
+params = { buy_price_low = 5.9, init_base_volume = 20000 }
+
+[output]
+dir = "out"
+formats = ["csv"]
+"#;
+
+#[test]
+fn parse_toml_and_yaml() {
+    let config = BacktestConfig::from_toml_str(TOML).unwrap();
+    assert_eq!(config.initial_cash, 500000.0);
+    assert_eq!(config.fees.min_commission, 5.0);
+    assert_eq!(config.strategy.params["init_base_volume"], 20000.0);
+    assert_eq!(config.new_account().fee.commission_ratio, 0.00025);
+
+    // TOML 与 YAML 互相转换后一致
+    let yaml = config.to_yaml_string();
+    assert_eq!(BacktestConfig::from_yaml_str(&yaml).unwrap(), config);
+    assert_eq!(BacktestConfig::from_toml_str(&config.to_toml_string()).unwrap(), config);
+}
+
+#[test]
+fn errors_point_at_key() {
+    let err = |text: &str| BacktestConfig::from_toml_str(text).unwrap_err();
+
+    let e = err(&TOML.replace("buy_price_low", "buy_price_lo"));
+    assert_eq!(e.key, "strategy.params.buy_price_lo");
+
+    let e = err(&TOML.replace("2024-12-31", "2024/12/31"));
+    assert_eq!(e.key, "range.end");
+
+    let e = err(&TOML.replace("initial_cash = 500000", "initial_cash = -1"));
+    assert_eq!(e.key, "initial_cash");
+
+    let e = err(&TOML.replace(r#"["csv"]"#, r#"["csv", "xls"]"#));
+    assert_eq!(e.key, "output.formats[1]");
+
+    let e = err(&TOML.replace(r#"name = "k""#, r#"name = "x""#));
+    assert_eq!(e.key, "strategy.name");
+
+    // 未知字段和类型错误由解析器报错，key 为出错位置
+    let e = err(&TOML.replace("min_commission", "min_comission"));
+    assert_eq!(e.key, "fees.min_comission");
+    assert!(e.message.contains("min_comission"), "{}", e);
+
+    let e = err(&TOML.replace("initial_cash = 500000", r#"initial_cash = "many""#));
+    assert_eq!(e.key, "initial_cash");
+
+    let e = err(&format!("{}\n[risk]\nmax_adds = 1.5\n", TOML));
+    assert_eq!(e.key, "risk.max_adds");
+
+    let yaml = BacktestConfig::from_toml_str(TOML).unwrap().to_yaml_string();
+    let e = BacktestConfig::from_yaml_str(&yaml.replace("min_commission", "min_comission")).unwrap_err();
+    assert_eq!(e.key, "fees.min_comission");
+}
+
+#[test]
+fn symbols_from_data_dir() {
+    let dir = std::env::temp_dir().join("backtest_symbols_test");
+    std::fs::create_dir_all(&dir).unwrap();
+    let header = "time,open,high,low,close,volume\n";
+    std::fs::write(dir.join("USHA601111.csv"), format!("{}1704240000,7,7.2,6.9,7.1,1000\n", header)).unwrap();
+    std::fs::write(dir.join("USZA000001.csv"), header).unwrap();
+    std::fs::write(dir.join("readme.txt"), "").unwrap();
+
+    let symbols = backtest::data::list_symbols(&dir);
+    let codes: Vec<&str> = symbols.iter().map(|s| s.code.as_str()).collect();
+    assert_eq!(codes, ["000001", "601111"]);
+
+    let text = TOML.replace("A:/data/day", &dir.display().to_string().replace('\\', "/"));
+    let config = BacktestConfig::from_toml_str(&text).unwrap();
+    assert_eq!(config.data_file().unwrap(), dir.join("USHA601111.csv"));
+    assert_eq!(config.load_bars().unwrap().len(), 1);
+}
+
+#[test]
+fn save_by_extension() {
+    let dir = std::env::temp_dir().join("backtest_config_save_test");
+    std::fs::create_dir_all(&dir).unwrap();
+    let config = BacktestConfig::from_toml_str(TOML).unwrap();
+
+    for name in ["preset.toml", "preset.yaml"] {
+        let path = dir.join(name);
+        config.save(&path).unwrap();
+        assert_eq!(BacktestConfig::load(&path).unwrap(), config);
+    }
+    let yaml = std::fs::read_to_string(dir.join("preset.yaml")).unwrap();
+    assert!(yaml.contains("code: '601111'"), "{}", yaml);
+}
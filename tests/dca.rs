@@ -0,0 +1,136 @@
+use backtest::engine::{new_account, run_with};
+use backtest::model::KLine;
+use backtest::strategy::dca::{DcaMode, DcaParams, DcaStrategy, Schedule};
+use backtest::strategy::build;
+use chrono::{Datelike, NaiveDate, Weekday};
+
+/// 从 start 开始的 n 个工作日的日线，收盘价由 price(i) 给出，时间为北京时间 15:00
+fn weekdays(start: NaiveDate, n: usize, price: impl Fn(usize) -> f64) -> Vec<KLine> {
+    start
+        .iter_days()
+        .filter(|d| !matches!(d.weekday(), Weekday::Sat | Weekday::Sun))
+        .take(n)
+        .enumerate()
+        .map(|(i, d)| {
+            let close = price(i);
+            KLine {
+                time: d.and_hms_opt(7, 0, 0).unwrap().and_utc().timestamp(),
+                open: close,
+                high: close,
+                low: close,
+                close,
+                volume: 1000,
+            }
+        })
+        .collect()
+}
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+fn trade_dates(params: DcaParams, bars: &[KLine]) -> Vec<(NaiveDate, i32)> {
+    let mut strategy = DcaStrategy::new(params);
+    let result = run_with(&mut strategy, bars, "601111", new_account(1_000_000.0));
+    result
+        .account
+        .transactions
+        .iter()
+        .map(|t| (backtest::data::bar_date(t.time), t.volume))
+        .collect()
+}
+
+#[test]
+fn monthly_rolls_to_next_trading_day() {
+    // 2024-06-15 是周六，顺延到 17 日周一
+    let bars = weekdays(date(2024, 5, 1), 60, |_| 10.0);
+    let params = DcaParams {
+        schedule: Schedule::Monthly(15),
+        amount: 10_000.0,
+        ..Default::default()
+    };
+    let dates: Vec<NaiveDate> = trade_dates(params, &bars).into_iter().map(|t| t.0).collect();
+    assert_eq!(dates, [date(2024, 5, 15), date(2024, 6, 17), date(2024, 7, 15)]);
+}
+
+#[test]
+fn every_n_days_and_weekly() {
+    let bars = weekdays(date(2024, 1, 1), 10, |_| 10.0);
+    let every = DcaParams {
+        schedule: Schedule::EveryDays(3),
+        ..Default::default()
+    };
+    assert_eq!(trade_dates(every, &bars).len(), 4);
+
+    // 周三定投，从周四开始时第一周顺延到周五
+    let bars = weekdays(date(2024, 1, 4), 7, |_| 10.0);
+    let weekly = DcaParams {
+        schedule: Schedule::Weekly(3),
+        ..Default::default()
+    };
+    let dates: Vec<NaiveDate> = trade_dates(weekly, &bars).into_iter().map(|t| t.0).collect();
+    assert_eq!(dates, [date(2024, 1, 4), date(2024, 1, 10)]);
+}
+
+#[test]
+fn fixed_amount_carries_odd_lots() {
+    // 每期 1500 元，15 元一手 1500 元；16 元时不足一手，金额留到下期
+    let bars = weekdays(date(2024, 1, 1), 3, |i| [15.0, 16.0, 16.0][i]);
+    let params = DcaParams {
+        schedule: Schedule::EveryDays(1),
+        amount: 1500.0,
+        ..Default::default()
+    };
+    let volumes: Vec<i32> = trade_dates(params, &bars).into_iter().map(|t| t.1).collect();
+    assert_eq!(volumes, [100, 100]);
+}
+
+#[test]
+fn value_averaging_buys_gap_and_sells_excess() {
+    // 目标市值每期加 1000：第 1 期 10 元买 100 股；第 2 期 20 元市值 2000 已达标；
+    // 第 3 期 40 元市值 4000 超出目标 3000，卖出 1000 元即 25 股，按手取整为 0；
+    // 第 4 期 50 元市值 5000 超出目标 4000，仍不足一手；第 5 期 5 元市值 500，目标 5000，买入 900 股
+    let bars = weekdays(date(2024, 1, 1), 5, |i| [10.0, 20.0, 40.0, 50.0, 5.0][i]);
+    let params = DcaParams {
+        mode: DcaMode::ValueAveraging,
+        schedule: Schedule::EveryDays(1),
+        amount: 1000.0,
+        allow_sell: true,
+        ..Default::default()
+    };
+    let trades = trade_dates(params.clone(), &bars);
+    assert_eq!(trades.iter().map(|t| t.1).collect::<Vec<_>>(), [100, 900]);
+
+    // 大幅上涨时卖出超出部分
+    let bars = weekdays(date(2024, 1, 1), 2, |i| [10.0, 100.0][i]);
+    let params = DcaParams { amount: 10_000.0, ..params };
+    let trades = trade_dates(params, &bars);
+    // 第 2 期目标 20000，市值 100000，卖出 800 股
+    assert_eq!(trades.iter().map(|t| t.1).collect::<Vec<_>>(), [1000, -800]);
+}
+
+#[test]
+fn drawdown_weighted_buys_more_after_drop() {
+    // 从 10 元跌到 8 元，回撤 20%，系数 5 时金额翻倍
+    let bars = weekdays(date(2024, 1, 1), 2, |i| [10.0, 8.0][i]);
+    let params = DcaParams {
+        mode: DcaMode::DrawdownWeighted,
+        schedule: Schedule::EveryDays(1),
+        amount: 4000.0,
+        drawdown_multiplier: 5.0,
+        max_multiple: 3.0,
+        ..Default::default()
+    };
+    let volumes: Vec<i32> = trade_dates(params, &bars).into_iter().map(|t| t.1).collect();
+    assert_eq!(volumes, [400, 1000]);
+}
+
+#[test]
+fn build_by_name() {
+    let params = [("mode", 2.0), ("interval", 10.0), ("schedule", 1.0)]
+        .map(|(k, v)| (k.to_string(), v))
+        .into();
+    assert!(build("dca", &params).is_ok());
+    let bad = [("mode".to_string(), 5.0)].into();
+    assert!(build("dca", &bad).is_err());
+}
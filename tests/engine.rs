@@ -0,0 +1,19 @@
+use backtest::engine;
+use backtest::model::KLine;
+use backtest::strategy::k_strategy::KStrategy;
+
+#[test]
+fn run_until_stops_early() {
+    let bars: Vec<KLine> = (0..100)
+        .map(|i| KLine { time: i * 86400, open: 7.0, high: 7.1, low: 6.9, close: 7.0, volume: 1000 })
+        .collect();
+    let mut strategy = KStrategy::new(6.0, 7.5, 1000, 0.03, 0.2, 9.0);
+    let mut seen = 0;
+    let result = engine::run_until(&mut strategy, &bars, "601111", engine::new_account(100_000.0), |i, _| {
+        seen += 1;
+        i < 29
+    });
+    assert_eq!(seen, 30);
+    assert_eq!(result.equity.len(), 30);
+    assert_eq!(result.positions.len(), 30);
+}
@@ -0,0 +1,39 @@
+use std::fs::File;
+
+use backtest::engine;
+use backtest::export::{export_result, ExportFormat};
+use backtest::model::KLine;
+use backtest::strategy::k_strategy::KStrategy;
+use parquet::file::reader::{FileReader, SerializedFileReader};
+
+#[test]
+fn export_all_formats() {
+    let bars: Vec<KLine> = (0..120)
+        .map(|i| {
+            let close = 7.0 + (i as f64 / 6.0).sin();
+            KLine { time: 1_700_000_000 + i * 86400, open: close, high: close + 0.1, low: close - 0.1, close, volume: 1000 }
+        })
+        .collect();
+    let mut strategy = KStrategy::new(6.0, 7.5, 1000, 0.03, 0.2, 9.0);
+    let result = engine::run(&mut strategy, &bars, "601111", 100_000.0);
+    let trades = result.account.transactions.len();
+    assert!(trades > 0);
+    assert!(result.account.orders.len() >= trades);
+
+    let dir = std::env::temp_dir().join("backtest_export_test");
+    let files = export_result(&result, &dir, &ExportFormat::ALL).unwrap();
+    assert_eq!(files.len(), 12);
+
+    let csv = std::fs::read_to_string(dir.join("transactions.csv")).unwrap();
+    assert_eq!(csv.lines().count(), trades + 1);
+    assert!(csv.lines().nth(1).unwrap().contains("601111"));
+
+    let json: serde_json::Value = serde_json::from_reader(File::open(dir.join("equity.json")).unwrap()).unwrap();
+    assert_eq!(json.as_array().unwrap().len(), bars.len());
+    assert_eq!(json[0]["balance"], 100_000.0);
+
+    let reader = SerializedFileReader::new(File::open(dir.join("positions.parquet")).unwrap()).unwrap();
+    assert_eq!(reader.metadata().file_metadata().num_rows() as usize, result.positions.len());
+    let reader = SerializedFileReader::new(File::open(dir.join("orders.parquet")).unwrap()).unwrap();
+    assert_eq!(reader.metadata().file_metadata().schema_descr().num_columns(), 8);
+}
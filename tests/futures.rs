@@ -0,0 +1,182 @@
+use backtest::account::{Account, Order, StockCode};
+use backtest::config::BacktestConfig;
+use backtest::data::align_feeds;
+use backtest::engine::{new_account, run_multi};
+use backtest::futures::{self, FuturesAccount, FuturesContract, FuturesOrder, Offset, Side};
+use backtest::model::KLine;
+use backtest::strategy::MultiStrategy;
+
+/// 2024-01-02 15:00 北京时间
+const DAY1: i64 = 1704178800;
+const DAY: i64 = 86400;
+
+fn account(cash: f64) -> Account {
+    Account {
+        futures: Some(FuturesAccount::new([FuturesContract::index_future("IF2406").unwrap()])),
+        ..new_account(cash)
+    }
+}
+
+fn order(time: i64, price: f64, volume: i32, side: Side, offset: Offset) -> FuturesOrder {
+    FuturesOrder {
+        code: "IF2406".to_string(),
+        time,
+        price,
+        volume,
+        side,
+        offset,
+    }
+}
+
+fn futures(account: &Account) -> &FuturesAccount {
+    account.futures.as_ref().unwrap()
+}
+
+fn close_to(a: f64, b: f64) -> bool {
+    (a - b).abs() < 1e-6
+}
+
+#[test]
+fn contract_model() {
+    let contract = FuturesContract::index_future("IF2406").unwrap();
+    assert_eq!(contract.multiplier, 300.0);
+    assert_eq!(FuturesContract::index_future("IC2406").unwrap().multiplier, 200.0);
+    assert!(FuturesContract::index_future("601111").is_none());
+    assert!(close_to(contract.round_price(3500.13), 3500.2));
+    assert!(close_to(contract.notional(3500.0, 2), 2_100_000.0));
+}
+
+#[test]
+fn open_settle_and_close_yesterday() {
+    let mut account = account(200_000.0);
+    assert!(futures::submit(&mut account, &order(DAY1, 3500.0, 1, Side::Long, Offset::Open)));
+    // 保证金 3500 * 300 * 12% = 126000，手续费 24.15
+    assert!(close_to(futures(&account).margin(), 126_000.0));
+    assert!(close_to(account.available_balance, 200_000.0 - 126_000.0 - 24.15));
+    // 成交后总资产只扣除手续费
+    assert!(close_to(account.balance, 200_000.0 - 24.15));
+    // 资金不足
+    assert!(!futures::submit(&mut account, &order(DAY1, 3500.0, 1, Side::Long, Offset::Open)));
+
+    // 结算价 3550：盈利 15000 划入可用资金，保证金按结算价重算
+    futures::settle(&mut account, "IF2406", 3550.0, DAY1);
+    let settlement = &futures(&account).settlements[0];
+    assert!(close_to(settlement.pnl, 15_000.0));
+    assert!(close_to(futures(&account).margin(), 127_800.0));
+    assert!(close_to(account.balance, 200_000.0 - 24.15 + 15_000.0));
+
+    // 次日平昨仓，按结算价计算当日盈亏，逐笔盈亏相对开仓价
+    assert!(futures::submit(&mut account, &order(DAY1 + DAY, 3560.0, 1, Side::Long, Offset::Close)));
+    let trade = futures(&account).trades.last().unwrap();
+    assert!(close_to(trade.pnl, 18_000.0));
+    assert!(close_to(trade.fee, 3560.0 * 300.0 * 0.000023));
+    assert!(futures(&account).position("IF2406").is_none());
+    assert!(close_to(account.available_balance, 200_000.0 - 24.15 + 18_000.0 - trade.fee));
+    assert!(close_to(account.balance, account.available_balance));
+}
+
+#[test]
+fn close_today_fee_and_short_pnl() {
+    let mut account = account(1_000_000.0);
+    assert!(futures::submit(&mut account, &order(DAY1, 3500.0, 2, Side::Short, Offset::Open)));
+    assert!(!futures::submit(&mut account, &order(DAY1, 3400.0, 3, Side::Short, Offset::Close)));
+    assert!(futures::submit(&mut account, &order(DAY1, 3400.0, 1, Side::Short, Offset::Close)));
+    let trade = futures(&account).trades.last().unwrap();
+    // 空头下跌 100 点盈利，平今仓手续费万分之 2.3
+    assert!(close_to(trade.pnl, 30_000.0));
+    assert!(close_to(trade.fee, 3400.0 * 300.0 * 0.00023));
+    let leg = &futures(&account).position("IF2406").unwrap().short;
+    assert_eq!((leg.volume, leg.today), (1, 1));
+}
+
+/// 第一根 K 线买入股票，同时按市值卖空股指期货对冲
+struct Hedge {
+    futures_lots: i32,
+}
+
+impl MultiStrategy for Hedge {
+    fn process_bars(&mut self, bars: &[KLine], codes: &[String], account: &mut Account) {
+        if account.hold.is_empty() {
+            account.buy(&Order {
+                market_type: ' ',
+                code: StockCode::from(codes[0].as_str()),
+                time: bars[0].time,
+                price: bars[0].close,
+                volume: 300_000,
+                order_type: 'B',
+            });
+            if self.futures_lots > 0 {
+                let order = FuturesOrder {
+                    code: codes[1].clone(),
+                    time: bars[1].time,
+                    price: bars[1].close,
+                    volume: self.futures_lots,
+                    side: Side::Short,
+                    offset: Offset::Open,
+                };
+                assert!(futures::submit(account, &order));
+            }
+        }
+    }
+}
+
+#[test]
+fn index_future_hedges_stock_holdings() {
+    // 指数 10 天下跌 10%，ETF 价格为指数的千分之一
+    let index: Vec<f64> = (0..10).map(|i| 3500.0 * (1.0 - 0.01 * i as f64)).collect();
+    let feed = |scale: f64| -> Vec<KLine> {
+        index
+            .iter()
+            .enumerate()
+            .map(|(i, &c)| KLine {
+                time: DAY1 + i as i64 * DAY,
+                open: c * scale,
+                high: c * scale,
+                low: c * scale,
+                close: c * scale,
+                volume: 1000,
+            })
+            .collect()
+    };
+    let steps = align_feeds(&[feed(0.001), feed(1.0)]);
+    let codes = ["510300".to_string(), "IF2406".to_string()];
+
+    let run = |lots| run_multi(&mut Hedge { futures_lots: lots }, &steps, &codes, account(1_300_000.0));
+    let naked = run(0);
+    let hedged = run(1);
+    // 未对冲亏损约 9.45 万，对冲后只剩手续费
+    assert!(naked.final_balance() < 1_300_000.0 - 90_000.0);
+    assert!((hedged.final_balance() - 1_300_000.0).abs() < 100.0, "{}", hedged.final_balance());
+    // 每个交易日结算一次，回测结束时结算最后一天
+    let futures = hedged.account.futures.as_ref().unwrap();
+    assert_eq!(futures.settlements.len(), 10);
+    assert_eq!(futures.settlements[9].time, DAY1 + 9 * DAY);
+    assert_eq!(futures.position("IF2406").unwrap().short.today, 0);
+    assert!(close_to(hedged.account.balance, hedged.final_balance()));
+}
+
+#[test]
+fn futures_section_in_config() {
+    let toml = r#"
+[data]
+dir = "data"
+code = "510300"
+
+[strategy]
+name = "k"
+
+[[futures]]
+code = "IF2406"
+multiplier = 300
+margin_rate = 0.12
+tick_size = 0.2
+close_today_fee = { ratio = 0.00023 }
+"#;
+    let config = BacktestConfig::from_toml_str(toml).unwrap();
+    let account = config.new_account();
+    assert_eq!(account.futures.unwrap().contracts["IF2406"].close_today_fee.ratio, 0.00023);
+    assert_eq!(BacktestConfig::from_toml_str(&config.to_toml_string()).unwrap(), config);
+
+    let e = BacktestConfig::from_toml_str(&toml.replace("margin_rate = 0.12", "margin_rate = 0")).unwrap_err();
+    assert_eq!(e.key, "futures[0].margin_rate");
+}
@@ -0,0 +1,125 @@
+use backtest::config::BacktestConfig;
+use backtest::engine::{new_account, run_with};
+use backtest::model::KLine;
+use backtest::strategy::grid::{BoundAction, GridParams, GridSpacing, GridStrategy};
+
+/// 2024-01-02 09:30 北京时间
+const DAY1: i64 = 1704159000;
+const DAY: i64 = 86400;
+
+fn bar(time: i64, close: f64) -> KLine {
+    KLine {
+        time,
+        open: close,
+        high: close,
+        low: close,
+        close,
+        volume: 1000,
+    }
+}
+
+fn params() -> GridParams {
+    GridParams {
+        lower: 5.0,
+        upper: 8.0,
+        grid_count: 3,
+        volume_per_grid: 100,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn levels_by_spacing() {
+    assert_eq!(params().levels(), [5.0, 6.0, 7.0, 8.0]);
+    let geometric = GridParams {
+        lower: 4.0,
+        upper: 16.0,
+        grid_count: 2,
+        spacing: GridSpacing::Geometric,
+        ..Default::default()
+    };
+    assert_eq!(geometric.levels(), [4.0, 8.0, 16.0]);
+}
+
+#[test]
+fn buys_on_drop_and_sells_next_day() {
+    let bars = [
+        // 首根买底仓，基准为 7 元
+        bar(DAY1, 7.0),
+        // 跌到 6 元买一格
+        bar(DAY1 + 60, 6.0),
+        // 当天涨回 7 元，T+1 不能卖
+        bar(DAY1 + 120, 7.0),
+        // 再跌回 6 元，该格已有持仓不重复买
+        bar(DAY1 + 180, 6.0),
+        // 次日涨到 7 元卖出该格，底仓保留
+        bar(DAY1 + DAY, 7.1),
+    ];
+    let mut strategy = GridStrategy::new(GridParams { base_volume: 200, ..params() });
+    let result = run_with(&mut strategy, &bars, "601111", new_account(100_000.0));
+
+    let trades: Vec<(f64, i32)> = result.account.transactions.iter().map(|t| (t.price, t.volume)).collect();
+    assert_eq!(trades, [(7.0, 200), (6.0, 100), (7.1, -100)]);
+    assert!(strategy.lots().is_empty());
+    let position = result.account.hold.values().next().unwrap();
+    assert_eq!(position.volume, 200);
+}
+
+#[test]
+fn crossing_several_levels_buys_each() {
+    // 基准 8 元，跌到 5 元跨过 7、6、5 三格
+    let bars = [bar(DAY1, 7.9), bar(DAY1 + DAY, 5.0)];
+    let mut strategy = GridStrategy::new(params());
+    let result = run_with(&mut strategy, &bars, "601111", new_account(100_000.0));
+    assert_eq!(result.account.transactions[0].volume, 300);
+    let levels: Vec<usize> = strategy.lots().iter().map(|l| l.level).collect();
+    assert_eq!(levels, [2, 1, 0]);
+}
+
+#[test]
+fn upper_bound_liquidates_after_t_plus_one() {
+    let bars = [
+        bar(DAY1, 6.5),
+        bar(DAY1 + 60, 5.0),
+        // 突破上边界，当日买入的不能卖
+        bar(DAY1 + 120, 8.5),
+        bar(DAY1 + DAY, 8.6),
+        // 已停止，不再交易
+        bar(DAY1 + 2 * DAY, 5.0),
+    ];
+    let mut strategy = GridStrategy::new(GridParams {
+        base_volume: 300,
+        upper_action: BoundAction::Liquidate,
+        ..params()
+    });
+    let result = run_with(&mut strategy, &bars, "601111", new_account(100_000.0));
+    let trades: Vec<(f64, i32)> = result.account.transactions.iter().map(|t| (t.price, t.volume)).collect();
+    assert_eq!(trades, [(6.5, 300), (5.0, 200), (8.6, -500)]);
+    assert!(strategy.is_stopped());
+}
+
+#[test]
+fn pause_outside_bounds() {
+    // 跌破下边界暂停，回到区间后以当时价位为基准
+    let bars = [bar(DAY1, 6.5), bar(DAY1 + DAY, 4.5), bar(DAY1 + 2 * DAY, 5.5), bar(DAY1 + 3 * DAY, 5.0)];
+    let mut strategy = GridStrategy::new(params());
+    let result = run_with(&mut strategy, &bars, "601111", new_account(100_000.0));
+    let trades: Vec<(f64, i32)> = result.account.transactions.iter().map(|t| (t.price, t.volume)).collect();
+    assert_eq!(trades, [(5.0, 100)]);
+    assert!(!strategy.is_stopped());
+}
+
+#[test]
+fn config_checks_grid_bounds() {
+    let config = |params: &str| {
+        BacktestConfig::from_toml_str(&format!(
+            "[data]\ndir = \".\"\ncode = \"601111\"\n[strategy]\nname = \"grid\"\nparams = {{ {} }}\n",
+            params
+        ))
+    };
+    // 单独看 lower = 10 高于默认上边界，组合起来合法
+    assert!(config("lower = 10, upper = 12, upper_action = 2").is_ok());
+    assert_eq!(config("lower = 12, upper = 10").unwrap_err().key, "strategy.params");
+    assert_eq!(config("upper_action = 3").unwrap_err().key, "strategy.params.upper_action");
+    assert_eq!(config("grid = 3").unwrap_err().key, "strategy.params.grid");
+}
@@ -0,0 +1,69 @@
+use backtest::indicator::{Atr, Bollinger, Ema, Highest, Lowest, Rsi, Sma, StdDev};
+use backtest::model::KLine;
+
+fn close(v: f64) -> f64 {
+    (v * 1e9).round() / 1e9
+}
+
+#[test]
+fn moving_averages() {
+    let mut sma = Sma::new(3);
+    let values: Vec<Option<f64>> = [1.0, 2.0, 3.0, 4.0].iter().map(|&v| sma.update(v)).collect();
+    assert_eq!(values, [None, None, Some(2.0), Some(3.0)]);
+
+    // 以前 3 个的平均为初值，alpha = 0.5
+    let mut ema = Ema::new(3);
+    let values: Vec<Option<f64>> = [1.0, 2.0, 3.0, 5.0].iter().map(|&v| ema.update(v)).collect();
+    assert_eq!(values, [None, None, Some(2.0), Some(3.5)]);
+}
+
+#[test]
+fn std_and_bollinger() {
+    let mut std = StdDev::new(4);
+    let last = [2.0, 4.0, 4.0, 4.0, 5.0].iter().map(|&v| std.update(v)).last().flatten();
+    // 4, 4, 4, 5 的总体标准差
+    assert_eq!(close(last.unwrap()), close(0.1875f64.sqrt()));
+
+    let mut band = Bollinger::new(2, 2.0);
+    assert_eq!(band.update(1.0), None);
+    let b = band.update(3.0).unwrap();
+    assert_eq!((b.lower, b.middle, b.upper), (0.0, 2.0, 4.0));
+}
+
+#[test]
+fn rsi_bounds() {
+    let mut rsi = Rsi::new(3);
+    let up: Vec<Option<f64>> = [1.0, 2.0, 3.0, 4.0].iter().map(|&v| rsi.update(v)).collect();
+    assert_eq!(up, [None, None, None, Some(100.0)]);
+
+    // 平均涨 2/3 跌 1/3
+    let mut rsi = Rsi::new(3);
+    let last = [10.0, 11.0, 10.0, 11.0].iter().map(|&v| rsi.update(v)).last().flatten();
+    assert_eq!(close(last.unwrap()), close(100.0 - 100.0 / 3.0));
+    // Wilder 平滑：再跌 1，gain = 4/9，loss = 5/9
+    assert_eq!(close(rsi.update(10.0).unwrap()), close(100.0 * 4.0 / 9.0));
+}
+
+#[test]
+fn atr_and_channels() {
+    let bar = |high: f64, low: f64, close: f64| KLine {
+        time: 0,
+        open: close,
+        high,
+        low,
+        close,
+        volume: 0,
+    };
+    let mut atr = Atr::new(2);
+    assert_eq!(atr.update(&bar(11.0, 9.0, 10.0)), None);
+    // 跳空：真实波幅取 13 - 10
+    assert_eq!(atr.update(&bar(13.0, 12.0, 12.5)), Some(2.5));
+    assert_eq!(atr.update(&bar(13.0, 12.0, 12.5)), Some(1.75));
+
+    let mut high = Highest::new(2);
+    let mut low = Lowest::new(2);
+    let highs: Vec<Option<f64>> = [3.0, 1.0, 2.0].iter().map(|&v| high.update(v)).collect();
+    let lows: Vec<Option<f64>> = [3.0, 1.0, 2.0].iter().map(|&v| low.update(v)).collect();
+    assert_eq!(highs, [None, Some(3.0), Some(2.0)]);
+    assert_eq!(lows, [None, Some(1.0), Some(1.0)]);
+}
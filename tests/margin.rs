@@ -0,0 +1,219 @@
+use backtest::account::{Account, Order, StockCode};
+use backtest::analysis::trades::round_trips;
+use backtest::config::BacktestConfig;
+use backtest::engine::new_account;
+use backtest::margin::{self, MarginAccount, MarginConfig, MarginEventKind};
+use backtest::risk::{RiskLimits, RiskManager, RiskRule};
+
+/// 2024-01-02 15:00 北京时间
+const DAY1: i64 = 1704178800;
+const DAY: i64 = 86400;
+const CODE: &str = "601111";
+
+fn order(time: i64, price: f64, volume: i32, order_type: char) -> Order {
+    Order {
+        market_type: ' ',
+        code: StockCode::from(CODE),
+        time,
+        price,
+        volume,
+        order_type,
+    }
+}
+
+fn account(cash: f64, config: MarginConfig) -> Account {
+    Account {
+        margin: Some(MarginAccount::new(config)),
+        ..new_account(cash)
+    }
+}
+
+fn margin(account: &Account) -> &MarginAccount {
+    account.margin.as_ref().unwrap()
+}
+
+fn unlock(account: &mut Account) {
+    let position = account.get_position(StockCode::from(CODE));
+    position.available_vol = position.volume;
+}
+
+#[test]
+fn financing_buy_and_repay_on_sell() {
+    let mut account = account(10_000.0, MarginConfig::default());
+    assert!(account.buy(&order(DAY1, 10.0, 1500, 'B')));
+    assert_eq!(margin(&account).cash_debt, 5000.0);
+    assert_eq!(account.available_balance, 0.0);
+    account.on_price_change(CODE, 10.0);
+    assert_eq!(account.net_assets(), 10_000.0);
+    assert_eq!(margin(&account).ratio(&account), 3.0);
+
+    // 保证金比例 1：净资产 1 万元最多借 1 万元，已借 5000
+    assert!(!account.buy(&order(DAY1, 10.0, 600, 'B')));
+    assert!(account.buy(&order(DAY1, 10.0, 500, 'B')));
+    assert_eq!(margin(&account).cash_debt, 10_000.0);
+
+    // 卖出所得先还融资
+    unlock(&mut account);
+    assert!(account.sell(&order(DAY1 + DAY, 10.0, 1200, 'S')));
+    assert_eq!(margin(&account).cash_debt, 0.0);
+    assert_eq!(account.available_balance, 2000.0);
+}
+
+#[test]
+fn short_sell_and_cover() {
+    let mut account = account(10_000.0, MarginConfig::default());
+    assert!(account.sell(&order(DAY1, 10.0, 1000, 'S')));
+    assert_eq!(margin(&account).short_volume(CODE), 1000);
+    // 融券卖出所得不能用于买入其他股票
+    assert_eq!(account.available_balance, 10_000.0);
+
+    account.on_price_change(CODE, 9.0);
+    assert_eq!(account.net_assets(), 11_000.0);
+    assert!(account.buy(&order(DAY1 + 60, 9.0, 1000, 'B')));
+    assert_eq!(margin(&account).short_volume(CODE), 0);
+    assert_eq!(account.available_balance, 11_000.0);
+    assert!(!account.hold.contains_key(&StockCode::from(CODE)));
+    let volumes: Vec<i32> = account.transactions.iter().map(|t| t.volume).collect();
+    assert_eq!(volumes, [-1000, 1000]);
+}
+
+#[test]
+fn short_round_trips_pair_separately() {
+    let mut account = account(100_000.0, MarginConfig::default());
+    assert!(account.buy(&order(DAY1 - 120, 10.0, 1000, 'B')));
+    // 当日买入不可卖，卖出全部为融券，买入先还券
+    assert!(account.sell(&order(DAY1 - 60, 10.5, 500, 'S')));
+    assert!(account.buy(&order(DAY1, 10.2, 500, 'B')));
+    unlock(&mut account);
+    assert!(account.sell(&order(DAY1 + DAY, 11.0, 1000, 'S')));
+    let types: String = account.transactions.iter().map(|t| t.order_type).collect();
+    assert_eq!(types, format!("B{}{}S", margin::SHORT_SELL, margin::BUY_TO_COVER));
+
+    let fee: Vec<f64> = account.transactions.iter().map(|t| t.fee).collect();
+    let trips = round_trips(&account.transactions);
+    assert_eq!(trips.len(), 2);
+    let short = &trips[0];
+    assert_eq!((short.entry_time, short.exit_time, short.volume), (DAY1 - 60, DAY1, -500));
+    assert!((short.pnl - (500.0 * 10.5 - fee[1] - 500.0 * 10.2 - fee[2])).abs() < 1e-9);
+    assert!(short.return_pct() > 0.0);
+    // 多头回合不受融券影响
+    let long = &trips[1];
+    assert_eq!((long.entry_time, long.volume), (DAY1 - 120, 1000));
+    assert!((long.pnl - (1000.0 * 1.0 - fee[0] - fee[3])).abs() < 1e-9);
+}
+
+#[test]
+fn sell_beyond_holdings_goes_short() {
+    let mut account = account(100_000.0, MarginConfig::default());
+    assert!(account.buy(&order(DAY1, 10.0, 1000, 'B')));
+    unlock(&mut account);
+    // 做 T：先卖出多于持仓的数量，超出部分融券
+    assert!(account.sell(&order(DAY1 + DAY, 10.5, 1500, 'S')));
+    assert_eq!(account.hold[&StockCode::from(CODE)].volume, 0);
+    assert_eq!(margin(&account).short_volume(CODE), 500);
+    // 买回时先还券
+    assert!(account.buy(&order(DAY1 + DAY + 60, 10.0, 1500, 'B')));
+    assert_eq!(margin(&account).short_volume(CODE), 0);
+    assert_eq!(account.hold[&StockCode::from(CODE)].volume, 1000);
+    account.on_price_change(CODE, 10.0);
+    assert_eq!(account.net_assets(), 100_000.0 + 750.0);
+}
+
+#[test]
+fn interest_accrues_by_calendar_day() {
+    let config = MarginConfig {
+        cash_rate: 0.0365,
+        ..Default::default()
+    };
+    let mut account = account(10_000.0, config);
+    assert!(account.buy(&order(DAY1, 10.0, 1500, 'B')));
+    account.on_price_change(CODE, 10.0);
+    margin::on_close(&mut account, DAY1);
+    // 周五到周一三个自然日，每天 5000 * 0.0365 / 365 = 0.5
+    margin::on_close(&mut account, DAY1 + 3 * DAY);
+    assert!((margin(&account).interest - 1.5).abs() < 1e-9);
+    assert!((account.balance - 9998.5).abs() < 1e-9);
+}
+
+#[test]
+fn margin_call_then_liquidation() {
+    let config = MarginConfig {
+        cash_rate: 0.0,
+        grace_days: 1,
+        ..Default::default()
+    };
+    let mut account = account(10_000.0, config);
+    assert!(account.buy(&order(DAY1, 10.0, 2000, 'B')));
+    account.on_price_change(CODE, 10.0);
+    margin::on_close(&mut account, DAY1);
+    assert!(margin(&account).log.is_empty());
+
+    // 跌到 6 元：担保资产 12000 / 负债 10000 = 1.2
+    account.on_price_change(CODE, 6.0);
+    unlock(&mut account);
+    margin::on_close(&mut account, DAY1 + DAY);
+    assert_eq!(margin(&account).log[0].kind, MarginEventKind::Call);
+    assert_eq!(account.hold[&StockCode::from(CODE)].volume, 2000);
+
+    // 追保期满仍未恢复，卖出持仓还款
+    margin::on_close(&mut account, DAY1 + 2 * DAY);
+    let kinds: Vec<MarginEventKind> = margin(&account).log.iter().map(|e| e.kind).collect();
+    assert_eq!(kinds, [MarginEventKind::Call, MarginEventKind::Liquidation]);
+    assert_eq!(account.hold[&StockCode::from(CODE)].volume, 0);
+    assert_eq!(margin(&account).cash_debt, 0.0);
+    assert_eq!(account.available_balance, 2000.0);
+    assert_eq!(account.balance, 2000.0);
+}
+
+#[test]
+fn margin_section_in_config() {
+    let toml = r#"
+[data]
+dir = "data"
+code = "601111"
+
+[strategy]
+name = "k"
+
+[margin]
+cash_rate = 0.05
+grace_days = 0
+"#;
+    let config = BacktestConfig::from_toml_str(toml).unwrap();
+    let margin = config.new_account().margin.unwrap();
+    assert_eq!(margin.config.cash_rate, 0.05);
+    assert_eq!(margin.config.maintenance_ratio, 1.3);
+    assert_eq!(BacktestConfig::from_toml_str(&config.to_toml_string()).unwrap(), config);
+
+    let e = BacktestConfig::from_toml_str(&format!("{}maintenance_ratio = 0.9\n", toml)).unwrap_err();
+    assert_eq!(e.key, "margin.maintenance_ratio");
+}
+
+#[test]
+fn risk_limits_apply_to_shorts() {
+    let mut account = Account {
+        risk: Some(RiskManager::new(RiskLimits { max_position_pct: Some(0.3), ..Default::default() })),
+        ..account(100_000.0, MarginConfig::default())
+    };
+    // 单票上限 3 万元，融券缩量到 3000 股，再融券被拒绝
+    assert!(account.sell(&order(DAY1, 10.0, 5000, 'S')));
+    assert_eq!(margin(&account).short_volume(CODE), 3000);
+    assert!(!account.sell(&order(DAY1 + 60, 10.0, 100, 'S')));
+    assert_eq!(margin(&account).short_volume(CODE), 3000);
+    let log: Vec<_> = account.risk.as_ref().unwrap().log.iter().map(|e| (e.order_type, e.rule, e.requested, e.allowed)).collect();
+    assert_eq!(
+        log,
+        [(margin::SHORT_SELL, RiskRule::MaxPosition, 5000, 3000), (margin::SHORT_SELL, RiskRule::MaxPosition, 100, 0)]
+    );
+    let orders: Vec<(i32, bool)> = account.orders.iter().map(|o| (o.order.volume, o.filled)).collect();
+    assert_eq!(orders, [(3000, true), (100, false)]);
+}
+
+#[test]
+fn short_capacity_includes_fee() {
+    let mut account = account(10_000.0, MarginConfig::default());
+    account.fee.min_commission = 5.0;
+    // 可融额度 1 万元，融券市值加手续费超出额度
+    assert!(!account.sell(&order(DAY1, 10.0, 1000, 'S')));
+    assert!(account.sell(&order(DAY1, 10.0, 900, 'S')));
+}
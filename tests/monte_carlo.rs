@@ -0,0 +1,89 @@
+use backtest::account::{StockCode, Transaction};
+use backtest::analysis::monte_carlo::{perturb_prices, resample_trades, Resample};
+use backtest::analysis::trades::round_trips;
+use backtest::engine;
+use backtest::model::KLine;
+use backtest::strategy::k_strategy::KStrategy;
+
+fn tx(time: i64, price: f64, volume: i32) -> Transaction {
+    Transaction {
+        code: StockCode::from("601111"),
+        time,
+        price,
+        volume,
+        order_type: if volume > 0 { 'B' } else { 'S' },
+        remain_vol: 0,
+        remain_cost: 0.0,
+        fee: 0.0,
+    }
+}
+
+#[test]
+fn fifo_round_trips() {
+    let trips = round_trips(&[tx(1, 10.0, 100), tx(2, 8.0, 100), tx(3, 12.0, -150), tx(4, 9.0, -50)]);
+    assert_eq!(trips.len(), 2);
+    assert_eq!(trips[0].entry_time, 1);
+    assert_eq!(trips[0].volume, 150);
+    assert!((trips[0].pnl - (12.0 * 150.0 - 1000.0 - 400.0)).abs() < 1e-9);
+    assert!((trips[1].pnl - 50.0).abs() < 1e-9);
+}
+
+#[test]
+fn round_trips_grouped_by_code() {
+    let other = |time: i64, price: f64, volume: i32| Transaction { code: StockCode::from("600000"), ..tx(time, price, volume) };
+    let trips = round_trips(&[tx(1, 10.0, 100), other(2, 5.0, 200), tx(3, 11.0, -100), other(4, 6.0, -200)]);
+    assert_eq!(trips.len(), 2);
+    assert_eq!(trips[0].code.as_str(), "601111");
+    assert_eq!((trips[0].entry_time, trips[0].entry_price), (1, 10.0));
+    assert!((trips[0].pnl - 100.0).abs() < 1e-9);
+    // 卖出只与同一只股票的买入配对
+    assert_eq!(trips[1].code.as_str(), "600000");
+    assert_eq!((trips[1].entry_time, trips[1].volume), (2, 200));
+    assert!((trips[1].pnl - 200.0).abs() < 1e-9);
+}
+
+#[test]
+fn shuffle_keeps_final_equity() {
+    let trips = round_trips(&[
+        tx(1, 10.0, 100),
+        tx(2, 11.0, -100),
+        tx(3, 10.0, 100),
+        tx(4, 9.0, -100),
+        tx(5, 10.0, 100),
+        tx(6, 12.0, -100),
+    ]);
+    let report = resample_trades(&trips, 10_000.0, Resample::Shuffle, 100, 3);
+    // 打乱顺序不改变期末资产，只改变回撤
+    assert!((report.final_equity.percentile(5.0) - report.actual_final).abs() < 1e-6);
+    assert!((report.final_equity.percentile(95.0) - report.actual_final).abs() < 1e-6);
+    assert!(report.max_drawdown.percentile(0.0) <= report.max_drawdown.percentile(100.0));
+
+    let boot = resample_trades(&trips, 10_000.0, Resample::Bootstrap, 500, 3);
+    assert!(boot.final_equity.percentile(5.0) < boot.final_equity.percentile(95.0));
+}
+
+#[test]
+fn perturbed_backtests() {
+    let bars: Vec<KLine> = (0..200)
+        .map(|i| {
+            let close = 7.0 + (i as f64 / 6.0).sin();
+            KLine { time: i * 86400, open: close, high: close + 0.1, low: close - 0.1, close, volume: 1000 }
+        })
+        .collect();
+    let run = |bars: &[KLine]| {
+        let mut strategy = KStrategy::new(6.0, 7.5, 1000, 0.03, 0.2, 9.0);
+        engine::run(&mut strategy, bars, "601111", 100_000.0)
+    };
+    let report = perturb_prices(&bars, 0.01, 50, 11, run);
+    assert_eq!(report.final_equity.values.len(), 50);
+    assert_eq!(report.max_drawdown.values.len(), 50);
+    assert!(report.final_equity.percentile(5.0) <= report.final_equity.percentile(95.0));
+
+    // 原始结果取自未扰动的回测
+    assert_eq!(report.init_cash, 100_000.0);
+    assert_eq!(report.actual_final, run(&bars).final_balance());
+    assert!((0.0..1.0).contains(&report.actual_drawdown));
+    assert!((0.0..=1.0).contains(&report.actual_rank()));
+    assert!((0.0..=1.0).contains(&report.final_equity.prob_below(report.init_cash)));
+    assert!(report.max_drawdown.values.iter().all(|d| (0.0..1.0).contains(d)));
+}
@@ -0,0 +1,55 @@
+use backtest::optimize::search::{genetic, random_search, GeneticConfig, RandomSearchConfig};
+use backtest::optimize::{ParamRange, ParamSet, ParamSpace};
+
+fn space() -> ParamSpace {
+    ParamSpace::new(vec![
+        ParamRange::new("x", 0.0, 10.0, 0.5),
+        ParamRange::new("y", 0.0, 10.0, 0.5),
+        ParamRange::new("z", 0.0, 10.0, 0.5),
+    ])
+}
+
+/// 在 (3, 7, 5) 处取得最大值 0
+fn objective(p: &ParamSet) -> f64 {
+    -((p["x"] - 3.0).powi(2) + (p["y"] - 7.0).powi(2) + (p["z"] - 5.0).powi(2))
+}
+
+#[test]
+fn random_search_is_reproducible() {
+    let config = RandomSearchConfig { max_evals: 200, patience: 0, seed: 7 };
+    let a = random_search(&space(), &config, objective);
+    let b = random_search(&space(), &config, objective);
+    assert_eq!(a.evaluations.len(), 200);
+    assert_eq!(a.best.as_ref().unwrap().params, b.best.as_ref().unwrap().params);
+    assert!(a.ranked()[0].score >= a.evaluations[0].score);
+}
+
+#[test]
+fn random_search_stops_early() {
+    let config = RandomSearchConfig { max_evals: 5000, patience: 20, seed: 1 };
+    let result = random_search(&space(), &config, objective);
+    assert!(result.stopped_early);
+    assert!(result.evaluations.len() < 5000);
+}
+
+#[test]
+fn random_search_on_huge_space() {
+    // 网格点数超出 usize 范围
+    let ranges = (0..8).map(|i| ParamRange::new(&format!("p{}", i), 0.0, 1e6, 1.0)).collect();
+    let space = ParamSpace::new(ranges);
+    assert_eq!(space.grid_size(), usize::MAX);
+    let config = RandomSearchConfig { max_evals: 50, patience: 0, seed: 3 };
+    let result = random_search(&space, &config, |p| -p["p0"]);
+    assert_eq!(result.evaluations.len(), 50);
+}
+
+#[test]
+fn genetic_finds_optimum() {
+    let config = GeneticConfig { population: 30, generations: 60, patience: 15, ..Default::default() };
+    let a = genetic(&space(), &config, objective);
+    let b = genetic(&space(), &config, objective);
+    let best = a.best.unwrap();
+    assert!(best.score > -1.0, "best score {}", best.score);
+    assert_eq!(best.params, b.best.unwrap().params);
+    assert_eq!(a.evaluations.len(), b.evaluations.len());
+}
@@ -0,0 +1,114 @@
+use backtest::analysis::pairs::{cointegration, ols, zscore};
+use backtest::data::align_feeds;
+use backtest::engine::{new_account, run_multi};
+use backtest::model::KLine;
+use backtest::optimize::search::Rng;
+use backtest::strategy::pairs::{PairsParams, PairsStrategy};
+
+/// 2024-01-02 15:00 北京时间
+const DAY1: i64 = 1704178800;
+
+fn bars(closes: &[f64]) -> Vec<KLine> {
+    closes
+        .iter()
+        .enumerate()
+        .map(|(i, &c)| KLine {
+            time: DAY1 + i as i64 * 86400,
+            open: c,
+            high: c,
+            low: c,
+            close: c,
+            volume: 1000,
+        })
+        .collect()
+}
+
+/// b 为随机游走，a = 1.5 * b 的对数关系加上均值回归的噪声
+fn cointegrated(n: usize, seed: u64) -> (Vec<f64>, Vec<f64>) {
+    let mut rng = Rng::new(seed);
+    let (mut lb, mut noise) = (10f64.ln(), 0.0);
+    let (mut a, mut b) = (Vec::new(), Vec::new());
+    for _ in 0..n {
+        lb += 0.01 * rng.next_normal();
+        noise = 0.7 * noise + 0.02 * rng.next_normal();
+        b.push(lb.exp());
+        a.push((0.2 + 1.5 * lb + noise).exp());
+    }
+    (a, b)
+}
+
+#[test]
+fn ols_and_zscore() {
+    let (alpha, beta) = ols(&[1.0, 2.0, 3.0], &[3.0, 5.0, 7.0]);
+    assert!((alpha - 1.0).abs() < 1e-12 && (beta - 2.0).abs() < 1e-12);
+    assert_eq!(zscore(&[1.0, 1.0]), None);
+    assert!(zscore(&[0.0, 0.0, 0.0, 3.0]).unwrap() > 1.0);
+}
+
+#[test]
+fn detects_cointegration() {
+    let (a, b) = cointegrated(500, 7);
+    let result = cointegration(&a, &b).unwrap();
+    assert!((result.beta - 1.5).abs() < 0.1, "{:?}", result);
+    assert!(result.is_cointegrated(), "{:?}", result);
+    assert!(result.half_life > 1.0 && result.half_life < 5.0, "{:?}", result);
+
+    // 两条独立的随机游走
+    let (_, c) = cointegrated(500, 8);
+    let result = cointegration(&a, &c).unwrap();
+    assert!(!result.is_cointegrated(), "{:?}", result);
+}
+
+#[test]
+fn align_skips_missing_bars() {
+    let a = bars(&[1.0, 2.0, 3.0, 4.0]);
+    let mut b = bars(&[10.0, 20.0, 30.0, 40.0, 50.0]);
+    // b 第 2 天停牌
+    b.remove(1);
+    let aligned = align_feeds(&[a, b]);
+    let times: Vec<i64> = aligned.iter().map(|s| (s.time - DAY1) / 86400).collect();
+    assert_eq!(times, [0, 2, 3]);
+    assert_eq!(aligned[1].bars[0].close, 3.0);
+    assert_eq!(aligned[1].bars[1].close, 30.0);
+}
+
+#[test]
+fn trades_both_legs_in_one_account() {
+    let (a, b) = cointegrated(400, 7);
+    let steps = align_feeds(&[bars(&a), bars(&b)]);
+    let codes = ["600000".to_string(), "600001".to_string()];
+    let mut strategy = PairsStrategy::new(PairsParams {
+        window: 40,
+        entry_z: 1.5,
+        ..Default::default()
+    });
+    let result = run_multi(&mut strategy, &steps, &codes, new_account(1_000_000.0));
+
+    let traded = |code: &str, side: char| {
+        result
+            .account
+            .transactions
+            .iter()
+            .filter(|t| t.code.as_str() == code && t.order_type == side)
+            .count()
+    };
+    for code in &codes {
+        assert!(traded(code, 'B') > 1 && traded(code, 'S') > 1, "{}", code);
+    }
+    assert!((strategy.hedge_ratio().unwrap() - 1.5).abs() < 0.5);
+    assert_eq!(result.equity.len(), steps.len());
+    // 中性底仓随行情涨跌，和只持有中性底仓相比，价差均值回归带来超额收益
+    let mut neutral = PairsStrategy::new(PairsParams {
+        window: 40,
+        entry_z: 100.0,
+        ..Default::default()
+    });
+    let baseline = run_multi(&mut neutral, &steps, &codes, new_account(1_000_000.0));
+    assert_eq!(baseline.account.transactions.len(), 2);
+    assert!(
+        result.final_balance() > baseline.final_balance(),
+        "{} <= {}",
+        result.final_balance(),
+        baseline.final_balance()
+    );
+}
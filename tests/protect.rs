@@ -0,0 +1,148 @@
+use backtest::account::{Account, Order, StockCode};
+use backtest::engine::{new_account, run};
+use backtest::model::{KLine, TickData};
+use backtest::protect::{self, ProtectiveOrder};
+use backtest::strategy::Strategy;
+
+/// 2024-01-02 15:00 北京时间
+const DAY1: i64 = 1704178800;
+const DAY: i64 = 86400;
+
+fn bar(time: i64, open: f64, high: f64, low: f64, close: f64) -> KLine {
+    KLine {
+        time,
+        open,
+        high,
+        low,
+        close,
+        volume: 1000,
+    }
+}
+
+/// 第一根 K 线收盘买入 1000 股并挂上保护单，之后不再操作
+struct BuyOnce {
+    orders: Vec<ProtectiveOrder>,
+}
+
+impl Strategy for BuyOnce {
+    fn process_bar(&mut self, bar: &KLine, code: &str, account: &mut Account) {
+        if !self.orders.is_empty() {
+            let order = Order {
+                market_type: ' ',
+                code: StockCode::from(code),
+                time: bar.time,
+                price: bar.close,
+                volume: 1000,
+                order_type: 'B',
+            };
+            assert!(account.buy(&order));
+            for order in self.orders.drain(..) {
+                account.protect.attach(code, order);
+            }
+        }
+        account.on_price_change(code, bar.close);
+    }
+}
+
+/// 返回 (触发的保护单, 成交价, 数量)
+fn exits(orders: Vec<ProtectiveOrder>, bars: &[KLine]) -> Vec<(&'static str, f64, i32)> {
+    let result = run(&mut BuyOnce { orders }, bars, "601111", 100_000.0);
+    let protect = &result.account.protect;
+    protect.fills.iter().map(|f| (f.name, f.price, f.volume)).collect()
+}
+
+#[test]
+fn fixed_stop_waits_for_t1_and_fills_at_gap_open() {
+    let bars = [
+        bar(DAY1 - 3600, 10.0, 10.0, 10.0, 10.0),
+        // 当天买入不可卖
+        bar(DAY1, 10.0, 10.0, 9.3, 9.6),
+        // 次日跳空低开，按开盘价成交
+        bar(DAY1 + DAY, 9.2, 9.4, 9.0, 9.3),
+        bar(DAY1 + 2 * DAY, 9.0, 9.1, 8.0, 8.5),
+    ];
+    assert_eq!(exits(vec![ProtectiveOrder::FixedStop(9.5)], &bars), [("固定止损", 9.2, 1000)]);
+
+    let bars = [bar(DAY1, 10.0, 10.0, 10.0, 10.0), bar(DAY1 + DAY, 9.8, 9.9, 9.4, 9.6)];
+    let result = run(&mut BuyOnce { orders: vec![ProtectiveOrder::FixedStop(9.5)] }, &bars, "601111", 100_000.0);
+    assert_eq!(result.account.transactions.last().unwrap().price, 9.5);
+    // 清仓后保护单自动撤销
+    assert!(result.account.protect.get("601111").is_empty());
+}
+
+#[test]
+fn percent_take_profit_and_stop_priority() {
+    let bars = [bar(DAY1, 10.0, 10.0, 10.0, 10.0), bar(DAY1 + DAY, 10.5, 11.5, 10.4, 11.2)];
+    assert_eq!(exits(vec![ProtectiveOrder::PercentTakeProfit(0.1)], &bars), [("比例止盈", 11.0, 1000)]);
+
+    // 同一根 K 线止损止盈都触发时按止损处理
+    let bars = [bar(DAY1, 10.0, 10.0, 10.0, 10.0), bar(DAY1 + DAY, 10.0, 10.6, 9.4, 10.0)];
+    let orders = vec![ProtectiveOrder::TakeProfit(10.5), ProtectiveOrder::PercentStop(0.05)];
+    assert_eq!(exits(orders, &bars), [("比例止损", 9.5, 1000)]);
+}
+
+#[test]
+fn atr_trailing_stop_ratchets_up() {
+    let bars = [
+        bar(DAY1, 10.0, 10.0, 10.0, 10.0),
+        bar(DAY1 + DAY, 10.0, 10.5, 9.9, 10.4),
+        bar(DAY1 + 2 * DAY, 10.4, 11.0, 10.3, 10.9),
+        // 最高价 11，ATR (0.6 + 0.7) / 2 = 0.65，止损价 10.35
+        bar(DAY1 + 3 * DAY, 10.8, 10.9, 10.2, 10.3),
+    ];
+    let exits = exits(vec![ProtectiveOrder::atr_trailing(2, 1.0)], &bars);
+    assert_eq!(exits.len(), 1);
+    assert_eq!(exits[0].0, "ATR 跟踪止损");
+    assert!((exits[0].1 - 10.35).abs() < 1e-9);
+}
+
+/// 只有最新价的 tick，盘口不参与保护单判断
+fn tick(time: i64, last: f64) -> TickData {
+    let [ask, bid] = [last + 0.01, last];
+    TickData {
+        time,
+        last_price: last,
+        volume: 100,
+        ask1_price: ask,
+        ask1_volume: 10,
+        ask2_price: ask,
+        ask2_volume: 0,
+        ask3_price: ask,
+        ask3_volume: 0,
+        ask4_price: ask,
+        ask4_volume: 0,
+        ask5_price: ask,
+        ask5_volume: 0,
+        bid1_price: bid,
+        bid1_volume: 10,
+        bid2_price: bid,
+        bid2_volume: 0,
+        bid3_price: bid,
+        bid3_volume: 0,
+        bid4_price: bid,
+        bid4_volume: 0,
+        bid5_price: bid,
+        bid5_volume: 0,
+    }
+}
+
+#[test]
+fn ticks_use_last_price() {
+    let mut account = new_account(100_000.0);
+    account.buy(&Order {
+        market_type: ' ',
+        code: StockCode::from("601111"),
+        time: DAY1 - DAY,
+        price: 10.0,
+        volume: 500,
+        order_type: 'B',
+    });
+    account.get_position(StockCode::from("601111")).available_vol = 500;
+    account.protect.attach("601111", ProtectiveOrder::FixedStop(9.8));
+    protect::on_tick(&mut account, &tick(DAY1 - 3600, 9.85), "601111");
+    assert!(account.protect.fills.is_empty());
+    protect::on_tick(&mut account, &tick(DAY1 - 3500, 9.79), "601111");
+    // 逐笔成交没有区间，按最新价卖出
+    assert_eq!(account.protect.fills[0].price, 9.79);
+    assert_eq!(account.hold[&StockCode::from("601111")].volume, 0);
+}
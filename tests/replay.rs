@@ -0,0 +1,108 @@
+use backtest::engine::new_account;
+use backtest::model::TickData;
+use backtest::replay::TickReplay;
+use backtest::strategy::tick_t::TickTStrategy;
+
+/// 2024-01-02 09:30 北京时间
+const DAY1: i64 = 1704159000;
+const DAY2: i64 = DAY1 + 86400;
+
+/// 买卖盘各五档，价差 0.01
+fn tick(time: i64, last: f64) -> TickData {
+    TickData {
+        time,
+        last_price: last,
+        volume: 100,
+        ask1_price: last + 0.01,
+        ask1_volume: 10,
+        ask2_price: last + 0.02,
+        ask2_volume: 20,
+        ask3_price: last + 0.03,
+        ask3_volume: 30,
+        ask4_price: last + 0.04,
+        ask4_volume: 40,
+        ask5_price: last + 0.05,
+        ask5_volume: 50,
+        bid1_price: last,
+        bid1_volume: 10,
+        bid2_price: last - 0.01,
+        bid2_volume: 20,
+        bid3_price: last - 0.02,
+        bid3_volume: 30,
+        bid4_price: last - 0.03,
+        bid4_volume: 40,
+        bid5_price: last - 0.04,
+        bid5_volume: 50,
+    }
+}
+
+fn strategy() -> Box<TickTStrategy> {
+    Box::new(TickTStrategy {
+        buy_drawdown_pct: 0.01,
+        stop_profit_pct: 0.01,
+        volume: 100,
+    })
+}
+
+#[test]
+fn ladder_order() {
+    let t = tick(DAY1, 10.0);
+    assert_eq!(t.asks()[0], (10.01, 10));
+    assert_eq!(t.bids()[4].1, 50);
+}
+
+#[test]
+fn resting_orders_fill_and_respect_t_plus_one() {
+    let ticks = vec![
+        tick(DAY1, 10.0),
+        // 卖一 9.90 触及买单
+        tick(DAY1 + 3, 9.89),
+        // 当天涨到止盈价上方，但持仓不可卖
+        tick(DAY1 + 6, 10.2),
+        // 次日解锁后卖出
+        tick(DAY2, 10.2),
+        tick(DAY2 + 3, 10.2),
+    ];
+    let mut replay = TickReplay::new(ticks, "601111", strategy(), new_account(100_000.0));
+
+    assert_eq!(replay.step(), 0);
+    assert_eq!(replay.book.orders().len(), 1);
+    assert_eq!(replay.book.orders()[0].price, 9.9);
+
+    assert_eq!(replay.step(), 1);
+    assert_eq!(replay.fills[0].price, 9.9);
+    assert_eq!(replay.fills[0].order_type, 'B');
+
+    // 当日买入不可卖，不挂卖单
+    assert_eq!(replay.step(), 0);
+    assert!(!replay.book.has('S'));
+
+    // 次日挂卖单 10.00 (9.9 * 1.01)，下一笔成交
+    assert_eq!(replay.step(), 0);
+    let sell = replay.book.orders().iter().find(|o| o.order_type == 'S').unwrap();
+    assert_eq!(sell.price, 10.0);
+    assert_eq!(replay.advance(5), 1);
+    assert!(replay.is_finished());
+    assert_eq!(replay.fills[1].price, 10.2);
+    assert_eq!(replay.account.transactions.len(), 2);
+    assert_eq!(replay.account.available_balance, 100_000.0 + (10.2 - 9.9) * 100.0);
+}
+
+#[test]
+fn rejected_orders_are_dropped() {
+    let ticks = vec![tick(DAY1, 10.0), tick(DAY1 + 3, 9.0)];
+    // 资金不够 100 股时不挂买单
+    let mut replay = TickReplay::new(ticks.clone(), "601111", strategy(), new_account(500.0));
+    replay.advance(2);
+    assert!(replay.fills.is_empty());
+    assert!(replay.book.orders().is_empty());
+
+    // 手动挂单资金不足，撮合时被账户拒绝
+    let mut replay = TickReplay::new(ticks, "601111", strategy(), new_account(2000.0));
+    replay.step();
+    let id = replay.book.place(DAY1, 9.5, 10_000, 'B');
+    assert!(replay.book.orders().iter().any(|o| o.id == id));
+    replay.step();
+    assert!(!replay.book.orders().iter().any(|o| o.id == id));
+    assert_eq!(replay.account.orders.iter().filter(|o| !o.filled).count(), 1);
+}
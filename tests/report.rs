@@ -0,0 +1,48 @@
+use backtest::account::StockCode;
+use backtest::engine;
+use backtest::metrics::monthly_returns;
+use backtest::model::KLine;
+use backtest::optimize::ParamSet;
+use backtest::report::html_report;
+use backtest::strategy::k_strategy::KStrategy;
+
+#[test]
+fn monthly_returns_chain() {
+    // 2024-01-15、2024-01-31、2024-02-01 (北京时间)
+    let equity = [(1705276800, 110.0), (1706630400, 121.0), (1706716800, 108.9)];
+    let months = monthly_returns(100.0, &equity);
+    assert_eq!(months.len(), 2);
+    assert_eq!((months[0].0, months[0].1), (2024, 1));
+    assert!((months[0].2 - 0.21).abs() < 1e-9);
+    assert!((months[1].2 + 0.1).abs() < 1e-9);
+}
+
+#[test]
+fn html_report_sections() {
+    let bars: Vec<KLine> = (0..120)
+        .map(|i| {
+            let close = 7.0 + (i as f64 / 6.0).sin();
+            KLine { time: 1_700_000_000 + i * 86400, open: close - 0.05, high: close + 0.1, low: close - 0.1, close, volume: 1000 }
+        })
+        .collect();
+    let mut strategy = KStrategy::new(6.0, 7.5, 1000, 0.03, 0.2, 9.0);
+    let result = engine::run(&mut strategy, &bars, "601111", 100_000.0);
+    assert!(!result.account.transactions.is_empty());
+
+    let html = html_report("601111 <k>", "601111", &bars, &result, &ParamSet::new(), None);
+    assert!(html.contains("601111 &lt;k&gt;"));
+    for section in ["绩效指标", "资金曲线", "回撤", "月度收益", "K 线与成交", "交易记录"] {
+        assert!(html.contains(section), "缺少 {}", section);
+    }
+    assert_eq!(html.matches("<svg").count(), 3);
+    assert!(html.contains("<title>买入"));
+
+    // 其他股票的成交不标注在 K 线图上
+    let mut result = result;
+    let markers = html.matches("<title>买入").count();
+    let mut other = result.account.transactions.iter().find(|t| t.volume > 0).unwrap().clone();
+    other.code = StockCode::from("600000");
+    result.account.transactions.push(other);
+    let html = html_report("601111", "601111", &bars, &result, &ParamSet::new(), None);
+    assert_eq!(html.matches("<title>买入").count(), markers);
+}
@@ -0,0 +1,177 @@
+use backtest::account::{Account, Order, StockCode};
+use backtest::config::BacktestConfig;
+use backtest::engine::{new_account, run_with};
+use backtest::model::KLine;
+use backtest::risk::{RiskLimits, RiskManager, RiskRule};
+use backtest::strategy::Strategy;
+
+const DAY: i64 = 86400;
+
+fn order(code: &str, time: i64, price: f64, volume: i32, order_type: char) -> Order {
+    Order {
+        market_type: ' ',
+        code: StockCode::from(code),
+        time,
+        price,
+        volume,
+        order_type,
+    }
+}
+
+fn account(limits: RiskLimits) -> Account {
+    Account {
+        risk: Some(RiskManager::new(limits)),
+        ..new_account(100_000.0)
+    }
+}
+
+fn log(account: &Account) -> Vec<(RiskRule, i32, i32)> {
+    let risk = account.risk.as_ref().unwrap();
+    risk.log.iter().map(|e| (e.rule, e.requested, e.allowed)).collect()
+}
+
+fn held(account: &Account, code: &str) -> i32 {
+    account.hold.get(&StockCode::from(code)).map(|p| p.volume).unwrap_or(0)
+}
+
+#[test]
+fn position_and_gross_caps_resize() {
+    let mut account = account(RiskLimits {
+        max_position_pct: Some(0.3),
+        max_gross_exposure: Some(0.5),
+        ..Default::default()
+    });
+    // 单票上限 3 万元
+    assert!(account.buy(&order("601111", 0, 10.0, 5000, 'B')));
+    assert_eq!(held(&account, "601111"), 3000);
+    assert!(!account.buy(&order("601111", 60, 10.0, 100, 'B')));
+
+    // 总仓位上限 5 万元，还能买 2 万元
+    assert!(account.buy(&order("600000", 120, 20.0, 1500, 'B')));
+    assert_eq!(held(&account, "600000"), 1000);
+    assert_eq!(
+        log(&account),
+        [
+            (RiskRule::MaxPosition, 5000, 3000),
+            (RiskRule::MaxPosition, 100, 0),
+            (RiskRule::MaxGrossExposure, 1500, 1000),
+        ]
+    );
+    // 委托记录保存缩量后的数量，被拒绝的委托未成交
+    let orders: Vec<(i32, bool)> = account.orders.iter().map(|o| (o.order.volume, o.filled)).collect();
+    assert_eq!(orders, [(3000, true), (100, false), (1000, true)]);
+}
+
+#[test]
+fn max_adds_resets_after_flat() {
+    let mut account = account(RiskLimits {
+        max_adds: Some(1),
+        ..Default::default()
+    });
+    assert!(account.buy(&order("601111", 0, 10.0, 100, 'B')));
+    assert!(account.buy(&order("601111", 60, 10.0, 200, 'B')));
+    assert!(!account.buy(&order("601111", 120, 10.0, 400, 'B')));
+    assert_eq!(log(&account), [(RiskRule::MaxAdds, 400, 0)]);
+
+    // 卖出不受限制，清仓后重新计数
+    account.get_position(StockCode::from("601111")).available_vol = 300;
+    assert!(account.sell(&order("601111", DAY, 10.0, 300, 'S')));
+    assert!(account.buy(&order("601111", DAY + 60, 10.0, 100, 'B')));
+    assert!(account.buy(&order("601111", DAY + 120, 10.0, 100, 'B')));
+}
+
+#[test]
+fn daily_loss_blocks_rest_of_day() {
+    let mut account = account(RiskLimits {
+        daily_loss_limit: Some(0.05),
+        ..Default::default()
+    });
+    assert!(account.buy(&order("601111", 0, 10.0, 5000, 'B')));
+    // 当日跌 20%，总资产亏损 1 万元
+    account.on_price_change("601111", 8.0);
+    assert!(!account.buy(&order("601111", 3600, 8.0, 100, 'B')));
+    assert_eq!(log(&account), [(RiskRule::DailyLoss, 100, 0)]);
+
+    // 次日以新的开盘资产计算
+    account.update_risk(DAY);
+    assert!(account.buy(&order("601111", DAY + 60, 8.0, 100, 'B')));
+}
+
+/// 首根 K 线买入 5000 股，之后每根收盘加买 100 股
+struct BuyEveryBar;
+
+impl Strategy for BuyEveryBar {
+    fn process_bar(&mut self, bar: &KLine, code: &str, account: &mut Account) {
+        let volume = if account.hold.is_empty() { 5000 } else { 100 };
+        account.buy(&order(code, bar.time, bar.close, volume, 'B'));
+        account.on_price_change(code, bar.close);
+    }
+}
+
+#[test]
+fn daily_loss_on_daily_bars() {
+    // 日线收盘时刻，当日第一次记录已是收盘价，亏损按上一交易日收盘资产计算
+    let bars: Vec<KLine> = [10.0, 10.0, 8.0, 8.0]
+        .iter()
+        .enumerate()
+        .map(|(i, &close)| KLine {
+            time: 1704178800 + i as i64 * DAY,
+            open: close,
+            high: close,
+            low: close,
+            close,
+            volume: 100_000,
+        })
+        .collect();
+    let account = account(RiskLimits {
+        daily_loss_limit: Some(0.05),
+        ..Default::default()
+    });
+    let result = run_with(&mut BuyEveryBar, &bars, "601111", account);
+    // 第三天跌 20% 拒绝买入，第四天不再亏损
+    assert_eq!(log(&result.account), [(RiskRule::DailyLoss, 100, 0)]);
+    assert_eq!(result.account.risk.as_ref().unwrap().log[0].time, bars[2].time);
+    assert_eq!(held(&result.account, "601111"), 5200);
+}
+
+#[test]
+fn drawdown_stop_halts_buying() {
+    let mut account = account(RiskLimits {
+        max_drawdown: Some(0.2),
+        ..Default::default()
+    });
+    assert!(account.buy(&order("601111", 0, 10.0, 9000, 'B')));
+    account.on_price_change("601111", 12.0);
+    account.update_risk(DAY);
+    // 最高点 118000，跌到 9.3 时总资产 93700，回撤超过 20%
+    account.on_price_change("601111", 9.3);
+    account.update_risk(2 * DAY);
+    assert!(account.risk.as_ref().unwrap().is_halted());
+
+    account.on_price_change("601111", 12.0);
+    assert!(!account.buy(&order("601111", 10 * DAY, 12.0, 100, 'B')));
+    assert_eq!(log(&account), [(RiskRule::MaxDrawdown, 100, 0)]);
+}
+
+#[test]
+fn risk_section_in_config() {
+    let toml = r#"
+[data]
+dir = "data"
+code = "601111"
+
+[strategy]
+name = "k"
+
+[risk]
+max_position_pct = 0.5
+max_adds = 2
+"#;
+    let config = BacktestConfig::from_toml_str(toml).unwrap();
+    let account = config.new_account();
+    assert_eq!(account.risk.unwrap().limits.max_adds, Some(2));
+    assert_eq!(BacktestConfig::from_toml_str(&config.to_toml_string()).unwrap(), config);
+
+    let e = BacktestConfig::from_toml_str(&format!("{}max_drawdown = 1.5\n", toml)).unwrap_err();
+    assert_eq!(e.key, "risk.max_drawdown");
+}
@@ -1,4 +1,5 @@
 use backtest::account::{Account, StockCode};
+use backtest::engine;
 use backtest::model::{ KLine};
 use backtest::strategy::k_strategy::KStrategy;
 use csv::Reader;
@@ -15,7 +16,7 @@ fn 区间做t() {
     let mut bars = Reader::from_path(r"A:\data\day\USHA601111.csv").unwrap();
 
     // 3. 初始化账户
-    let mut account = Account {
+    let account = Account {
         balance: 1_000_000.0,
         available_balance: 1_000_000.0,
         ..Default::default()
@@ -33,10 +34,8 @@ fn 区间做t() {
     // 6. 处理每个 K 线
     let iter = bars.deserialize();
     // 6. 处理每个 K 线
-    for ite in iter {
-        let bar: KLine = ite.unwrap();
-        strategy.process_bar(&bar, &code, &mut account);
-    }
+    let bars: Vec<KLine> = iter.map(|b| b.unwrap()).collect();
+    let account = engine::run_with(&mut strategy, &bars, code, account).account;
     // 7. 打印结果
     let position = account.hold.get(&StockCode::from(code)).unwrap();
 
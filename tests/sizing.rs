@@ -0,0 +1,100 @@
+use backtest::account::{FeeModel, Order, StockCode};
+use backtest::analysis::trades::RoundTrip;
+use backtest::engine::new_account;
+use backtest::sizing::{
+    affordable, round_lots, AtrRiskPerTrade, FixedFraction, Kelly, RiskPerTrade, SizeRequest, Sizer,
+};
+
+fn trip(pnl: f64) -> RoundTrip {
+    RoundTrip {
+        code: StockCode::from("601111"),
+        entry_time: 0,
+        exit_time: 0,
+        volume: 100,
+        entry_price: 10.0,
+        exit_price: 10.0 + pnl / 100.0,
+        pnl,
+    }
+}
+
+#[test]
+fn lot_rounding() {
+    assert_eq!(round_lots(199.9), 100);
+    assert_eq!(round_lots(-300.0), 0);
+    assert_eq!(round_lots(f64::NAN), 0);
+}
+
+#[test]
+fn affordable_whole_lots() {
+    let account = new_account(1_000.0);
+    assert_eq!(affordable(&account, 10.0, 250), 100);
+    // 零股先取整，买不起时为 0 而不是负数
+    assert_eq!(affordable(&account, 10.0, 50), 0);
+    assert_eq!(affordable(&account, 20.0, 150), 0);
+    assert_eq!(affordable(&account, 10.0, -30), 0);
+}
+
+#[test]
+fn fraction_risk_and_atr() {
+    let account = new_account(100_000.0);
+    let req = SizeRequest::new("601111", 10.0);
+    // 总资产一半，5 万元 / 10 元
+    assert_eq!(FixedFraction { fraction: 0.5 }.target_shares(&req, &account), 5000);
+
+    // 风险 1% 即 1000 元，每股风险 0.3 元，3333 股取整为 3300
+    let risk = RiskPerTrade { risk_fraction: 0.01 };
+    assert_eq!(risk.target_shares(&req.with_stop(9.7), &account), 3300);
+    assert_eq!(risk.target_shares(&req, &account), 0);
+    assert_eq!(risk.target_shares(&req.with_stop(10.5), &account), 0);
+
+    // 2 倍 ATR 0.25 元为每股风险，1000 / 0.5 = 2000 股
+    let atr = AtrRiskPerTrade {
+        risk_fraction: 0.01,
+        atr_multiple: 2.0,
+    };
+    assert_eq!(atr.target_shares(&req.with_atr(0.25), &account), 2000);
+}
+
+#[test]
+fn order_shares_uses_holdings_and_cash() {
+    let mut account = new_account(100_000.0);
+    account.fee = FeeModel {
+        commission_ratio: 0.001,
+        min_commission: 5.0,
+        tax_ratio: 0.0,
+    };
+    account.buy(&Order {
+        market_type: ' ',
+        code: StockCode::from("601111"),
+        time: 0,
+        price: 10.0,
+        volume: 3000,
+        order_type: 'B',
+    });
+    let req = SizeRequest::new("601111", 10.0);
+    // 总资产约 99970，目标 5000 股，已持有 3000 股
+    assert_eq!(FixedFraction { fraction: 0.5 }.order_shares(&req, &account), 1900);
+    // 满仓目标受可用资金和手续费限制
+    let all = FixedFraction { fraction: 1.0 }.order_shares(&req, &account);
+    assert_eq!(all, 6900);
+    assert!(all as f64 * 10.0 * 1.001 <= account.available_balance);
+}
+
+#[test]
+fn fractional_kelly() {
+    // 胜率 60%，盈亏比 2：f = 0.6 - 0.4 / 2 = 0.4，半凯利 0.2
+    let trips: Vec<RoundTrip> = [200.0, 200.0, 200.0, -100.0, -100.0].map(trip).to_vec();
+    let kelly = Kelly::from_trades(&trips, 0.5).unwrap();
+    assert!((kelly.optimal_fraction() - 0.2).abs() < 1e-12);
+    let account = new_account(100_000.0);
+    assert_eq!(kelly.target_shares(&SizeRequest::new("601111", 10.0), &account), 2000);
+
+    // 负期望不下单
+    let losing = Kelly {
+        win_rate: 0.3,
+        win_loss_ratio: 1.0,
+        fraction: 1.0,
+    };
+    assert_eq!(losing.optimal_fraction(), 0.0);
+    assert!(Kelly::from_trades(&[trip(100.0)], 0.5).is_none());
+}
@@ -0,0 +1,89 @@
+use backtest::engine;
+use backtest::metrics::Metric;
+use backtest::model::KLine;
+use backtest::optimize::walk_forward::{walk_forward, WalkForwardConfig};
+use backtest::optimize::{ParamRange, ParamSpace};
+use backtest::strategy::k_strategy::{KStrategy, KStrategyParams};
+
+/// 在 6~8 元之间来回震荡的日线
+fn oscillating_bars(n: usize) -> Vec<KLine> {
+    (0..n)
+        .map(|i| {
+            let close = 7.0 + (i as f64 / 8.0).sin();
+            KLine {
+                time: 1_600_000_000 + i as i64 * 86400,
+                open: close,
+                high: close + 0.05,
+                low: close - 0.05,
+                close,
+                volume: 100_000,
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn param_space_grid() {
+    let space = ParamSpace::new(vec![
+        ParamRange::new("add_pos_drawdown_pct", 0.02, 0.10, 0.02),
+        ParamRange::new("init_stop_profit", 0.1, 0.3, 0.1),
+    ]);
+    assert_eq!(space.ranges[0].count(), 5);
+    assert_eq!(space.grid_size(), 15);
+    assert_eq!(space.grid().len(), 15);
+}
+
+#[test]
+fn walk_forward_stitches_out_of_sample() {
+    let bars = oscillating_bars(300);
+    let space = ParamSpace::new(vec![
+        ParamRange::new("add_pos_drawdown_pct", 0.02, 0.06, 0.02),
+        ParamRange::new("init_stop_profit", 0.1, 0.3, 0.1),
+    ]);
+    let config = WalkForwardConfig {
+        in_sample: 100,
+        out_of_sample: 50,
+        anchored: false,
+        metric: Metric::TotalReturn,
+    };
+    let base = KStrategyParams {
+        buy_price_low: 6.0,
+        buy_price_high: 7.5,
+        init_base_volume: 1000,
+        add_pos_drawdown_pct: 0.02,
+        init_stop_profit: 0.1,
+        liquidation_price: 9.0,
+    };
+
+    let result = walk_forward(&bars, &space, &config, 1_000_000.0, |set, bars, cash| {
+        let mut strategy = KStrategy::from_params(&base.with(set));
+        Some(engine::run(&mut strategy, bars, "601111", cash))
+    });
+
+    assert_eq!(result.windows.len(), 4);
+    // 样本外窗口首尾相接
+    assert_eq!(result.equity.len(), 200);
+    assert_eq!(result.equity[0].0, bars[100].time);
+    assert_eq!(result.stability.len(), 2);
+    for (i, w) in result.windows.iter().enumerate() {
+        assert!(w.params.contains_key("add_pos_drawdown_pct"));
+        assert_eq!(w.out_of_sample.0, bars[100 + i * 50].time);
+        assert_eq!(w.in_sample.0, bars[i * 50].time);
+    }
+
+    // 拼接后的绩效按整条样本外资金曲线计算
+    let last = result.equity.last().unwrap().1;
+    assert_eq!(result.metrics.final_balance, last);
+    assert!((result.metrics.total_return - (last / 1_000_000.0 - 1.0)).abs() < 1e-12);
+    let trades: usize = result.windows.iter().map(|w| w.out_of_sample_metrics.trade_count).sum();
+    assert_eq!(result.metrics.trade_count, trades);
+
+    for s in &result.stability {
+        let values: Vec<f64> = result.windows.iter().map(|w| w.params[&s.name]).collect();
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        assert!((s.mean - mean).abs() < 1e-12, "{}", s.name);
+        assert!(s.min <= s.mean && s.mean <= s.max);
+        assert!(s.std_dev >= 0.0);
+        assert_eq!(s.changes, values.windows(2).filter(|w| w[0] != w[1]).count());
+    }
+}